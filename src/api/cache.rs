@@ -0,0 +1,56 @@
+/// On-disk cache of API responses.
+///
+/// Search results and asset lists are stored under `~/.cache/lutrisartfetcher/api/`, one
+/// file per request URL (named by its SHA-256), and reused until they are older than
+/// `cache_ttl_hours`. Re-runs over an unchanged library then cost no API requests for
+/// lookups. The cache is best-effort: unreadable or unwritable entries count as misses.
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+/// Cached response bodies keyed by request URL.
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl ResponseCache {
+    pub fn new(dir: &Path, ttl: Duration) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            ttl,
+        }
+    }
+
+    /// The cached body for `url`, unless it is missing or expired.
+    pub fn get(&self, url: &str) -> Option<Vec<u8>> {
+        let path = self.entry_path(url);
+        let age = std::fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+        if age > self.ttl {
+            return None;
+        }
+        std::fs::read(path).ok()
+    }
+
+    /// Store the body of a successful response for `url`.
+    pub fn put(&self, url: &str, body: &[u8]) {
+        let path = self.entry_path(url);
+        let tmp = path.with_extension("tmp");
+        let _ = std::fs::create_dir_all(&self.dir)
+            .and_then(|()| std::fs::write(&tmp, body))
+            .and_then(|()| std::fs::rename(&tmp, &path));
+    }
+
+    fn entry_path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::digest(url.as_bytes());
+        let mut name = String::with_capacity(digest.len() * 2 + 5);
+        for byte in digest {
+            let _ = write!(name, "{byte:02x}");
+        }
+        name.push_str(".json");
+        self.dir.join(name)
+    }
+}
+
@@ -0,0 +1,166 @@
+/// HTTP traffic capture and replay for bug reports.
+///
+/// `--capture <dir>` writes every response the client receives to `dir`: an index
+/// (`capture.jsonl`) with URL, status, and content type, plus one `NNNN.body` file per
+/// response. Request headers — including the `Authorization` bearer token — are never
+/// recorded. `--replay <dir>` serves those responses back instead of touching the network.
+use std::collections::{HashMap, VecDeque};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use color_eyre::eyre::{Context, Result, eyre};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+use super::client::{HttpResponse, Validators};
+
+/// Name of the index file inside a capture directory.
+const INDEX_FILE: &str = "capture.jsonl";
+
+/// One recorded exchange, as stored in the index.
+#[derive(Debug, Serialize, Deserialize)]
+struct CaptureEntry {
+    url: String,
+    status: u16,
+    content_type: Option<String>,
+    /// Body file name, relative to the capture directory.
+    body_file: String,
+}
+
+/// Appends responses to a capture directory.
+pub struct Capture {
+    dir: PathBuf,
+    state: Mutex<CaptureState>,
+    /// Responses that couldn't be recorded, for [`Self::take_warnings`].
+    warnings: Mutex<Vec<String>>,
+}
+
+struct CaptureState {
+    index: File,
+    next_seq: u32,
+}
+
+impl Capture {
+    /// Create (or append to) a capture directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or index file cannot be created.
+    pub fn new(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)
+            .wrap_err_with(|| format!("Failed to create capture directory {}", dir.display()))?;
+        let index_path = dir.join(INDEX_FILE);
+        let next_seq = std::fs::read_to_string(&index_path)
+            .map_or(0, |s| u32::try_from(s.lines().count()).unwrap_or(u32::MAX));
+        let index = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&index_path)
+            .wrap_err_with(|| format!("Failed to open {}", index_path.display()))?;
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            state: Mutex::new(CaptureState { index, next_seq }),
+            warnings: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Record one response. Failures are kept as warnings but never abort the run.
+    pub fn record(&self, url: &str, response: &HttpResponse) {
+        if let Err(e) = self.try_record(url, response) {
+            let message = format!("Failed to record capture for {url}: {e}");
+            self.warnings.lock().unwrap_or_else(PoisonError::into_inner).push(message);
+        }
+    }
+
+    /// The warnings collected since the last call.
+    pub fn take_warnings(&self) -> Vec<String> {
+        std::mem::take(&mut *self.warnings.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    fn try_record(&self, url: &str, response: &HttpResponse) -> Result<()> {
+        let mut state = self
+            .state
+            .lock()
+            .map_err(|_| eyre!("capture state poisoned"))?;
+
+        let body_file = format!("{:04}.body", state.next_seq);
+        std::fs::write(self.dir.join(&body_file), &response.body)?;
+
+        let entry = CaptureEntry {
+            url: url.to_owned(),
+            status: response.status.as_u16(),
+            content_type: response.content_type.clone(),
+            body_file,
+        };
+        let line = serde_json::to_string(&entry)?;
+        writeln!(state.index, "{line}")?;
+        state.next_seq += 1;
+        Ok(())
+    }
+}
+
+/// Serves recorded responses by URL.
+///
+/// Repeated requests for the same URL get the recorded responses in order; once only
+/// one remains it is returned for every further request.
+pub struct Replay {
+    responses: Mutex<HashMap<String, VecDeque<HttpResponse>>>,
+}
+
+impl Replay {
+    /// Load a capture directory written by [`Capture`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the index or any body file is missing or malformed.
+    pub fn load(dir: &Path) -> Result<Self> {
+        let index_path = dir.join(INDEX_FILE);
+        let index = std::fs::read_to_string(&index_path)
+            .wrap_err_with(|| format!("Failed to read capture index {}", index_path.display()))?;
+
+        let mut responses: HashMap<String, VecDeque<HttpResponse>> = HashMap::new();
+        for (lineno, line) in index.lines().enumerate().filter(|(_, l)| !l.trim().is_empty()) {
+            let entry: CaptureEntry = serde_json::from_str(line)
+                .wrap_err_with(|| format!("Malformed capture entry on line {}", lineno + 1))?;
+            let body = std::fs::read(dir.join(&entry.body_file))
+                .wrap_err_with(|| format!("Missing capture body {}", entry.body_file))?;
+            let status = StatusCode::from_u16(entry.status)
+                .wrap_err_with(|| format!("Invalid status on line {}", lineno + 1))?;
+            responses.entry(entry.url).or_default().push_back(HttpResponse {
+                status,
+                content_type: entry.content_type,
+                retry_after: None,
+                validators: Validators::default(),
+                body,
+            });
+        }
+
+        Ok(Self {
+            responses: Mutex::new(responses),
+        })
+    }
+
+    /// The next recorded response for `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the capture holds no response for this URL.
+    pub fn response(&self, url: &str) -> Result<HttpResponse> {
+        let mut responses = self
+            .responses
+            .lock()
+            .map_err(|_| eyre!("replay state poisoned"))?;
+        let queue = responses
+            .get_mut(url)
+            .ok_or_else(|| eyre!("No captured response for {url}"))?;
+        let response = if queue.len() > 1 {
+            queue.pop_front()
+        } else {
+            queue.front().cloned()
+        };
+        response.ok_or_else(|| eyre!("No captured response for {url}"))
+    }
+}
@@ -2,191 +2,1190 @@
 ///
 /// Thin async wrapper around `reqwest` for searching games, fetching asset lists,
 /// and downloading images. Includes configurable request delay to respect rate limits.
-use std::time::Duration;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::collections::hash_map::Entry;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, PoisonError, RwLock};
+use std::time::{Duration, Instant};
 
 use color_eyre::eyre::{Context, Result, eyre};
-use reqwest::Client;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client, Proxy, StatusCode, Url};
+use serde::de::DeserializeOwned;
+use tokio::io::AsyncWriteExt;
 
-use super::models::{ApiResponse, AssetType, ImageAsset, SearchResult};
+use super::cache::ResponseCache;
+use super::capture::{Capture, Replay};
+use super::models::{ApiObject, ApiResponse, AssetType, CollectionAsset, GameInfo, ImageAsset, SearchResult};
+use super::stats;
+use super::throttle::Throttle;
+use crate::config::{self, Config};
 
-const BASE_URL: &str = "https://www.steamgriddb.com/api/v2";
+/// Default `SteamGridDB` API root, used unless `api_base_url` is set in config.
+pub const DEFAULT_BASE_URL: &str = "https://www.steamgriddb.com/api/v2";
 
 /// Async client for the `SteamGridDB` REST API.
 pub struct SteamGridDbClient {
-    /// Authenticated client for API endpoints.
+    /// Client for API endpoints; the bearer token is attached per request.
     client: Client,
+    /// Current API key — replaceable mid-run when the old one is revoked.
+    api_key: RwLock<String>,
     /// Bare client for CDN image downloads (no auth headers).
     cdn_client: Client,
-    request_delay: Duration,
+    /// Paces API requests from the configured delay and the server's rate-limit headers.
+    throttle: Throttle,
+    /// Retries after the first attempt for transient failures.
+    max_retries: u32,
+    /// First backoff delay; doubles with each retry.
+    retry_base_delay: Duration,
+    /// API root without a trailing slash.
+    base_url: String,
+    /// Replacement scheme + host for image URLs, if a mirror is configured.
+    cdn_base: Option<Url>,
+    /// Records traffic to disk when `--capture` is active.
+    capture: Option<Capture>,
+    /// Serves responses from a capture instead of the network when `--replay` is active.
+    replay: Option<Replay>,
+    /// Reuses recent search and asset list responses across runs.
+    cache: Option<ResponseCache>,
+    /// Collection whose art is preferred, if one is configured.
+    collection_id: Option<u64>,
+    /// Asset IDs that are never picked.
+    blocked_assets: BTreeSet<u64>,
+    /// Game IDs whose art is never used.
+    excluded_game_ids: BTreeSet<u64>,
+    /// Game IDs to use for Lutris slugs instead of looking them up.
+    overrides: BTreeMap<String, u64>,
+    /// The collection's assets per type, fetched on first use.
+    collection: tokio::sync::Mutex<HashMap<AssetType, Vec<CollectionAsset>>>,
+    /// Problems that didn't fail a request, for [`Self::take_warnings`].
+    warnings: Mutex<Vec<String>>,
+}
+
+/// Maximum number of assets the API returns per page.
+pub const PAGE_LIMIT: u32 = 50;
+
+/// Search term for [`SteamGridDbClient::validate_key`].
+const KEY_CHECK_TERM: &str = "portal";
+
+/// Upper bound for a single backoff or `Retry-After` wait.
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Bytes that compressed API responses spared us this run, across all clients.
+static BYTES_SAVED: AtomicU64 = AtomicU64::new(0);
+
+/// How many bytes compression has saved on API responses so far.
+pub fn bytes_saved() -> u64 {
+    BYTES_SAVED.load(Ordering::Relaxed)
+}
+
+/// Start counting [`bytes_saved`] afresh, for another run in the same session.
+pub fn reset_bytes_saved() {
+    BYTES_SAVED.store(0, Ordering::Relaxed);
+}
+
+/// Which game an asset list request is for.
+#[derive(Debug, Clone, Copy)]
+pub enum AssetLookup<'a> {
+    /// A `SteamGridDB` game ID.
+    Game(u64),
+    /// A store's own ID, e.g. `platform = "steam"` with the Steam app ID.
+    Platform { platform: &'a str, id: &'a str },
+}
+
+/// Server-side filters for an asset list request.
+#[derive(Debug, Clone, Copy, Default)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct AssetQuery<'a> {
+    /// Only return images of this size, e.g. `"600x900"`.
+    pub dimensions: Option<&'a str>,
+    /// Include animated images as well as static ones.
+    pub animated: bool,
+    /// Leave out NSFW, humor, and epilepsy-tagged images.
+    pub exclude_nsfw: bool,
+    pub exclude_humor: bool,
+    pub exclude_epilepsy: bool,
+    /// Further parameters passed through as-is, from `extra_query_params`.
+    pub extra: &'a [(String, String)],
+}
+
+/// One page of an asset list.
+#[derive(Debug)]
+pub struct AssetPage<T = ImageAsset> {
+    pub assets: Vec<T>,
+    /// Whether a further page may hold more assets.
+    pub has_more: bool,
+}
+
+impl<T> Default for AssetPage<T> {
+    fn default() -> Self {
+        Self {
+            assets: Vec::new(),
+            has_more: false,
+        }
+    }
+}
+
+impl<T> AssetPage<T> {
+    fn from_response(body: ApiResponse<T>, page: u32) -> Self {
+        // Prefer the reported total; otherwise a full page suggests another one follows
+        let has_more = match (body.total, body.limit) {
+            (Some(total), Some(limit)) => u64::from(page + 1) * u64::from(limit) < u64::from(total),
+            _ => u32::try_from(body.data.len()).is_ok_and(|n| n >= PAGE_LIMIT),
+        };
+        Self {
+            assets: body.data,
+            has_more,
+        }
+    }
+}
+
+/// Which of the two HTTP clients a request goes through.
+#[derive(Debug, Clone, Copy)]
+enum Endpoint {
+    /// Authenticated API calls.
+    Api,
+    /// Unauthenticated image downloads.
+    Cdn,
+}
+
+/// A fully buffered HTTP response.
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub status: StatusCode,
+    pub content_type: Option<String>,
+    /// Parsed `Retry-After` header (seconds form only).
+    pub retry_after: Option<Duration>,
+    pub validators: Validators,
+    pub body: Vec<u8>,
+}
+
+/// `ETag` / `Last-Modified` of a response, sent back later to ask whether it changed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+/// Result of a conditional image download.
+#[derive(Debug)]
+pub enum ImageDownload {
+    /// The image changed (or nothing was known about it) and `size` bytes of it were
+    /// written to the [`FileSink`].
+    Modified { size: u64, validators: Validators },
+    /// The server answered 304 — the copy we have is current.
+    NotModified,
+}
+
+/// Whether a `Content-Type` is a web page or API error rather than an image — what a
+/// misbehaving CDN sends, with a 200, in place of the file.
+pub fn is_error_page_type(content_type: &str) -> bool {
+    let mime = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    mime.starts_with("text/")
+        || matches!(mime.as_str(), "application/json" | "application/xml" | "application/xhtml+xml")
+}
+
+/// Least time between two progress callbacks while streaming a download.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Where a streamed image download is written, and who hears about its progress.
+pub struct FileSink<'a> {
+    pub path: &'a Path,
+    /// Called now and then with the bytes received so far and the expected total.
+    pub progress: &'a (dyn Fn(u64, Option<u64>) + Send + Sync),
+}
+
+impl FileSink<'_> {
+    /// `Range` and `If-Range` headers resuming what an interrupted attempt left in the
+    /// file — empty when there is nothing to resume, or no validator to check that the
+    /// image hasn't changed since.
+    pub fn resume_headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let partial = std::fs::metadata(self.path).map_or(0, |m| m.len());
+        let validator = std::fs::read_to_string(self.validator_path()).unwrap_or_default();
+        let if_range = HeaderValue::from_str(validator.trim()).ok().filter(|v| !v.is_empty());
+        let range = HeaderValue::from_str(&format!("bytes={partial}-")).ok();
+        if let (true, Some(range), Some(if_range)) = (partial > 0, range, if_range) {
+            headers.insert(header::RANGE, range);
+            headers.insert(header::IF_RANGE, if_range);
+        }
+        headers
+    }
+
+    /// Drop a partial file the server won't resume (HTTP 416).
+    pub async fn discard_partial(&self) {
+        let _ = tokio::fs::remove_file(self.path).await;
+        let _ = tokio::fs::remove_file(self.validator_path()).await;
+    }
+
+    /// Where the validator of the image being written is kept while the file is
+    /// partial, for [`Self::resume_headers`].
+    fn validator_path(&self) -> std::path::PathBuf {
+        validator_path(self.path)
+    }
+
+    /// Stream a response body into the file, returning the file's full size.
+    ///
+    /// A `206 Partial Content` answer to [`Self::range`] is appended to the partial file;
+    /// anything else replaces it. The result must reach the size the server announced —
+    /// a cut-off body is an error, and the partial file is kept for the next attempt.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the body can't be read, is incomplete, or the file can't be written.
+    pub async fn write_response(&self, mut resp: reqwest::Response) -> Result<u64> {
+        let (mut file, mut received, total) = if resp.status() == StatusCode::PARTIAL_CONTENT {
+            let (start, total) = resp
+                .headers()
+                .get(header::CONTENT_RANGE)
+                .and_then(|v| v.to_str().ok())
+                .and_then(parse_content_range)
+                .ok_or_else(|| eyre!("Partial response without a usable Content-Range"))?;
+            let file = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(self.path)
+                .await
+                .wrap_err("open failed")?;
+            let partial = file.metadata().await.wrap_err("open failed")?.len();
+            if start != partial {
+                return Err(eyre!("Server resumed at byte {start}, but {partial} bytes are on disk"));
+            }
+            (file, start, total)
+        } else {
+            let total = resp.content_length();
+            let file = tokio::fs::File::create(self.path).await.wrap_err("create failed")?;
+            // Only a strong ETag or a date may be sent back in If-Range
+            let validator = [header::ETAG, header::LAST_MODIFIED].into_iter().find_map(|name| {
+                let value = resp.headers().get(name)?.to_str().ok()?;
+                (!value.starts_with("W/")).then_some(value)
+            });
+            match validator {
+                Some(value) => tokio::fs::write(self.validator_path(), value).await.wrap_err("write failed")?,
+                None => {
+                    let _ = tokio::fs::remove_file(self.validator_path()).await;
+                }
+            }
+            (file, 0, total)
+        };
+        let mut reported = Instant::now();
+        while let Some(chunk) = resp.chunk().await.wrap_err("Failed to read image body")? {
+            file.write_all(&chunk).await.wrap_err("write failed")?;
+            received += chunk.len() as u64;
+            if reported.elapsed() >= PROGRESS_INTERVAL {
+                (self.progress)(received, total);
+                reported = Instant::now();
+            }
+        }
+        file.flush().await.wrap_err("write failed")?;
+        (self.progress)(received, total);
+        if let Some(total) = total.filter(|&t| t != received) {
+            return Err(eyre!("Download cut off at {received} of {total} bytes"));
+        }
+        let _ = tokio::fs::remove_file(self.validator_path()).await;
+        Ok(received)
+    }
+
+    /// Write an already buffered body, e.g. from a replay.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub async fn write_bytes(&self, bytes: &[u8]) -> Result<u64> {
+        tokio::fs::write(self.path, bytes).await.wrap_err("write failed")?;
+        let _ = tokio::fs::remove_file(self.validator_path()).await;
+        let size = bytes.len() as u64;
+        (self.progress)(size, Some(size));
+        Ok(size)
+    }
+}
+
+impl HttpResponse {
+    /// Deserialize the body as JSON.
+    fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.body).map_err(Into::into)
+    }
 }
 
 impl SteamGridDbClient {
-    /// Create a new client with the given API key and inter-request delay.
+    /// Create a new client with the given API key, using connection settings from `config`.
     ///
     /// # Errors
     ///
-    /// Returns an error if the HTTP client cannot be built.
-    pub fn new(api_key: &str, delay_ms: u64) -> Result<Self> {
-        let client = Client::builder()
-            .default_headers({
-                let mut headers = reqwest::header::HeaderMap::new();
-                let val = reqwest::header::HeaderValue::from_str(&format!("Bearer {api_key}"))
-                    .wrap_err("Invalid API key format")?;
-                headers.insert(reqwest::header::AUTHORIZATION, val);
-                headers
-            })
-            .timeout(Duration::from_secs(30))
-            .build()
+    /// Returns an error if the HTTP client cannot be built or a configured endpoint is invalid.
+    pub fn new(api_key: &str, config: &Config) -> Result<Self> {
+        let base_url = parse_endpoint(&config.api_base_url)
+            .wrap_err("Invalid api_base_url in config")?
+            .as_str()
+            .trim_end_matches('/')
+            .to_owned();
+        let cdn_base = config
+            .cdn_base_url
+            .as_deref()
+            .map(parse_endpoint)
+            .transpose()
+            .wrap_err("Invalid cdn_base_url in config")?;
+
+        check_key_format(api_key)?;
+
+        let client = http_client(config, config.http_timeout_secs)
             .wrap_err("Failed to build HTTP client")?;
 
-        let cdn_client = Client::builder()
-            .timeout(Duration::from_secs(60))
-            .build()
+        let cdn_client = http_client(config, config.http_timeout_secs.saturating_mul(2))
             .wrap_err("Failed to build CDN HTTP client")?;
 
+        // Capture and replay need to see every request, so they bypass the cache
+        let cache = (config.cache_ttl_hours > 0
+            && config.capture_dir.is_none()
+            && config.replay_dir.is_none())
+        .then(|| {
+            let ttl = Duration::from_secs(config.cache_ttl_hours * 3600);
+            config::cache_dir().map(|dir| ResponseCache::new(&dir.join("api"), ttl))
+        })
+        .transpose()?;
+
         Ok(Self {
             client,
             cdn_client,
-            request_delay: Duration::from_millis(delay_ms),
+            api_key: RwLock::new(api_key.to_owned()),
+            throttle: Throttle::new(Duration::from_millis(config.request_delay_ms), config.request_burst),
+            max_retries: config.max_retries,
+            retry_base_delay: Duration::from_millis(config.retry_base_delay_ms),
+            base_url,
+            cdn_base,
+            capture: config.capture_dir.as_deref().map(Capture::new).transpose()?,
+            replay: config.replay_dir.as_deref().map(Replay::load).transpose()?,
+            cache,
+            collection_id: config.collection_id,
+            blocked_assets: config.blocked_assets.clone(),
+            excluded_game_ids: config.excluded_game_ids.clone(),
+            overrides: config.overrides.clone(),
+            collection: tokio::sync::Mutex::new(HashMap::new()),
+            warnings: Mutex::new(Vec::new()),
         })
     }
 
-    /// Validate the API key by hitting a known endpoint.
+    /// Whether the game's art is never used, per `excluded_game_ids`.
+    pub fn excludes_game(&self, id: u64) -> bool {
+        self.excluded_game_ids.contains(&id)
+    }
+
+    /// The game ID configured for a Lutris slug in `[overrides]`, if any.
+    pub fn id_override(&self, slug: &str) -> Option<u64> {
+        self.overrides.get(slug).copied()
+    }
+
+    /// Drop the assets listed in `blocked_assets`.
+    pub fn without_blocked(&self, mut assets: Vec<ImageAsset>) -> Vec<ImageAsset> {
+        assets.retain(|a| !self.blocked_assets.contains(&a.id));
+        assets
+    }
+
+    /// Replace the API key used for all further requests.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key can't be sent as an HTTP header.
+    pub fn set_api_key(&self, api_key: &str) -> Result<()> {
+        check_key_format(api_key)?;
+        let mut key = self.api_key.write().unwrap_or_else(PoisonError::into_inner);
+        api_key.clone_into(&mut key);
+        Ok(())
+    }
+
+    /// Problems met along the way that didn't fail a request, e.g. a collection too
+    /// large to list in full or a response that couldn't be captured. Each is returned
+    /// once.
+    pub fn take_warnings(&self) -> Vec<String> {
+        let mut warnings = std::mem::take(&mut *self.warnings.lock().unwrap_or_else(PoisonError::into_inner));
+        warnings.extend(self.capture.as_ref().map(Capture::take_warnings).unwrap_or_default());
+        warnings
+    }
+
+    /// Check the API key with a single cheap authenticated request.
     ///
-    /// Returns `true` if the server responds with 200.
+    /// Returns `false` only when the server rejects the key (401/403). Not reaching the
+    /// server, or any other answer, says nothing about the key and is an error instead.
     pub async fn validate_key(&self) -> Result<bool> {
-        let url = format!("{BASE_URL}/grids/game/1?dimensions=600x900");
-        let resp = self.client.get(&url).send().await.wrap_err("Key validation request failed")?;
-        Ok(resp.status().is_success())
+        // Autocomplete needs auth but no particular game to exist, and its reply is tiny
+        let url = format!("{}/search/autocomplete/{KEY_CHECK_TERM}", self.base_url);
+        match self.fetch_once(Endpoint::Api, &url, &HeaderMap::new(), None).await {
+            Ok(resp) if resp.status.is_success() => Ok(true),
+            Ok(resp) if resp.status == StatusCode::FORBIDDEN => Ok(false),
+            Ok(resp) => Err(eyre!(
+                "SteamGridDB answered with status {} — the key couldn't be checked",
+                resp.status
+            )),
+            Err(e) if is_unauthorized(&e) => Ok(false),
+            Err(e) => Err(e.wrap_err("Couldn't reach SteamGridDB to check the key")),
+        }
     }
 
     /// Search for a game by name. Slugs should be pre-converted (replace `-` with space).
     pub async fn search(&self, term: &str) -> Result<Vec<SearchResult>> {
-        let url = format!("{BASE_URL}/search/autocomplete/{term}");
-        self.delay().await;
+        let url = format!("{}/search/autocomplete/{term}", self.base_url);
 
         let resp = self
-            .client
-            .get(&url)
-            .send()
+            .fetch_cached(&url)
             .await
             .wrap_err_with(|| format!("Search request failed for '{term}'"))?;
 
-        if !resp.status().is_success() {
-            return Err(eyre!("Search failed with status {}", resp.status()));
+        if !resp.status.is_success() {
+            return Err(StatusError::new(resp.status, format!("Search failed with status {}", resp.status)).into());
         }
 
         let body: ApiResponse<SearchResult> = resp
             .json()
-            .await
             .wrap_err("Failed to parse search response")?;
 
         Ok(body.data)
     }
 
-    /// Fetch asset images for a game by its `SteamGridDB` ID.
+    /// Fetch a game's details by its `SteamGridDB` ID.
+    pub async fn get_game(&self, id: u64) -> Result<GameInfo> {
+        let url = format!("{}/games/id/{id}", self.base_url);
+        self.delay().await;
+
+        let resp = self
+            .fetch(Endpoint::Api, &url)
+            .await
+            .wrap_err_with(|| format!("Game request failed for {id}"))?;
+
+        if !resp.status.is_success() {
+            let message = format!("Game request failed with status {} for {id}", resp.status);
+            return Err(StatusError::new(resp.status, message).into());
+        }
+
+        let body: ApiObject<GameInfo> = resp
+            .json()
+            .wrap_err("Failed to parse game response")?;
+
+        Ok(body.data)
+    }
+
+    /// Look up the `SteamGridDB` game for a store's own ID, e.g. a Steam app ID. Returns
+    /// `None` if the store ID is unknown to `SteamGridDB`.
+    ///
+    /// Exact, unlike a name search, which can land on DLC or remasters.
+    pub async fn get_game_by_platform(&self, platform: &str, id: &str) -> Result<Option<GameInfo>> {
+        let url = format!("{}/games/{platform}/{id}", self.base_url);
+        self.delay().await;
+
+        let resp = self
+            .fetch(Endpoint::Api, &url)
+            .await
+            .wrap_err_with(|| format!("Game lookup failed for {platform}/{id}"))?;
+
+        if resp.status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status.is_success() {
+            let message = format!("Game lookup failed with status {} for {platform}/{id}", resp.status);
+            return Err(StatusError::new(resp.status, message).into());
+        }
+
+        let body: ApiObject<GameInfo> = resp
+            .json()
+            .wrap_err("Failed to parse game lookup response")?;
+
+        Ok(Some(body.data))
+    }
+
+    /// Fetch one page of asset images for a game by its `SteamGridDB` ID.
     pub async fn get_assets(
         &self,
         asset_type: AssetType,
         game_id: u64,
-        dimensions: Option<&str>,
-    ) -> Result<Vec<ImageAsset>> {
-        let mut url = format!("{BASE_URL}/{}/game/{game_id}", asset_type.api_path());
-        if let Some(dims) = dimensions {
-            use std::fmt::Write;
-            let _ = write!(url, "?dimensions={dims}");
-        }
-        self.delay().await;
+        query: &AssetQuery<'_>,
+        page: u32,
+    ) -> Result<AssetPage> {
+        let url = self.asset_url(&format!("{}/game/{game_id}", asset_type.api_path()), query, page);
 
         let resp = self
-            .client
-            .get(&url)
-            .send()
+            .fetch_cached(&url)
             .await
             .wrap_err_with(|| format!("Asset request failed for game {game_id}"))?;
 
-        if !resp.status().is_success() {
-            return Err(eyre!(
-                "Asset fetch failed with status {} for game {game_id}",
-                resp.status()
-            ));
+        if !resp.status.is_success() {
+            let message = format!("Asset fetch failed with status {} for game {game_id}", resp.status);
+            return Err(StatusError::new(resp.status, message).into());
         }
 
         let body: ApiResponse<ImageAsset> = resp
             .json()
-            .await
             .wrap_err("Failed to parse asset response")?;
 
-        Ok(body.data)
+        Ok(AssetPage::from_response(body, page))
     }
 
-    /// Fetch assets using a platform-specific ID (e.g. Steam app ID) for a more
-    /// accurate match than text search.
+    /// Fetch one page of assets using a platform-specific ID (e.g. Steam app ID) for a
+    /// more accurate match than text search.
     pub async fn get_assets_by_platform(
         &self,
         asset_type: AssetType,
         platform: &str,
         platform_id: &str,
-        dimensions: Option<&str>,
-    ) -> Result<Vec<ImageAsset>> {
-        let mut url = format!(
-            "{BASE_URL}/{}/{platform}/{platform_id}",
-            asset_type.api_path()
+        query: &AssetQuery<'_>,
+        page: u32,
+    ) -> Result<AssetPage> {
+        let url = self.asset_url(
+            &format!("{}/{platform}/{platform_id}", asset_type.api_path()),
+            query,
+            page,
         );
-        if let Some(dims) = dimensions {
-            use std::fmt::Write;
-            let _ = write!(url, "?dimensions={dims}");
-        }
-        self.delay().await;
 
         let resp = self
-            .client
-            .get(&url)
-            .send()
+            .fetch_cached(&url)
             .await
             .wrap_err_with(|| {
                 format!("Platform asset request failed for {platform}/{platform_id}")
             })?;
 
-        if !resp.status().is_success() {
-            // Platform lookup can 404 for non-Steam games; not an error per se
-            return Ok(Vec::new());
+        if !resp.status.is_success() {
+            // Platform lookup can 404 for store IDs SteamGridDB lacks; not an error per se
+            return Ok(AssetPage::default());
         }
 
         let body: ApiResponse<ImageAsset> = resp
             .json()
-            .await
             .wrap_err("Failed to parse platform asset response")?;
 
-        Ok(body.data)
+        Ok(AssetPage::from_response(body, page))
+    }
+
+    /// Fetch up to `max_pages` pages of assets and concatenate them.
+    ///
+    /// Stops early once a page reports nothing further, so games with few assets still
+    /// cost a single request.
+    pub async fn get_assets_paged(
+        &self,
+        asset_type: AssetType,
+        lookup: AssetLookup<'_>,
+        query: &AssetQuery<'_>,
+        max_pages: u32,
+    ) -> Result<Vec<ImageAsset>> {
+        let mut assets = Vec::new();
+        for page in 0..max_pages.max(1) {
+            let result = match lookup {
+                AssetLookup::Game(id) => self.get_assets(asset_type, id, query, page).await?,
+                AssetLookup::Platform { platform, id } => {
+                    self.get_assets_by_platform(asset_type, platform, id, query, page)
+                        .await?
+                }
+            };
+            assets.extend(result.assets);
+            if !result.has_more {
+                break;
+            }
+        }
+        Ok(assets)
     }
 
-    /// Download raw image bytes from a CDN URL.
+    /// The configured collection's art of one type for a game; empty without a
+    /// collection or when it has nothing for the game.
+    ///
+    /// The whole collection is listed once per asset type, up to `max_pages` pages, and
+    /// kept for the rest of the run. `query`'s filters apply, but not its dimensions:
+    /// curated art is used whatever its size.
+    pub async fn collection_assets(
+        &self,
+        game_id: u64,
+        asset_type: AssetType,
+        query: &AssetQuery<'_>,
+        max_pages: u32,
+    ) -> Result<Vec<ImageAsset>> {
+        let Some(collection) = self.collection_id else {
+            return Ok(Vec::new());
+        };
+        let mut cached = self.collection.lock().await;
+        let listed = match cached.entry(asset_type) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let query = AssetQuery { dimensions: None, ..*query };
+                entry.insert(self.get_collection_paged(collection, asset_type, &query, max_pages).await?)
+            }
+        };
+        Ok(listed
+            .iter()
+            .filter(|c| c.game_id == game_id)
+            .map(|c| c.asset.clone())
+            .collect())
+    }
+
+    /// Fetch up to `max_pages` pages of a collection's assets of one type, with a
+    /// warning when there are more.
+    async fn get_collection_paged(
+        &self,
+        collection: u64,
+        asset_type: AssetType,
+        query: &AssetQuery<'_>,
+        max_pages: u32,
+    ) -> Result<Vec<CollectionAsset>> {
+        let path = format!("collections/{collection}/{}", asset_type.api_path());
+        let mut assets = Vec::new();
+        let pages = max_pages.max(1);
+        for page in 0..pages {
+            let url = self.asset_url(&path, query, page);
+            let resp = self
+                .fetch_cached(&url)
+                .await
+                .wrap_err_with(|| format!("Request failed for collection {collection}"))?;
+            if !resp.status.is_success() {
+                let message = format!("Collection fetch failed with status {} for collection {collection}", resp.status);
+                return Err(StatusError::new(resp.status, message).into());
+            }
+            let body: ApiResponse<CollectionAsset> = resp
+                .json()
+                .wrap_err("Failed to parse collection response")?;
+            let result = AssetPage::from_response(body, page);
+            assets.extend(result.assets);
+            if !result.has_more {
+                break;
+            }
+            if page + 1 == pages {
+                let message = format!(
+                    "Collection {collection} has more {asset_type} art than {pages} pages hold — the rest is left out (raise max_asset_pages)"
+                );
+                self.warnings.lock().unwrap_or_else(PoisonError::into_inner).push(message);
+            }
+        }
+        Ok(assets)
+    }
+
+    /// Build an asset list URL for `path` (relative to the API root) and the given page.
+    fn asset_url(&self, path: &str, query: &AssetQuery<'_>, page: u32) -> String {
+        use std::fmt::Write;
+        let mut url = format!("{}/{path}?page={page}&limit={PAGE_LIMIT}", self.base_url);
+        if let Some(dims) = query.dimensions {
+            let _ = write!(url, "&dimensions={dims}");
+        }
+        if query.animated {
+            url.push_str("&types=static,animated");
+        }
+        // Filtering server-side keeps excluded images from crowding out the first page
+        let excluded = [
+            ("nsfw", query.exclude_nsfw),
+            ("humor", query.exclude_humor),
+            ("epilepsy", query.exclude_epilepsy),
+        ];
+        for (tag, exclude) in excluded {
+            if exclude {
+                let _ = write!(url, "&{tag}=false");
+            }
+        }
+        if !query.extra.is_empty() {
+            if let Ok(mut parsed) = Url::parse(&url) {
+                parsed.query_pairs_mut().extend_pairs(query.extra);
+                url = parsed.into();
+            }
+        }
+        url
+    }
+
+    /// Download an image from a CDN URL into `sink` unless it still matches `known`,
+    /// using `If-None-Match` / `If-Modified-Since`.
     ///
     /// Uses a separate client without auth headers — the CDN rejects Bearer tokens.
-    pub async fn download_image(&self, url: &str) -> Result<Vec<u8>> {
+    pub async fn download_image_if_modified(
+        &self,
+        url: &str,
+        known: &Validators,
+        sink: &FileSink<'_>,
+    ) -> Result<ImageDownload> {
+        let url = self.cdn_url(url);
+        let url: &str = &url;
+        let mut headers = HeaderMap::new();
+        let conditions = [
+            (header::IF_NONE_MATCH, &known.etag),
+            (header::IF_MODIFIED_SINCE, &known.last_modified),
+        ];
+        for (name, value) in conditions {
+            if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+                headers.insert(name, value);
+            }
+        }
         let resp = self
-            .cdn_client
-            .get(url)
-            .send()
+            .fetch_with_headers(Endpoint::Cdn, url, &headers, Some(sink))
             .await
             .wrap_err_with(|| format!("Image download failed for {url}"))?;
 
-        if !resp.status().is_success() {
-            return Err(eyre!("Image download returned status {}", resp.status()));
+        if resp.status == StatusCode::NOT_MODIFIED {
+            return Ok(ImageDownload::NotModified);
+        }
+        if !resp.status.is_success() {
+            return Err(StatusError::new(resp.status, format!("Image download returned status {}", resp.status)).into());
+        }
+        if let Some(kind) = resp.content_type.as_deref().filter(|t| is_error_page_type(t)) {
+            // Nothing worth resuming from
+            sink.discard_partial().await;
+            return Err(eyre!("Image download returned {kind} instead of an image"));
         }
 
-        let bytes = resp
-            .bytes()
-            .await
-            .wrap_err("Failed to read image bytes")?;
-        Ok(bytes.to_vec())
+        let size = tokio::fs::metadata(sink.path).await.wrap_err("Downloaded image is missing")?.len();
+        Ok(ImageDownload::Modified {
+            size,
+            validators: resp.validators,
+        })
     }
 
-    /// Sleep for the configured inter-request delay.
+    /// Perform a paced API request, answering from the response cache when it holds a
+    /// fresh copy. Only successful responses are cached.
+    async fn fetch_cached(&self, url: &str) -> Result<HttpResponse> {
+        if let Some(body) = self.cache.as_ref().and_then(|c| c.get(url)) {
+            stats::record_cache_hit();
+            return Ok(HttpResponse {
+                status: StatusCode::OK,
+                content_type: Some("application/json".to_owned()),
+                retry_after: None,
+                validators: Validators::default(),
+                body,
+            });
+        }
+
+        self.delay().await;
+        let resp = self.fetch(Endpoint::Api, url).await?;
+        if let Some(ref cache) = self.cache {
+            if resp.status.is_success() {
+                cache.put(url, &resp.body);
+            }
+        }
+        Ok(resp)
+    }
+
+    /// Perform a GET request and buffer the response.
+    ///
+    /// Every request goes through here, so capture and replay see all traffic.
+    ///
+    /// Rate limiting (429), server errors (5xx), timeouts, and connection failures are
+    /// retried with jittered exponential backoff; the last response or error is returned
+    /// once retries run out.
+    async fn fetch(&self, endpoint: Endpoint, url: &str) -> Result<HttpResponse> {
+        self.fetch_with_headers(endpoint, url, &HeaderMap::new(), None).await
+    }
+
+    /// [`Self::fetch`] with extra request headers. With a `sink`, a successful body is
+    /// streamed into it instead of being buffered.
+    #[tracing::instrument(name = "request", level = "debug", skip(self, headers, sink))]
+    async fn fetch_with_headers(
+        &self,
+        endpoint: Endpoint,
+        url: &str,
+        headers: &HeaderMap,
+        sink: Option<&FileSink<'_>>,
+    ) -> Result<HttpResponse> {
+        let mut attempt = 0;
+        loop {
+            let result = self.fetch_once(endpoint, url, headers, sink).await;
+            let retry_after = match result {
+                Ok(ref resp) if is_transient_status(resp.status) => resp.retry_after,
+                // The unresumable partial file was dropped; start over straight away
+                Ok(ref resp) if resp.status == StatusCode::RANGE_NOT_SATISFIABLE && sink.is_some() => {
+                    Some(Duration::ZERO)
+                }
+                Err(ref e) if is_transient_error(e) => None,
+                _ => return result,
+            };
+            if attempt >= self.max_retries {
+                return result;
+            }
+            let wait = retry_after.unwrap_or_else(|| self.backoff(attempt));
+            tracing::debug!(attempt, ?wait, "retrying");
+            if matches!(endpoint, Endpoint::Api) {
+                stats::record_retry();
+            }
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    /// Backoff before retry number `attempt + 1`: doubling from the base delay, capped,
+    /// with ±50% jitter so concurrent tasks don't retry in lockstep.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .retry_base_delay
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(MAX_BACKOFF);
+        exp.mul_f64(0.5 + fastrand::f64())
+    }
+
+    /// Perform a single request without retries.
+    async fn fetch_once(
+        &self,
+        endpoint: Endpoint,
+        url: &str,
+        headers: &HeaderMap,
+        sink: Option<&FileSink<'_>>,
+    ) -> Result<HttpResponse> {
+        if let Some(ref replay) = self.replay {
+            let response = replay.response(url)?;
+            if matches!(endpoint, Endpoint::Api) && response.status == StatusCode::UNAUTHORIZED {
+                return Err(Unauthorized.into());
+            }
+            if let Some(sink) = sink.filter(|_| response.status.is_success()) {
+                sink.write_bytes(&response.body).await?;
+            }
+            return Ok(response);
+        }
+
+        let request = match endpoint {
+            Endpoint::Api => {
+                let key = self.api_key.read().unwrap_or_else(PoisonError::into_inner);
+                // JSON compresses well; images are already compressed, so only ask here
+                self.client
+                    .get(url)
+                    .bearer_auth(&*key)
+                    .header(header::ACCEPT_ENCODING, "br, gzip")
+            }
+            Endpoint::Cdn => self.cdn_client.get(url),
+        };
+        let request = request
+            .headers(headers.clone())
+            .headers(sink.map(FileSink::resume_headers).unwrap_or_default());
+        let resp = match request.send().await {
+            Ok(resp) => resp,
+            Err(e) => {
+                tracing::debug!(error = %e, "request failed");
+                if matches!(endpoint, Endpoint::Api) {
+                    stats::record_failure();
+                }
+                return Err(e.into());
+            }
+        };
+        let status = resp.status();
+        if status == StatusCode::RANGE_NOT_SATISFIABLE {
+            if let Some(sink) = sink {
+                sink.discard_partial().await;
+            }
+        }
+        if matches!(endpoint, Endpoint::Api) {
+            self.throttle.update(resp.headers());
+        }
+        let header_string = |name: header::HeaderName| {
+            resp.headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(str::to_owned)
+        };
+        let content_type = header_string(header::CONTENT_TYPE);
+        let encoding = header_string(header::CONTENT_ENCODING);
+        let validators = Validators {
+            etag: header_string(header::ETAG),
+            last_modified: header_string(header::LAST_MODIFIED),
+        };
+        let retry_after = resp
+            .headers()
+            .get(header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .map(|secs: u64| Duration::from_secs(secs).min(MAX_BACKOFF));
+        let mut streamed = None;
+        let body = match sink {
+            Some(sink) if status.is_success() => {
+                streamed = Some(sink.write_response(resp).await?);
+                // Captures keep the image too, so a replay can write it out again
+                if self.capture.is_some() {
+                    tokio::fs::read(sink.path).await.wrap_err("Failed to read back image")?
+                } else {
+                    Vec::new()
+                }
+            }
+            _ => resp.bytes().await.wrap_err("Failed to read response body")?.to_vec(),
+        };
+        let bytes = streamed.unwrap_or(body.len() as u64);
+        tracing::debug!(status = status.as_u16(), bytes, "response");
+        if matches!(endpoint, Endpoint::Api) {
+            stats::record_response(status, body.len());
+        }
+        let body = decode_body(encoding.as_deref(), body)?;
+
+        let response = HttpResponse {
+            status,
+            content_type,
+            retry_after,
+            validators,
+            body,
+        };
+        if let Some(ref capture) = self.capture {
+            capture.record(url, &response);
+        }
+        if matches!(endpoint, Endpoint::Api) && status == StatusCode::UNAUTHORIZED {
+            return Err(Unauthorized.into());
+        }
+        Ok(response)
+    }
+
+    /// Rewrite a `SteamGridDB` image URL onto the configured CDN mirror, keeping its path
+    /// and query. Images from other sources (e.g. Steam's CDN) are left alone.
+    fn cdn_url<'a>(&self, url: &'a str) -> std::borrow::Cow<'a, str> {
+        let Some(ref base) = self.cdn_base else {
+            return url.into();
+        };
+        let Ok(original) = Url::parse(url) else {
+            return url.into();
+        };
+        if !original.host_str().is_some_and(|h| h.ends_with("steamgriddb.com")) {
+            return url.into();
+        }
+        let mut rewritten = base.clone();
+        let path = format!("{}{}", base.path().trim_end_matches('/'), original.path());
+        rewritten.set_path(&path);
+        rewritten.set_query(original.query());
+        rewritten.to_string().into()
+    }
+
+    /// Wait before the next API request, as paced by the throttle.
     async fn delay(&self) {
-        if !self.request_delay.is_zero() {
-            tokio::time::sleep(self.request_delay).await;
+        self.throttle.wait().await;
+    }
+}
+
+/// `User-Agent` sent unless the config names another: the crate version and where to
+/// find the project.
+const DEFAULT_USER_AGENT: &str = concat!(
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+/// Build an HTTP client with the configured proxy, connect timeout, and `User-Agent`,
+/// giving each request `timeout_secs` to complete.
+///
+/// # Errors
+///
+/// Returns an error if the proxy URL is invalid or the client cannot be built.
+pub fn http_client(config: &Config, timeout_secs: u64) -> Result<Client> {
+    // Each client is built once and shared, so its pool keeps connections open across
+    // the many small API calls of a run; keepalive stops idle ones being dropped
+    let mut builder = Client::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .pool_idle_timeout(Duration::from_secs(90))
+        .tcp_keepalive(Duration::from_secs(60))
+        .user_agent(config.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT));
+    // An explicit proxy replaces the HTTP(S)_PROXY / ALL_PROXY environment variables
+    if let Some(ref url) = config.proxy_url {
+        builder = builder.proxy(parse_proxy(url).wrap_err("Invalid proxy_url in config")?);
+    }
+    builder.build().map_err(Into::into)
+}
+
+/// The file next to a partial download `path` that holds the validator it was started
+/// with: `<name>.tmp.validator`.
+pub fn validator_path(path: &Path) -> std::path::PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(".validator");
+    name.into()
+}
+
+/// The first byte and total size from a `Content-Range` header, e.g.
+/// `bytes 1000-4999/5000`. The total is `None` when the server gives `*`.
+fn parse_content_range(value: &str) -> Option<(u64, Option<u64>)> {
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split_once('-')?.0.trim().parse().ok()?;
+    Some((start, total.trim().parse().ok()))
+}
+
+/// Decode a response body sent with `Content-Encoding`, adding what it saved to the
+/// run's total.
+fn decode_body(encoding: Option<&str>, raw: Vec<u8>) -> Result<Vec<u8>> {
+    let mut decoded = Vec::new();
+    match encoding.map(str::trim) {
+        Some("gzip") => {
+            flate2::read::GzDecoder::new(raw.as_slice())
+                .read_to_end(&mut decoded)
+                .wrap_err("Failed to decode gzip response")?;
+        }
+        Some("br") => {
+            brotli::Decompressor::new(raw.as_slice(), 4096)
+                .read_to_end(&mut decoded)
+                .wrap_err("Failed to decode brotli response")?;
+        }
+        _ => return Ok(raw),
+    }
+    let saved = decoded.len().saturating_sub(raw.len());
+    BYTES_SAVED.fetch_add(saved as u64, Ordering::Relaxed);
+    Ok(decoded)
+}
+
+/// Whether a status is worth retrying: rate limiting or a server-side failure.
+fn is_transient_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Whether a request error is worth retrying: timeouts, connection failures, and
+/// bodies cut off mid-transfer (which a retry resumes rather than restarts).
+fn is_transient_error(error: &color_eyre::Report) -> bool {
+    error.downcast_ref::<reqwest::Error>().is_some_and(is_transient_request_error)
+}
+
+fn is_transient_request_error(error: &reqwest::Error) -> bool {
+    // Reading a streamed body reports a dropped connection as a decode error
+    error.is_timeout() || error.is_connect() || error.is_body() || error.is_decode()
+}
+
+/// Whether a failed request is worth trying again later in the run: it failed with a
+/// transient status or error (see [`is_transient_status`]) that outlasted its retries.
+pub fn is_transient(error: &color_eyre::Report) -> bool {
+    error.chain().any(|e| {
+        e.downcast_ref::<StatusError>().is_some_and(|e| is_transient_status(e.status))
+            || e.downcast_ref::<reqwest::Error>().is_some_and(is_transient_request_error)
+    })
+}
+
+/// A request answered with an unsuccessful status.
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: StatusCode,
+    message: String,
+}
+
+impl StatusError {
+    pub fn new(status: StatusCode, message: String) -> Self {
+        Self { status, message }
+    }
+}
+
+impl fmt::Display for StatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// The API rejected our key (HTTP 401) — it was revoked, expired, or mistyped.
+#[derive(Debug)]
+pub struct Unauthorized;
+
+impl fmt::Display for Unauthorized {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SteamGridDB rejected the API key (HTTP 401)")
+    }
+}
+
+impl std::error::Error for Unauthorized {}
+
+/// Whether an error (or anything in its cause chain) is an [`Unauthorized`] response.
+pub fn is_unauthorized(err: &color_eyre::Report) -> bool {
+    err.chain().any(|e| e.downcast_ref::<Unauthorized>().is_some())
+}
+
+/// Reject keys that can't be sent in an `Authorization` header.
+fn check_key_format(api_key: &str) -> Result<()> {
+    reqwest::header::HeaderValue::from_str(&format!("Bearer {api_key}"))
+        .map(drop)
+        .wrap_err("Invalid API key format")
+}
+
+/// Parse a configured proxy URL, accepting `http`, `https`, `socks5`, and `socks5h`.
+///
+/// # Errors
+///
+/// Returns an error if the URL is malformed or uses another scheme.
+pub fn parse_proxy(url: &str) -> Result<Proxy> {
+    let parsed = Url::parse(url).wrap_err_with(|| format!("'{url}' is not a valid URL"))?;
+    match parsed.scheme() {
+        "http" | "https" | "socks5" | "socks5h" if parsed.has_host() => {
+            Proxy::all(parsed).wrap_err_with(|| format!("'{url}' is not a usable proxy"))
         }
+        _ => Err(eyre!("'{url}' must be an http(s) or socks5 URL with a host")),
+    }
+}
+
+/// Parse a configured endpoint URL, accepting only `http` and `https`.
+///
+/// # Errors
+///
+/// Returns an error if the URL is malformed or uses another scheme.
+pub fn parse_endpoint(url: &str) -> Result<Url> {
+    let parsed = Url::parse(url).wrap_err_with(|| format!("'{url}' is not a valid URL"))?;
+    match parsed.scheme() {
+        "http" | "https" if parsed.has_host() => Ok(parsed),
+        _ => Err(eyre!("'{url}' must be an http(s) URL with a host")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asset_urls_carry_filters_and_extra_params() {
+        let config = Config { cache_ttl_hours: 0, ..Config::default() };
+        let client = SteamGridDbClient::new("key", &config).unwrap();
+        let extra = [("oneoftag".to_owned(), "pixel art".to_owned())];
+        let query = AssetQuery {
+            dimensions: Some("600x900"),
+            exclude_nsfw: true,
+            extra: &extra,
+            ..AssetQuery::default()
+        };
+        assert_eq!(
+            client.asset_url("grids/game/7", &query, 1),
+            format!("{DEFAULT_BASE_URL}/grids/game/7?page=1&limit={PAGE_LIMIT}&dimensions=600x900&nsfw=false&oneoftag=pixel+art")
+        );
+        assert_eq!(
+            client.asset_url("heroes/game/7", &AssetQuery::default(), 0),
+            format!("{DEFAULT_BASE_URL}/heroes/game/7?page=0&limit={PAGE_LIMIT}")
+        );
+    }
+
+    #[test]
+    fn content_ranges_give_the_first_byte_and_total() {
+        assert_eq!(parse_content_range("bytes 1000-4999/5000"), Some((1000, Some(5000))));
+        assert_eq!(parse_content_range("bytes 0-99/*"), Some((0, None)));
+        assert_eq!(parse_content_range("bytes */5000"), None);
+        assert_eq!(parse_content_range("items 0-9/10"), None);
+    }
+
+    #[test]
+    fn partial_files_resume_only_with_a_validator() {
+        let dir = std::env::temp_dir().join(format!("lutrisartfetcher-resume-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("celeste.0a1b2c3d.tmp");
+        let sink = FileSink { path: &path, progress: &|_, _| {} };
+        std::fs::write(&path, [0; 100]).unwrap();
+        assert!(sink.resume_headers().is_empty());
+
+        std::fs::write(validator_path(&path), "\"v1\"").unwrap();
+        let headers = sink.resume_headers();
+        assert_eq!(headers[header::RANGE], "bytes=100-");
+        assert_eq!(headers[header::IF_RANGE], "\"v1\"");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn collections_are_filtered_and_warn_when_cut_off() {
+        let dir = std::env::temp_dir().join(format!("lutrisartfetcher-collection-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        // A replay only answers the exact URL, so this one must carry the filters
+        let url = format!("{DEFAULT_BASE_URL}/collections/9/grids?page=0&limit={PAGE_LIMIT}&nsfw=false");
+        let entry = serde_json::json!({ "url": url, "status": 200, "content_type": "application/json", "body_file": "0000.body" });
+        std::fs::write(dir.join("capture.jsonl"), format!("{entry}\n")).unwrap();
+        let asset = |id, game_id| serde_json::json!({ "id": id, "game_id": game_id, "width": 600, "height": 900, "url": "u" });
+        let body = serde_json::json!({ "success": true, "data": [asset(1, 7), asset(2, 8)], "total": 120, "limit": PAGE_LIMIT });
+        std::fs::write(dir.join("0000.body"), body.to_string()).unwrap();
+
+        let config = Config {
+            collection_id: Some(9),
+            replay_dir: Some(dir.clone()),
+            ..Config::default()
+        };
+        let client = SteamGridDbClient::new("key", &config).unwrap();
+        let query = AssetQuery {
+            dimensions: Some("600x900"),
+            exclude_nsfw: true,
+            ..AssetQuery::default()
+        };
+        let assets = client.collection_assets(7, AssetType::Grid, &query, 1).await.unwrap();
+        assert_eq!(assets.iter().map(|a| a.id).collect::<Vec<_>>(), [1]);
+        let warnings = client.take_warnings();
+        assert!(warnings.len() == 1 && warnings[0].contains("Collection 9"), "{warnings:?}");
+        std::fs::remove_dir_all(&dir).unwrap();
     }
 }
@@ -1,5 +1,9 @@
 /// `SteamGridDB` API module — client and data models.
+pub mod cache;
+pub mod capture;
 pub mod client;
 pub mod models;
+pub mod stats;
+pub mod throttle;
 
 pub use client::SteamGridDbClient;
@@ -1,4 +1,5 @@
 /// Serde structs for `SteamGridDB` API v2 responses and local enums.
+use std::collections::BTreeMap;
 use std::fmt;
 use std::path::PathBuf;
 
@@ -15,6 +16,20 @@ use serde::{Deserialize, Serialize};
 pub struct ApiResponse<T> {
     pub success: bool,
     pub data: Vec<T>,
+    /// Total number of results, on paginated endpoints.
+    #[serde(default)]
+    pub total: Option<u32>,
+    /// Page size, on paginated endpoints.
+    #[serde(default)]
+    pub limit: Option<u32>,
+}
+
+/// Envelope for endpoints that return a single object rather than a list.
+#[derive(Debug, Clone, Deserialize)]
+#[allow(dead_code)]
+pub struct ApiObject<T> {
+    pub success: bool,
+    pub data: T,
 }
 
 // ---------------------------------------------------------------------------
@@ -33,6 +48,21 @@ pub struct SearchResult {
     pub verified: bool,
 }
 
+/// A game as returned by the `/games/...` lookup endpoints.
+#[derive(Debug, Clone, Deserialize)]
+#[allow(dead_code)]
+pub struct GameInfo {
+    pub id: u64,
+    pub name: String,
+    /// Unix timestamp of the release date, when known.
+    #[serde(default)]
+    pub release_date: Option<i64>,
+    #[serde(default)]
+    pub types: Vec<String>,
+    #[serde(default)]
+    pub verified: bool,
+}
+
 // ---------------------------------------------------------------------------
 // Grid / Hero / Logo / Icon images
 // ---------------------------------------------------------------------------
@@ -40,13 +70,17 @@ pub struct SearchResult {
 /// A single image asset returned by any of the grid/hero/logo/icon endpoints.
 ///
 /// The response schema is identical across asset types, so we reuse one struct.
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Default, Deserialize)]
 #[allow(dead_code)]
 pub struct ImageAsset {
     pub id: u64,
     #[serde(default)]
     pub score: i32,
     #[serde(default)]
+    pub upvotes: u32,
+    #[serde(default)]
+    pub downvotes: u32,
+    #[serde(default)]
     pub style: String,
     pub width: u32,
     pub height: u32,
@@ -54,21 +88,49 @@ pub struct ImageAsset {
     pub nsfw: bool,
     #[serde(default)]
     pub humor: bool,
+    /// Flagged as a photosensitive-epilepsy risk (flashing animation).
+    #[serde(default)]
+    pub epilepsy: bool,
     #[serde(default)]
     pub mime: String,
     pub url: String,
     #[serde(default)]
     pub thumb: String,
+    #[serde(default)]
+    pub author: Option<Author>,
+}
+
+/// An asset in a `SteamGridDB` collection, which spans many games.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectionAsset {
+    /// The `SteamGridDB` game the asset belongs to.
+    pub game_id: u64,
+    #[serde(flatten)]
+    pub asset: ImageAsset,
+}
+
+/// The user who uploaded an asset.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[allow(dead_code)]
+pub struct Author {
+    #[serde(default)]
+    pub name: String,
+    /// The uploader's 64-bit Steam ID, which identifies them on `SteamGridDB`.
+    #[serde(default)]
+    pub steam64: String,
 }
 
 // ---------------------------------------------------------------------------
 // Asset types
 // ---------------------------------------------------------------------------
 
-/// The four categories of visual assets we can download.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// The categories of visual assets we can download.
+///
+/// Banners are wide grids (460x215 or 920x430 on `SteamGridDB`) for Lutris' banner view.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum AssetType {
     Grid,
+    Banner,
     Hero,
     Logo,
     Icon,
@@ -78,7 +140,7 @@ impl AssetType {
     /// The `SteamGridDB` API path segment for this asset type.
     pub fn api_path(self) -> &'static str {
         match self {
-            Self::Grid => "grids",
+            Self::Grid | Self::Banner => "grids",
             Self::Hero => "heroes",
             Self::Logo => "logos",
             Self::Icon => "icons",
@@ -89,6 +151,7 @@ impl AssetType {
     pub fn display_name(self) -> &'static str {
         match self {
             Self::Grid => "Grid",
+            Self::Banner => "Banner",
             Self::Hero => "Hero",
             Self::Logo => "Logo",
             Self::Icon => "Icon",
@@ -100,6 +163,7 @@ impl AssetType {
     pub fn lutris_subdir(self) -> &'static str {
         match self {
             Self::Grid => "coverart",
+            Self::Banner => "banners",
             Self::Hero => "heroes",
             Self::Logo => "logos",
             Self::Icon => "icons", // not used directly — see `asset_path()`
@@ -108,7 +172,7 @@ impl AssetType {
 
     /// All supported asset types.
     pub fn all() -> &'static [Self] {
-        &[Self::Grid, Self::Hero, Self::Logo, Self::Icon]
+        &[Self::Grid, Self::Banner, Self::Hero, Self::Logo, Self::Icon]
     }
 }
 
@@ -125,6 +189,7 @@ impl std::str::FromStr for AssetType {
     fn from_str(s: &str) -> Result<Self> {
         match s.to_lowercase().as_str() {
             "grid" | "grids" => Ok(Self::Grid),
+            "banner" | "banners" => Ok(Self::Banner),
             "hero" | "heroes" => Ok(Self::Hero),
             "logo" | "logos" => Ok(Self::Logo),
             "icon" | "icons" => Ok(Self::Icon),
@@ -144,8 +209,8 @@ pub enum DownloadStatus {
     Pending,
     /// Searching for the game on `SteamGridDB`.
     Searching,
-    /// Downloading the image bytes.
-    Downloading,
+    /// Downloading the image: `received` bytes so far, of `total` if the server said.
+    Downloading { received: u64, total: Option<u64> },
     /// Successfully saved to disk.
     Done(PathBuf),
     /// Skipped (e.g. file already exists).
@@ -166,7 +231,7 @@ impl DownloadStatus {
         match self {
             Self::Pending => "·",
             Self::Searching => "⟳",
-            Self::Downloading => "↓",
+            Self::Downloading { .. } => "↓",
             Self::Done(_) => "✓",
             Self::Skipped(_) => "─",
             Self::Failed(_) => "✗",
@@ -174,6 +239,44 @@ impl DownloadStatus {
     }
 }
 
+/// How many assets finished downloaded, skipped, and failed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AssetCounts {
+    pub downloaded: usize,
+    pub skipped: usize,
+    pub failed: usize,
+}
+
+/// [`AssetCounts`] by asset type, for the end-of-run summaries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunCounts {
+    pub by_asset: BTreeMap<AssetType, AssetCounts>,
+}
+
+impl RunCounts {
+    /// Count an asset's status; only finished ones count.
+    pub fn record(&mut self, asset: AssetType, status: &DownloadStatus) {
+        if !status.is_terminal() {
+            return;
+        }
+        let counts = self.by_asset.entry(asset).or_default();
+        match status {
+            DownloadStatus::Done(_) => counts.downloaded += 1,
+            DownloadStatus::Skipped(_) => counts.skipped += 1,
+            _ => counts.failed += 1,
+        }
+    }
+
+    /// The counts across all asset types.
+    pub fn total(&self) -> AssetCounts {
+        self.by_asset.values().fold(AssetCounts::default(), |total, c| AssetCounts {
+            downloaded: total.downloaded + c.downloaded,
+            skipped: total.skipped + c.skipped,
+            failed: total.failed + c.failed,
+        })
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Progress message sent from download tasks to the TUI
 // ---------------------------------------------------------------------------
@@ -185,3 +288,20 @@ pub struct DownloadProgress {
     pub asset_type: AssetType,
     pub status: DownloadStatus,
 }
+
+/// Everything the download pipeline reports back to its consumer (TUI or headless output).
+#[derive(Debug, Clone)]
+pub enum PipelineEvent {
+    /// Status change for one (game, asset) pair.
+    Progress(DownloadProgress),
+    /// A chunk of games finished and its checkpoint was written.
+    ChunkFinished { chunk: usize, chunks: usize },
+    /// Non-fatal, run-level notice (e.g. a fallback kicked in).
+    Warning(String),
+    /// The API key was rejected; the pipeline is paused until a new key is supplied.
+    AuthExpired,
+    /// The run was abandoned because the art sources look unreachable; says why.
+    Aborted(String),
+    /// The run couldn't start, e.g. because an art source is misconfigured; says why.
+    NotStarted(String),
+}
@@ -0,0 +1,134 @@
+/// Run-wide `SteamGridDB` API usage counters.
+///
+/// Every client adds to the same counters, so the summary shown at the end of a run
+/// covers all of its API traffic: requests sent, retries, 404s, 429s, bytes received,
+/// answers served from the response cache, and the lowest rate-limit budget the server
+/// reported.
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use reqwest::StatusCode;
+
+use crate::download::format_size;
+
+static REQUESTS: AtomicU64 = AtomicU64::new(0);
+static RETRIES: AtomicU64 = AtomicU64::new(0);
+static NOT_FOUND: AtomicU64 = AtomicU64::new(0);
+static RATE_LIMITED: AtomicU64 = AtomicU64::new(0);
+static BYTES: AtomicU64 = AtomicU64::new(0);
+static CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+/// Lowest `X-RateLimit-Remaining` seen, or `u64::MAX` before any.
+static LOWEST_REMAINING: AtomicU64 = AtomicU64::new(u64::MAX);
+static RATE_LIMIT: AtomicU64 = AtomicU64::new(0);
+
+/// A snapshot of the counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ApiStats {
+    /// Requests sent, retries included.
+    pub requests: u64,
+    pub retries: u64,
+    pub not_found: u64,
+    pub rate_limited: u64,
+    /// Response body bytes received, as sent (before decompression).
+    pub bytes: u64,
+    pub cache_hits: u64,
+    /// Lowest remaining request budget the server reported, and the window's limit.
+    pub lowest_budget: Option<(u64, u64)>,
+}
+
+/// The counters so far.
+pub fn snapshot() -> ApiStats {
+    let lowest = LOWEST_REMAINING.load(Ordering::Relaxed);
+    ApiStats {
+        requests: REQUESTS.load(Ordering::Relaxed),
+        retries: RETRIES.load(Ordering::Relaxed),
+        not_found: NOT_FOUND.load(Ordering::Relaxed),
+        rate_limited: RATE_LIMITED.load(Ordering::Relaxed),
+        bytes: BYTES.load(Ordering::Relaxed),
+        cache_hits: CACHE_HITS.load(Ordering::Relaxed),
+        lowest_budget: (lowest != u64::MAX).then(|| (lowest, RATE_LIMIT.load(Ordering::Relaxed))),
+    }
+}
+
+/// Start the counters afresh, for another run in the same session.
+pub fn reset() {
+    for counter in [&REQUESTS, &RETRIES, &NOT_FOUND, &RATE_LIMITED, &BYTES, &CACHE_HITS, &RATE_LIMIT] {
+        counter.store(0, Ordering::Relaxed);
+    }
+    LOWEST_REMAINING.store(u64::MAX, Ordering::Relaxed);
+}
+
+/// Count a response to an API request.
+pub(super) fn record_response(status: StatusCode, bytes: usize) {
+    REQUESTS.fetch_add(1, Ordering::Relaxed);
+    BYTES.fetch_add(bytes as u64, Ordering::Relaxed);
+    match status {
+        StatusCode::NOT_FOUND => NOT_FOUND.fetch_add(1, Ordering::Relaxed),
+        StatusCode::TOO_MANY_REQUESTS => RATE_LIMITED.fetch_add(1, Ordering::Relaxed),
+        _ => 0,
+    };
+}
+
+/// Count an API request that failed without a response.
+pub(super) fn record_failure() {
+    REQUESTS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(super) fn record_retry() {
+    RETRIES.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(super) fn record_cache_hit() {
+    CACHE_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Note the rate-limit budget a response reported.
+pub(super) fn record_budget(remaining: u64, limit: u64) {
+    if remaining < LOWEST_REMAINING.fetch_min(remaining, Ordering::Relaxed) {
+        RATE_LIMIT.store(limit, Ordering::Relaxed);
+    }
+}
+
+impl ApiStats {
+    /// One-line summary, e.g. `"120 API requests (3 retries, 2 not found), 1.2 MiB
+    /// received, 40 from cache"`.
+    pub fn summary(&self) -> String {
+        let mut details = Vec::new();
+        if self.retries > 0 {
+            details.push(format!("{} retries", self.retries));
+        }
+        if self.not_found > 0 {
+            details.push(format!("{} not found", self.not_found));
+        }
+        if self.rate_limited > 0 {
+            details.push(format!("{} rate-limited", self.rate_limited));
+        }
+        let mut parts = vec![format!("{} API requests", self.requests)];
+        if !details.is_empty() {
+            parts[0] = format!("{} ({})", parts[0], details.join(", "));
+        }
+        parts.push(format!("{} received", format_size(self.bytes)));
+        if self.cache_hits > 0 {
+            parts.push(format!("{} from cache", self.cache_hits));
+        }
+        if let Some((remaining, limit)) = self.lowest_budget {
+            parts.push(format!("rate-limit budget fell to {remaining}/{limit}"));
+        }
+        parts.join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counters_start_afresh_for_each_run() {
+        record_response(StatusCode::NOT_FOUND, 512);
+        record_retry();
+        record_budget(10, 50);
+        assert!(snapshot().requests > 0 && snapshot().lowest_budget.is_some());
+
+        reset();
+        assert_eq!(snapshot(), ApiStats::default());
+    }
+}
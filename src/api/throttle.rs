@@ -0,0 +1,163 @@
+/// Request pacing shared by all concurrent tasks.
+///
+/// A token bucket refilled every `request_delay_ms` (holding up to `request_burst`
+/// tokens) caps the request rate however many downloads run at once. On top of that,
+/// once `X-RateLimit-*` headers are seen, the remaining budget is spread evenly over
+/// the window as it runs low, and requests pause until the reset when it is exhausted.
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use reqwest::header::HeaderMap;
+
+/// Longest single pause, in case the server reports a far-off reset.
+const MAX_PAUSE: Duration = Duration::from_secs(60);
+
+/// Paces API requests.
+pub struct Throttle {
+    bucket: Mutex<TokenBucket>,
+    state: Mutex<Option<RateLimit>>,
+}
+
+/// Tokens refill one per `interval`; a request takes one, waiting if none is left.
+#[derive(Debug)]
+struct TokenBucket {
+    interval: Duration,
+    capacity: f64,
+    /// Goes negative while requests are queued for tokens not yet refilled.
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl TokenBucket {
+    /// Take a token at `now`, returning how long until it is actually available.
+    ///
+    /// Waiting callers reserve their token up front, so concurrent tasks queue up one
+    /// interval apart instead of all waking at once.
+    fn take(&mut self, now: Instant) -> Duration {
+        if self.interval.is_zero() {
+            return Duration::ZERO;
+        }
+        let refill = now.saturating_duration_since(self.refilled).as_secs_f64() / self.interval.as_secs_f64();
+        self.tokens = (self.tokens + refill).min(self.capacity);
+        self.refilled = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            self.interval.mul_f64(-self.tokens)
+        }
+    }
+}
+
+/// The most recently reported rate-limit window.
+#[derive(Debug, Clone, Copy)]
+struct RateLimit {
+    limit: u32,
+    remaining: u32,
+    reset_at: Instant,
+}
+
+impl Throttle {
+    /// Allow one request per `interval`, with bursts of up to `burst` back-to-back.
+    pub fn new(interval: Duration, burst: u32) -> Self {
+        let capacity = f64::from(burst.max(1));
+        Self {
+            bucket: Mutex::new(TokenBucket {
+                interval,
+                capacity,
+                tokens: capacity,
+                refilled: Instant::now(),
+            }),
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Wait before sending the next request.
+    pub async fn wait(&self) {
+        let now = Instant::now();
+        let queued = self.bucket.lock().unwrap_or_else(PoisonError::into_inner).take(now);
+        let pause = queued.max(self.pause(now));
+        if !pause.is_zero() {
+            tokio::time::sleep(pause).await;
+        }
+    }
+
+    /// Record the rate-limit headers of a response, if it has them.
+    pub fn update(&self, headers: &HeaderMap) {
+        let header = |name: &str| -> Option<u64> {
+            headers.get(name)?.to_str().ok()?.trim().parse().ok()
+        };
+        let (Some(limit), Some(remaining)) = (header("x-ratelimit-limit"), header("x-ratelimit-remaining")) else {
+            return;
+        };
+        let reset_in = header("x-ratelimit-reset").map_or(Duration::from_secs(60), reset_delay);
+        super::stats::record_budget(remaining, limit);
+
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        *state = Some(RateLimit {
+            limit: u32::try_from(limit).unwrap_or(u32::MAX),
+            remaining: u32::try_from(remaining).unwrap_or(u32::MAX),
+            reset_at: Instant::now() + reset_in.min(MAX_PAUSE),
+        });
+    }
+
+    /// How long the server's reported budget asks us to wait at `now`.
+    fn pause(&self, now: Instant) -> Duration {
+        let state = *self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let Some(limit) = state else {
+            return Duration::ZERO;
+        };
+        if now >= limit.reset_at {
+            // The window rolled over; no fresh numbers yet
+            return Duration::ZERO;
+        }
+        let window_left = limit.reset_at - now;
+        match limit.remaining {
+            0 => window_left,
+            r if u64::from(r) * 2 >= u64::from(limit.limit) => Duration::ZERO,
+            r => window_left / r,
+        }
+    }
+}
+
+/// Interpret an `X-RateLimit-Reset` value: servers send either seconds until the reset
+/// or a Unix timestamp.
+fn reset_delay(value: u64) -> Duration {
+    const TIMESTAMP_THRESHOLD: u64 = 1_000_000_000;
+    if value < TIMESTAMP_THRESHOLD {
+        return Duration::from_secs(value);
+    }
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    Duration::from_secs(value.saturating_sub(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_bucket_queues_concurrent_requests() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(100);
+        let mut bucket = TokenBucket {
+            interval,
+            capacity: 2.0,
+            tokens: 2.0,
+            refilled: start,
+        };
+
+        // The burst goes straight through, then callers queue one interval apart
+        assert_eq!(bucket.take(start), Duration::ZERO);
+        assert_eq!(bucket.take(start), Duration::ZERO);
+        assert_eq!(bucket.take(start), interval);
+        assert_eq!(bucket.take(start), interval * 2);
+
+        // Idle time refills, but never beyond the burst size
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+        assert_eq!(bucket.take(later), Duration::ZERO);
+        assert_eq!(bucket.take(later), interval);
+    }
+}
@@ -1,18 +1,29 @@
 /// Application state machine — holds all state, handles key events and download progress.
-use std::collections::HashSet;
-use std::time::Instant;
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use color_eyre::eyre::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::widgets::ListState;
 use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio::sync::watch;
+use tokio_util::sync::CancellationToken;
 
-use crate::api::models::{AssetType, DownloadProgress, DownloadStatus};
+use crate::api::models::{AssetCounts, AssetType, DownloadProgress, DownloadStatus, PipelineEvent, RunCounts};
+use crate::api::stats::ApiStats;
 use crate::api::SteamGridDbClient;
 use crate::config::Config;
 use crate::db::Game;
 use crate::download::{self, GameEntry};
 use crate::event::AppEvent;
+use crate::failures::FailedAssets;
+use crate::history::RunHistory;
+use crate::launcher::LauncherBackend;
+use crate::manifest::Manifest;
+use crate::provider;
 
 // ---------------------------------------------------------------------------
 // Screen state
@@ -43,10 +54,82 @@ pub enum AppScreen {
         downloaded: usize,
         skipped: usize,
         failed: usize,
+        /// The same counts for each asset type.
+        by_asset: RunCounts,
         elapsed_secs: u64,
+        /// API traffic avoided by compressed responses.
+        bytes_saved: u64,
+        /// `SteamGridDB` API usage over the run.
+        api: ApiStats,
+        /// The run was abandoned before every game was processed.
+        stopped: bool,
     },
 }
 
+// ---------------------------------------------------------------------------
+// Sorting
+// ---------------------------------------------------------------------------
+
+/// Ordering applied to the game list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum SortOrder {
+    /// Alphabetical by name (the database order).
+    #[default]
+    Name,
+    /// Most recently installed first — new installs are the ones missing art.
+    RecentlyInstalled,
+    /// Most recently played first.
+    RecentlyPlayed,
+    /// Most hours played first.
+    MostPlayed,
+    /// Largest art on disk first.
+    #[value(name = "size")]
+    ArtSize,
+}
+
+impl SortOrder {
+    /// Short label shown in the game list title.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::RecentlyInstalled => "recently installed",
+            Self::RecentlyPlayed => "recently played",
+            Self::MostPlayed => "most played",
+            Self::ArtSize => "art size",
+        }
+    }
+
+    /// The next order in the `s` key cycle.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Name => Self::RecentlyInstalled,
+            Self::RecentlyInstalled => Self::RecentlyPlayed,
+            Self::RecentlyPlayed => Self::MostPlayed,
+            Self::MostPlayed => Self::ArtSize,
+            Self::ArtSize => Self::Name,
+        }
+    }
+
+    /// Sort `items` in place by this order, using `game` to reach each item's game and
+    /// `launcher` to find its art.
+    pub fn sort<T>(self, items: &mut [T], launcher: &dyn LauncherBackend, game: impl Fn(&T) -> &Game) {
+        match self {
+            Self::Name => items.sort_by_cached_key(|t| game(t).name.to_lowercase()),
+            // `Reverse(None)` sorts after every `Reverse(Some(_))`, so unknown dates go last
+            Self::RecentlyInstalled => items.sort_by_key(|t| Reverse(game(t).installed_at)),
+            Self::RecentlyPlayed => items.sort_by_key(|t| Reverse(game(t).lastplayed)),
+            // Never-played games have no playtime, so they go last too
+            Self::MostPlayed => items.sort_by(|a, b| {
+                let hours = |t| game(t).playtime.unwrap_or(0.0);
+                hours(b).total_cmp(&hours(a))
+            }),
+            Self::ArtSize => {
+                items.sort_by_cached_key(|t| Reverse(download::art_size_on_disk(launcher, &game(t).slug)));
+            }
+        }
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Log
 // ---------------------------------------------------------------------------
@@ -64,17 +147,65 @@ pub enum LogLevel {
 // App
 // ---------------------------------------------------------------------------
 
+/// Byte progress of the image being downloaded right now.
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    pub slug: String,
+    /// Display name of the game.
+    pub game: String,
+    pub asset: AssetType,
+    pub received: u64,
+    /// Expected size, if the server sent one.
+    pub total: Option<u64>,
+}
+
+/// Log lines kept in memory; older lines are dropped so huge libraries stay bounded.
+const MAX_LOG_ENTRIES: usize = 1000;
+
+/// How long the library file has to be left alone before the game list is reloaded.
+const RELOAD_DELAY: Duration = Duration::from_secs(1);
+
 /// Root application state.
+#[allow(clippy::struct_excessive_bools)]
 pub struct App {
     pub screen: AppScreen,
     pub games: Vec<GameEntry>,
+    /// Uninstalled games, while they're hidden from the list.
+    pub hidden_uninstalled: Vec<GameEntry>,
     pub list_state: ListState,
     pub log: Vec<(LogLevel, String)>,
     pub selected_assets: HashSet<AssetType>,
     pub config: Config,
+    /// The launcher whose games are listed.
+    pub launcher: Arc<dyn LauncherBackend>,
     pub should_quit: bool,
     pub show_help: bool,
     pub force_download: bool,
+    /// Skip what was finished by the previous, interrupted run.
+    pub resume: bool,
+    /// Fetch only these (game, asset) pairs: the failures being retried.
+    pub retry_only: Option<FailedAssets>,
+    /// Slugs the list is narrowed to (`--game`, or the games `--sample` or
+    /// `--retry-failed` picked), kept when it's reloaded.
+    pub game_filter: Vec<String>,
+    /// When the launcher's library last changed, until the list is reloaded.
+    games_changed_at: Option<Instant>,
+    /// Sends replacement API keys to the running pipeline.
+    key_tx: Option<UnboundedSender<String>>,
+    /// Stops the running pipeline.
+    cancel: Option<CancellationToken>,
+    /// Pauses the running pipeline.
+    pause: Option<watch::Sender<bool>>,
+    /// When the running pipeline was paused, if it is.
+    pub paused_since: Option<Instant>,
+    /// The download screen to return to once a rotated key has been validated.
+    paused_screen: Option<AppScreen>,
+    /// Timings from previous runs, for duration estimates.
+    pub history: RunHistory,
+    /// Current ordering of `games`.
+    pub sort_order: SortOrder,
+    /// The download in flight, for the per-file progress bar.
+    pub transfer: Option<Transfer>,
     /// Spinner animation frame counter.
     pub tick_count: u64,
 }
@@ -83,13 +214,16 @@ impl App {
     /// Initialize the app. Decides the starting screen based on config state.
     pub fn new(
         config: Config,
+        launcher: Arc<dyn LauncherBackend>,
         games: Vec<Game>,
         assets: HashSet<AssetType>,
         force: bool,
     ) -> Self {
-        let entries: Vec<GameEntry> = games.into_iter().map(GameEntry::new).collect();
+        let entries = games.into_iter().map(GameEntry::new);
+        let (entries, hidden_uninstalled): (Vec<GameEntry>, Vec<GameEntry>) =
+            entries.partition(|e| e.game.installed || config.include_uninstalled);
 
-        let screen = if config.api_key.is_none() {
+        let screen = if config.api_key.is_none() && provider::needs_api_key(&config) {
             AppScreen::ApiKeyEntry {
                 input: String::new(),
                 cursor_pos: 0,
@@ -108,13 +242,27 @@ impl App {
         Self {
             screen,
             games: entries,
+            hidden_uninstalled,
             list_state,
             log: Vec::new(),
             selected_assets: assets,
             config,
+            launcher,
             should_quit: false,
             show_help: false,
             force_download: force,
+            resume: false,
+            retry_only: None,
+            game_filter: Vec::new(),
+            games_changed_at: None,
+            key_tx: None,
+            cancel: None,
+            pause: None,
+            paused_since: None,
+            paused_screen: None,
+            history: RunHistory::load(),
+            sort_order: SortOrder::default(),
+            transfer: None,
             tick_count: 0,
         }
     }
@@ -148,7 +296,7 @@ impl App {
             AppScreen::AssetTypeSelection { .. } => self.handle_asset_selection(key),
             AppScreen::GameList => self.handle_game_list(key, tx),
             AppScreen::Downloading { .. } => self.handle_downloading(key),
-            AppScreen::Done { .. } => self.handle_done(key),
+            AppScreen::Done { .. } => self.handle_done(key, tx),
         }
     }
 
@@ -171,19 +319,15 @@ impl App {
                 *cursor_pos += 1;
                 *error_msg = None;
             }
-            KeyCode::Backspace => {
-                if *cursor_pos > 0 {
-                    *cursor_pos -= 1;
-                    input.remove(*cursor_pos);
-                }
+            KeyCode::Backspace if *cursor_pos > 0 => {
+                *cursor_pos -= 1;
+                input.remove(*cursor_pos);
             }
             KeyCode::Left => {
                 *cursor_pos = cursor_pos.saturating_sub(1);
             }
-            KeyCode::Right => {
-                if *cursor_pos < input.len() {
-                    *cursor_pos += 1;
-                }
+            KeyCode::Right if *cursor_pos < input.len() => {
+                *cursor_pos += 1;
             }
             KeyCode::Enter => {
                 if input.trim().is_empty() {
@@ -196,22 +340,32 @@ impl App {
 
                 // Spawn async validation
                 let tx = tx.clone();
+                let config = self.config.clone();
                 tokio::spawn(async move {
-                    let result = validate_and_store_key(api_key).await;
+                    let result = validate_and_store_key(api_key, &config).await;
                     // We send a special progress event to signal validation result
                     let status = match result {
                         Ok(()) => DownloadStatus::Done(std::path::PathBuf::new()),
+                        // Show why the server was unreachable, not just that it was
+                        Err(e) if e.chain().count() > 1 => {
+                            DownloadStatus::Failed(format!("{e}: {}", e.root_cause()))
+                        }
                         Err(e) => DownloadStatus::Failed(e.to_string()),
                     };
-                    let _ = tx.send(AppEvent::Download(DownloadProgress {
+                    let _ = tx.send(AppEvent::Pipeline(PipelineEvent::Progress(DownloadProgress {
                         game_slug: "__api_key_validation__".into(),
                         asset_type: AssetType::Grid,
                         status,
-                    }));
+                    })));
                 });
             }
             KeyCode::Esc => {
-                self.should_quit = true;
+                // Mid-run, give up on the batch rather than quitting outright
+                if let Some(AppScreen::Downloading { started_at, .. }) = self.paused_screen.take() {
+                    self.stop_downloads(started_at);
+                } else {
+                    self.should_quit = true;
+                }
             }
             _ => {}
         }
@@ -228,12 +382,10 @@ impl App {
 
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
-                *cursor = cursor.saturating_sub(1);
+                *cursor = step(*cursor, all.len(), false, self.config.wrap_navigation);
             }
             KeyCode::Down | KeyCode::Char('j') => {
-                if *cursor + 1 < all.len() {
-                    *cursor += 1;
-                }
+                *cursor = step(*cursor, all.len(), true, self.config.wrap_navigation);
             }
             KeyCode::Char(' ') => {
                 let asset = all[*cursor];
@@ -269,8 +421,10 @@ impl App {
     fn handle_game_list(&mut self, key: KeyEvent, tx: &UnboundedSender<AppEvent>) {
         let len = self.games.len();
         if len == 0 {
-            if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
-                self.should_quit = true;
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+                KeyCode::Char('i') => self.toggle_uninstalled(),
+                _ => {}
             }
             return;
         }
@@ -278,11 +432,11 @@ impl App {
         match key.code {
             KeyCode::Up | KeyCode::Char('k') => {
                 let i = self.list_state.selected().unwrap_or(0);
-                self.list_state.select(Some(i.saturating_sub(1)));
+                self.list_state.select(Some(step(i, len, false, self.config.wrap_navigation)));
             }
             KeyCode::Down | KeyCode::Char('j') => {
                 let i = self.list_state.selected().unwrap_or(0);
-                self.list_state.select(Some((i + 1).min(len - 1)));
+                self.list_state.select(Some(step(i, len, true, self.config.wrap_navigation)));
             }
             KeyCode::Home => {
                 self.list_state.select(Some(0));
@@ -298,6 +452,30 @@ impl App {
                 let i = self.list_state.selected().unwrap_or(0);
                 self.list_state.select(Some((i + 10).min(len - 1)));
             }
+            KeyCode::Char('s') => {
+                self.set_sort_order(self.sort_order.next());
+            }
+            KeyCode::Char('x') => {
+                self.toggle_excluded();
+            }
+            KeyCode::Char('i') => {
+                self.toggle_uninstalled();
+            }
+            KeyCode::Char('c') => {
+                self.cycle_category();
+            }
+            KeyCode::Char('u') => {
+                if let Some(slug) = self.list_state.selected().and_then(|i| self.games.get(i)).map(|e| e.game.slug.clone()) {
+                    self.restore_backups(&[slug], None);
+                }
+            }
+            KeyCode::Char('U') => match download::last_backup_run() {
+                Some(run) => {
+                    let slugs: Vec<String> = self.games.iter().map(|e| e.game.slug.clone()).collect();
+                    self.restore_backups(&slugs, Some(&run));
+                }
+                None => self.log(LogLevel::Warn, "No backed-up art to restore".into()),
+            },
             KeyCode::Enter => {
                 self.start_downloads(tx);
             }
@@ -308,103 +486,476 @@ impl App {
         }
     }
 
+    /// Put back the art downloads replaced for these games — only what `run` backed up,
+    /// if given — and log how it went.
+    fn restore_backups(&mut self, slugs: &[String], run: Option<&Path>) {
+        let mut manifest = match Manifest::load() {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                self.log(LogLevel::Error, format!("Could not restore art: {e:#}"));
+                return;
+            }
+        };
+        let mut restored = 0;
+        for slug in slugs {
+            for (asset, result) in download::restore_backups(&*self.launcher, &mut manifest, slug, run) {
+                match result {
+                    Ok(done) => {
+                        restored += 1;
+                        if let Some(warning) = done.warning {
+                            self.log(LogLevel::Warn, format!("{slug} ({asset}): {warning}"));
+                        }
+                    }
+                    Err(e) => self.log(LogLevel::Error, format!("Could not restore {slug} ({asset}): {e}")),
+                }
+            }
+        }
+        if let Err(e) = manifest.save() {
+            self.log(LogLevel::Warn, format!("Could not save manifest: {e}"));
+        }
+        let message = match (restored, slugs) {
+            (0, _) => "Nothing to restore".to_owned(),
+            (_, [slug]) => format!("Restored {restored} previous assets for {slug}"),
+            _ => format!("Restored {restored} assets replaced by the last run"),
+        };
+        self.log(LogLevel::Info, message);
+    }
+
+    /// Toggle "never fetch art" for the highlighted game and persist it.
+    fn toggle_excluded(&mut self) {
+        let Some(entry) = self.list_state.selected().and_then(|i| self.games.get(i)) else {
+            return;
+        };
+        let (slug, name) = (entry.game.slug.clone(), entry.game.name.clone());
+
+        let message = if self.config.excludes(&entry.game) {
+            // Drop a manual exclusion; if the non-game heuristics still match, override them.
+            self.config.excluded_games.remove(&slug);
+            if self.config.excludes(&entry.game) {
+                self.config.included_games.insert(slug);
+            }
+            format!("{name} will have art fetched again")
+        } else {
+            self.config.included_games.remove(&slug);
+            if !self.config.excludes(&entry.game) {
+                self.config.excluded_games.insert(slug);
+            }
+            format!("{name} excluded from art fetching")
+        };
+        self.log(LogLevel::Info, message);
+
+        // Save onto the on-disk config so command-line overrides aren't persisted
+        let saved = Config::load().and_then(|mut on_disk| {
+            on_disk.excluded_games.clone_from(&self.config.excluded_games);
+            on_disk.included_games.clone_from(&self.config.included_games);
+            on_disk.save()
+        });
+        if let Err(e) = saved {
+            self.log(LogLevel::Warn, format!("Could not save config: {e}"));
+        }
+    }
+
+    /// Show or hide the games that aren't installed — and with them, whether they get art.
+    fn toggle_uninstalled(&mut self) {
+        let selected = self.list_state.selected().and_then(|i| self.games.get(i)).map(|e| e.game.slug.clone());
+        self.config.include_uninstalled = !self.config.include_uninstalled;
+        let message = if self.config.include_uninstalled {
+            let count = self.hidden_uninstalled.len();
+            self.games.append(&mut self.hidden_uninstalled);
+            format!("Showing {count} uninstalled games")
+        } else {
+            let (installed, uninstalled) = std::mem::take(&mut self.games)
+                .into_iter()
+                .partition(|e| e.game.installed);
+            self.games = installed;
+            self.hidden_uninstalled = uninstalled;
+            format!("Hiding {} uninstalled games", self.hidden_uninstalled.len())
+        };
+        self.resort();
+        // Stay on the same game; if it was just hidden, on the first
+        let index = selected.and_then(|slug| self.games.iter().position(|e| e.game.slug == slug));
+        if index.is_some() || !self.games.is_empty() {
+            self.list_state.select(Some(index.unwrap_or(0)));
+        }
+        self.log(LogLevel::Info, message);
+    }
+
+    /// Narrow the run to the next Lutris category in the library, or after the last one,
+    /// back to all of them. Not saved to the config.
+    fn cycle_category(&mut self) {
+        let all: BTreeSet<String> = self
+            .games
+            .iter()
+            .chain(&self.hidden_uninstalled)
+            .flat_map(|e| e.game.categories.iter().cloned())
+            .collect();
+        let current = match self.config.categories.len() {
+            1 => self.config.categories.first(),
+            _ => None,
+        };
+        let next = match current {
+            Some(current) => all.iter().find(|&c| c > current).cloned(),
+            None => all.first().cloned(),
+        };
+        let message = match next {
+            Some(category) => {
+                self.config.categories = BTreeSet::from([category.clone()]);
+                format!("Only fetching games in category {category}")
+            }
+            None if all.is_empty() => "No games are in any category".to_owned(),
+            None => {
+                self.config.categories.clear();
+                "Fetching games in all categories".to_owned()
+            }
+        };
+        self.log(LogLevel::Info, message);
+    }
+
+    /// Whether a game is left out of downloads and totals.
+    pub fn is_excluded(&self, entry: &GameEntry) -> bool {
+        self.config.excludes(&entry.game)
+    }
+
+    /// Games that will actually be processed, i.e. not excluded.
+    pub fn active_games(&self) -> impl Iterator<Item = &GameEntry> {
+        self.games.iter().filter(|e| !self.is_excluded(e))
+    }
+
+    /// Re-sort the game list, keeping the highlighted game selected.
+    pub fn set_sort_order(&mut self, order: SortOrder) {
+        let selected_slug = self
+            .list_state
+            .selected()
+            .and_then(|i| self.games.get(i))
+            .map(|e| e.game.slug.clone());
+
+        self.sort_order = order;
+        order.sort(&mut self.games, &*self.launcher, |e| &e.game);
+
+        if let Some(slug) = selected_slug {
+            let index = self.games.iter().position(|e| e.game.slug == slug);
+            self.list_state.select(index.or(Some(0)));
+        }
+    }
+
+    /// Re-sort the list after games came or went, keeping a valid selection.
+    fn resort(&mut self) {
+        self.set_sort_order(self.sort_order);
+        if self.games.is_empty() {
+            self.list_state.select(None);
+        } else if self.list_state.selected().is_none() {
+            self.list_state.select(Some(0));
+        }
+    }
+
+    // -- Library changes ----------------------------------------------------
+
+    /// Note that the launcher's library changed; the list is reloaded once it settles.
+    pub fn games_changed(&mut self) {
+        self.games_changed_at = Some(Instant::now());
+    }
+
+    /// Reload the game list in the background if the library changed a while ago; it
+    /// arrives as [`AppEvent::GamesLoaded`]. Waits while downloads run, so they finish
+    /// with the list they started with.
+    pub fn reload_games_if_due(&mut self, tx: &UnboundedSender<AppEvent>) {
+        let due = self.games_changed_at.is_some_and(|at| at.elapsed() >= RELOAD_DELAY);
+        if !due || !matches!(self.screen, AppScreen::GameList) {
+            return;
+        }
+        self.games_changed_at = None;
+        let tx = tx.clone();
+        let config = self.config.clone();
+        let launcher = Arc::clone(&self.launcher);
+        tokio::task::spawn_blocking(move || {
+            let games = launcher.games(&config, true).map_err(|e| e.to_string());
+            let _ = tx.send(AppEvent::GamesLoaded(games));
+        });
+    }
+
+    /// Take in the reloaded library — unless downloads started in the meantime, in which
+    /// case it's reloaded again once they're done.
+    pub fn games_loaded(&mut self, games: Result<Vec<Game>, String>) {
+        if !matches!(self.screen, AppScreen::GameList) {
+            self.games_changed();
+            return;
+        }
+        match games {
+            Ok(games) => self.refresh_games(games),
+            Err(e) => {
+                let name = self.launcher.name();
+                self.log(LogLevel::Warn, format!("Could not reload the {name} library: {e}"));
+            }
+        }
+        for warning in self.launcher.take_warnings() {
+            self.log(LogLevel::Warn, warning);
+        }
+    }
+
+    /// Replace the game list with `games`, keeping the statuses of games already in it.
+    pub fn refresh_games(&mut self, games: Vec<Game>) {
+        let mut old: HashMap<(i64, String), GameEntry> = std::mem::take(&mut self.games)
+            .into_iter()
+            .chain(std::mem::take(&mut self.hidden_uninstalled))
+            .map(|e| ((e.game.id, e.game.slug.clone()), e))
+            .collect();
+        let mut added = 0;
+        for game in games {
+            if !self.game_filter.is_empty() && !self.game_filter.contains(&game.slug) {
+                continue;
+            }
+            let entry = if let Some(entry) = old.remove(&(game.id, game.slug.clone())) {
+                GameEntry { game, ..entry }
+            } else {
+                added += 1;
+                GameEntry::new(game)
+            };
+            if entry.game.installed || self.config.include_uninstalled {
+                self.games.push(entry);
+            } else {
+                self.hidden_uninstalled.push(entry);
+            }
+        }
+        self.resort();
+
+        let removed = old.len();
+        if added + removed > 0 {
+            let name = self.launcher.name();
+            self.log(LogLevel::Info, format!("{name} library changed: {added} games added, {removed} removed"));
+        }
+    }
+
     // -- Downloading --------------------------------------------------------
 
     fn handle_downloading(&mut self, key: KeyEvent) {
-        if matches!(key.code, KeyCode::Char('q') | KeyCode::Esc) {
-            self.should_quit = true;
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => self.should_quit = true,
+            KeyCode::Char('p') => self.toggle_pause(),
+            KeyCode::Char('c') => {
+                if let AppScreen::Downloading { started_at, .. } = self.screen {
+                    self.cancel_downloads();
+                    self.log(LogLevel::Warn, "Downloads cancelled — re-run with --resume to continue".into());
+                    self.finish_downloads(started_at, true);
+                }
+            }
+            _ => {}
         }
     }
 
     // -- Done ---------------------------------------------------------------
 
-    fn handle_done(&mut self, key: KeyEvent) {
-        if matches!(
-            key.code,
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter
-        ) {
-            self.should_quit = true;
+    fn handle_done(&mut self, key: KeyEvent, tx: &UnboundedSender<AppEvent>) {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Enter => self.should_quit = true,
+            KeyCode::Char('r') => self.retry_failed(tx),
+            _ => {}
         }
     }
 
+    /// Run the pipeline again over just the assets that failed.
+    fn retry_failed(&mut self, tx: &UnboundedSender<AppEvent>) {
+        let failed = self.failed_assets();
+        if failed.is_empty() {
+            return;
+        }
+        for entry in &mut self.games {
+            if let Some(assets) = failed.games.get(&entry.game.slug) {
+                for &asset in assets {
+                    *entry.status_mut(asset) = DownloadStatus::Pending;
+                }
+            }
+        }
+        self.log(LogLevel::Info, format!("Retrying {} failed assets", failed.len()));
+        // The checkpoint counts games with a failed asset as done
+        self.resume = false;
+        self.retry_only = Some(failed);
+        self.start_downloads(tx);
+    }
+
+    /// The assets of the current game list that failed.
+    fn failed_assets(&self) -> FailedAssets {
+        let mut failed = FailedAssets::default();
+        for entry in self.active_games() {
+            for &asset in &self.selected_assets {
+                if matches!(entry.status(asset), DownloadStatus::Failed(_)) {
+                    failed.insert(&entry.game.slug, asset);
+                }
+            }
+        }
+        failed
+    }
+
+    /// The asset types a run fetches for a game: the selected ones, narrowed to the
+    /// failures when retrying.
+    fn run_assets<'a>(&'a self, entry: &'a GameEntry) -> impl Iterator<Item = AssetType> + 'a {
+        self.selected_assets.iter().copied().filter(move |&asset| {
+            self.retry_only
+                .as_ref()
+                .map_or(true, |only| only.contains(&entry.game.slug, asset))
+        })
+    }
+
     // -- Downloads ----------------------------------------------------------
 
     /// Kick off the download pipeline in a background task.
     fn start_downloads(&mut self, tx: &UnboundedSender<AppEvent>) {
-        let total = self.games.len() * self.selected_assets.len();
+        let games: Vec<Game> = self
+            .active_games()
+            .filter(|e| self.run_assets(e).next().is_some())
+            .map(|e| e.game.clone())
+            .collect();
+        if games.is_empty() {
+            self.log(LogLevel::Warn, "Every game is excluded — nothing to download".into());
+            return;
+        }
+
+        let total = unique_slugs(self.active_games()).map(|e| self.run_assets(e).count()).sum();
         self.screen = AppScreen::Downloading {
             current: 0,
             total,
             started_at: Instant::now(),
         };
 
-        let games: Vec<Game> = self.games.iter().map(|e| e.game.clone()).collect();
         let assets = self.selected_assets.clone();
-        let grid_dim = self.config.preferred_grid_dimension.clone();
-        let nsfw = self.config.nsfw_filter;
-        let humor = self.config.humor_filter;
-        let force = self.force_download;
+        // A failed replacement leaves the old file in place; retries replace it
+        let force = self.force_download || self.retry_only.is_some();
+        let mut opts = download::DownloadOpts::new(&self.config, Arc::clone(&self.launcher), force, self.resume);
+        opts.retry_only.clone_from(&self.retry_only);
+        // The highlighted game is fetched first
+        opts.priority = self.list_state.selected().and_then(|i| self.games.get(i)).map(|e| e.game.slug.clone());
+        self.cancel = Some(opts.cancel.clone());
+        self.pause = Some(opts.pause.clone());
+        self.paused_since = None;
+        // The summary counts this run's traffic alone
+        crate::api::client::reset_bytes_saved();
+        crate::api::stats::reset();
         let max_conc = self.config.max_concurrent_downloads as usize;
         let api_key = self.config.api_key.clone().unwrap_or_default();
-        let delay = self.config.request_delay_ms;
+        let config = self.config.clone();
         let event_tx = tx.clone();
+        let (key_tx, key_rx) = mpsc::unbounded_channel();
+        self.key_tx = Some(key_tx);
 
         tokio::spawn(async move {
-            let Ok(client) = SteamGridDbClient::new(&api_key, delay) else {
-                return;
-            };
-            let opts = download::DownloadOpts {
-                grid_dim: grid_dim.clone(),
-                nsfw_filter: nsfw,
-                humor_filter: humor,
-                force,
+            let providers = match provider::from_config(&api_key, &config) {
+                Ok(providers) => providers,
+                Err(e) => {
+                    let _ = event_tx.send(AppEvent::Pipeline(PipelineEvent::NotStarted(format!("{e:#}"))));
+                    return;
+                }
             };
-            // Bridge: download_all sends DownloadProgress, we wrap into AppEvent
-            let (dl_tx, mut dl_rx) = mpsc::unbounded_channel::<DownloadProgress>();
+            // Bridge: download_all sends PipelineEvent, we wrap into AppEvent
+            let (dl_tx, mut dl_rx) = mpsc::unbounded_channel::<PipelineEvent>();
 
             let fwd = tokio::spawn({
                 let event_tx = event_tx.clone();
                 async move {
                     while let Some(p) = dl_rx.recv().await {
-                        let _ = event_tx.send(AppEvent::Download(p));
+                        let _ = event_tx.send(AppEvent::Pipeline(p));
                     }
                 }
             });
 
             download::download_all(
-                &client, &games, &assets, &opts, max_conc, dl_tx,
+                &providers, &games, &assets, &opts, max_conc, dl_tx, Some(key_rx),
             )
             .await;
             let _ = fwd.await;
         });
     }
 
-    /// Process a download progress event — update game entry and log.
-    pub fn handle_download_progress(&mut self, progress: &DownloadProgress) {
-        // Special case: API key validation result
-        if progress.game_slug == "__api_key_validation__" {
-            match progress.status {
-                DownloadStatus::Done(_) => {
-                    // Key is valid — save it and advance screen
-                    if let AppScreen::ApiKeyEntry { ref input, .. } = self.screen {
-                        self.config.api_key = Some(input.trim().to_owned());
-                        if let Err(e) = self.config.save() {
-                            self.log(LogLevel::Warn, format!("Could not save config: {e}"));
-                        }
-                    }
-                    self.log(LogLevel::Ok, "API key validated and saved".into());
-                    self.screen = AppScreen::AssetTypeSelection { cursor: 0 };
+    /// Process an event from the download pipeline.
+    pub fn handle_pipeline_event(&mut self, event: &PipelineEvent) {
+        match event {
+            PipelineEvent::Progress(progress) => self.handle_download_progress(progress),
+            PipelineEvent::ChunkFinished { chunk, chunks } => {
+                let AssetCounts { downloaded, skipped, failed } = self.count_results().total();
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Chunk {chunk}/{chunks} checkpointed — {downloaded} downloaded, \
+                         {skipped} skipped, {failed} failed so far"
+                    ),
+                );
+            }
+            PipelineEvent::Warning(msg) => self.log(LogLevel::Warn, msg.clone()),
+            PipelineEvent::Aborted(reason) => {
+                self.log(LogLevel::Error, format!("Downloads stopped: {reason}"));
+                self.log(LogLevel::Info, "Re-run with --resume once it's back".into());
+                if let AppScreen::Downloading { started_at, .. } = self.screen {
+                    self.finish_downloads(started_at, true);
                 }
-                DownloadStatus::Failed(ref msg) => {
+            }
+            PipelineEvent::NotStarted(reason) => {
+                self.log(LogLevel::Error, format!("Could not start downloads: {reason}"));
+                (self.key_tx, self.cancel, self.pause) = (None, None, None);
+                if matches!(self.screen, AppScreen::Downloading { .. }) {
+                    self.screen = AppScreen::GameList;
+                }
+            }
+            PipelineEvent::AuthExpired => {
+                self.log(
+                    LogLevel::Warn,
+                    "SteamGridDB rejected the API key — downloads paused".into(),
+                );
+                if matches!(self.screen, AppScreen::Downloading { .. }) {
+                    self.paused_screen = Some(self.screen.clone());
                     self.screen = AppScreen::ApiKeyEntry {
                         input: String::new(),
                         cursor_pos: 0,
-                        error_msg: Some(format!("Invalid key: {msg}")),
+                        error_msg: Some(
+                            "Your API key was rejected mid-run. Enter a new key to resume, \
+                             or press Esc to stop."
+                                .into(),
+                        ),
                         validating: false,
                     };
                 }
-                _ => {}
             }
+        }
+    }
+
+    /// Act on the result of validating a newly entered API key.
+    fn handle_key_validation(&mut self, status: &DownloadStatus) {
+        match status {
+            DownloadStatus::Done(_) => {
+                // Key is valid — save it and advance screen
+                if let AppScreen::ApiKeyEntry { ref input, .. } = self.screen {
+                    self.config.api_key = Some(input.trim().to_owned());
+                    if let Err(e) = self.config.save() {
+                        self.log(LogLevel::Warn, format!("Could not save config: {e}"));
+                    }
+                }
+                self.log(LogLevel::Ok, "API key validated and saved".into());
+
+                // Rotating mid-run: hand the key to the paused pipeline and go back
+                if let Some(paused) = self.paused_screen.take() {
+                    if let (Some(key_tx), Some(key)) = (&self.key_tx, &self.config.api_key) {
+                        let _ = key_tx.send(key.clone());
+                    }
+                    self.log(LogLevel::Info, "Resuming downloads with the new key".into());
+                    self.screen = paused;
+                } else {
+                    self.screen = AppScreen::AssetTypeSelection { cursor: 0 };
+                }
+            }
+            DownloadStatus::Failed(msg) => {
+                self.screen = AppScreen::ApiKeyEntry {
+                    input: String::new(),
+                    cursor_pos: 0,
+                    error_msg: Some(msg.clone()),
+                    validating: false,
+                };
+            }
+            _ => {}
+        }
+    }
+
+    /// Process a download progress event — update game entry and log.
+    fn handle_download_progress(&mut self, progress: &DownloadProgress) {
+        // Special case: API key validation result
+        if progress.game_slug == "__api_key_validation__" {
+            self.handle_key_validation(&progress.status);
             return;
         }
 
@@ -425,12 +976,21 @@ impl App {
                     format!("Searching for {display_name} ({asset})..."),
                 );
             }
-            DownloadStatus::Downloading => {
+            DownloadStatus::Downloading { received: 0, .. } => {
                 self.log(
                     LogLevel::Info,
                     format!("Downloading {asset} for {display_name}..."),
                 );
             }
+            &DownloadStatus::Downloading { received, total } => {
+                self.transfer = Some(Transfer {
+                    slug: slug.clone(),
+                    game: display_name,
+                    asset,
+                    received,
+                    total,
+                });
+            }
             DownloadStatus::Done(path) => {
                 self.log(
                     LogLevel::Ok,
@@ -452,10 +1012,15 @@ impl App {
             DownloadStatus::Pending => {}
         }
 
-        // Update game entry
-        if let Some(entry) = self.games.iter_mut().find(|e| e.game.slug == *slug) {
+        // Update every entry of the game, as they share their art
+        for entry in self.games.iter_mut().filter(|e| e.game.slug == *slug) {
             *entry.status_mut(asset) = progress.status.clone();
         }
+        if progress.status.is_terminal()
+            && self.transfer.as_ref().is_some_and(|t| t.slug == *slug && t.asset == asset)
+        {
+            self.transfer = None;
+        }
 
         // Update progress counter
         if let AppScreen::Downloading {
@@ -470,36 +1035,80 @@ impl App {
 
             // Check if all done
             if *current >= total {
-                let elapsed = started_at.elapsed().as_secs();
-                let (downloaded, skipped, failed) = self.count_results();
-                self.screen = AppScreen::Done {
-                    downloaded,
-                    skipped,
-                    failed,
-                    elapsed_secs: elapsed,
-                };
+                self.finish_downloads(started_at, false);
             }
         }
     }
 
-    /// Count terminal statuses across all game entries.
-    fn count_results(&self) -> (usize, usize, usize) {
-        let mut downloaded = 0usize;
-        let mut skipped = 0usize;
-        let mut failed = 0usize;
+    /// Abandon a run paused on a rejected key. Dropping the key channel lets the
+    /// pipeline abort, saving the manifest and keeping its checkpoint for `--resume`.
+    fn stop_downloads(&mut self, started_at: Instant) {
+        self.key_tx = None;
+        self.log(
+            LogLevel::Warn,
+            "Downloads stopped — set a new key and re-run with --resume to continue".into(),
+        );
+        self.finish_downloads(started_at, true);
+    }
 
-        for entry in &self.games {
-            for &asset in &self.selected_assets {
-                match entry.status(asset) {
-                    DownloadStatus::Done(_) => downloaded += 1,
-                    DownloadStatus::Skipped(_) => skipped += 1,
-                    DownloadStatus::Failed(_) => failed += 1,
-                    _ => {}
-                }
+    /// Pause or resume the running pipeline. Downloads already under way finish first;
+    /// time spent paused doesn't count towards the elapsed time or the ETA.
+    fn toggle_pause(&mut self) {
+        let Some(ref pause) = self.pause else { return };
+        if let Some(since) = self.paused_since.take() {
+            pause.send_replace(false);
+            if let AppScreen::Downloading { ref mut started_at, .. } = self.screen {
+                *started_at += since.elapsed();
             }
+            self.log(LogLevel::Info, "Downloads resumed".into());
+        } else {
+            pause.send_replace(true);
+            self.paused_since = Some(Instant::now());
+            self.log(
+                LogLevel::Info,
+                "Downloads paused — files already downloading will finish. Press p to resume".into(),
+            );
         }
+    }
+
+    /// Stop the running pipeline, if any, aborting its requests in flight.
+    pub fn cancel_downloads(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel.cancel();
+        }
+    }
+
+    /// Switch to the summary screen.
+    fn finish_downloads(&mut self, started_at: Instant, stopped: bool) {
+        let covered = |slug: &str, asset| {
+            self.selected_assets.contains(&asset) && self.active_games().any(|e| e.game.slug == slug)
+        };
+        if let Err(e) = self.failed_assets().save_merged(covered) {
+            self.log(LogLevel::Warn, format!("Could not save the list of failed assets: {e}"));
+        }
+        let by_asset = self.count_results();
+        let AssetCounts { downloaded, skipped, failed } = by_asset.total();
+        self.screen = AppScreen::Done {
+            downloaded,
+            skipped,
+            failed,
+            by_asset,
+            elapsed_secs: started_at.elapsed().as_secs(),
+            bytes_saved: crate::api::client::bytes_saved(),
+            api: crate::api::stats::snapshot(),
+            stopped,
+        };
+    }
 
-        (downloaded, skipped, failed)
+    /// Count terminal statuses across all game entries, by asset type.
+    fn count_results(&self) -> RunCounts {
+        let mut counts = RunCounts::default();
+        for entry in unique_slugs(self.games.iter()) {
+            for &asset in &self.selected_assets {
+                counts.record(asset, entry.status(asset));
+            }
+        }
+        counts
     }
 
     /// Calculate overall progress as a ratio [0.0, 1.0].
@@ -516,15 +1125,64 @@ impl App {
         }
     }
 
-    /// Append a log message.
+    /// Estimated seconds for a full run over the current game list, from run history.
+    pub fn estimate_run_secs(&self) -> Option<f64> {
+        let games: Vec<Game> = self.active_games().map(|e| e.game.clone()).collect();
+        self.history
+            .estimate_run(&*self.launcher, &games, &self.selected_assets, self.force_download)
+    }
+
+    /// Estimated seconds until the current run finishes.
+    ///
+    /// Uses historical per-asset timings for the work that's left, falling back to
+    /// linear extrapolation from elapsed time when there's no history yet.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn eta_secs(&self) -> Option<f64> {
+        let AppScreen::Downloading {
+            current,
+            total,
+            started_at,
+        } = self.screen
+        else {
+            return None;
+        };
+
+        let unsearched = self
+            .active_games()
+            .filter(|e| {
+                self.selected_assets
+                    .iter()
+                    .all(|&a| matches!(e.status(a), DownloadStatus::Pending))
+            })
+            .count();
+        let remaining = self.active_games().flat_map(|e| {
+            self.run_assets(e).filter(move |&a| {
+                !e.status(a).is_terminal()
+                    && (self.force_download || !download::asset_exists(&*self.launcher, a, &e.game.slug))
+            })
+        });
+        if let Some(secs) = self.history.estimate_secs(unsearched, remaining) {
+            return Some(secs);
+        }
+
+        (current > 0).then(|| {
+            let per_item = started_at.elapsed().as_secs_f64() / current as f64;
+            per_item * total.saturating_sub(current) as f64
+        })
+    }
+
+    /// Append a log message, dropping the oldest entries beyond `MAX_LOG_ENTRIES`.
     pub fn log(&mut self, level: LogLevel, message: String) {
+        if self.log.len() >= MAX_LOG_ENTRIES {
+            self.log.drain(..self.log.len() + 1 - MAX_LOG_ENTRIES);
+        }
         self.log.push((level, message));
     }
 }
 
 /// Validate an API key and save it to config if valid (called from spawned task).
-async fn validate_and_store_key(api_key: String) -> Result<()> {
-    let client = SteamGridDbClient::new(&api_key, 0)?;
+async fn validate_and_store_key(api_key: String, config: &Config) -> Result<()> {
+    let client = SteamGridDbClient::new(&api_key, config)?;
     let valid = client.validate_key().await?;
     if valid {
         let mut config = Config::load()?;
@@ -532,6 +1190,124 @@ async fn validate_and_store_key(api_key: String) -> Result<()> {
         config.save()?;
         Ok(())
     } else {
-        Err(color_eyre::eyre::eyre!("API key rejected by SteamGridDB"))
+        Err(color_eyre::eyre::eyre!("API key rejected by SteamGridDB — check it and try again"))
+    }
+}
+
+/// The first entry of each slug. A game added under several runners is listed once per
+/// runner, but its art is fetched once, so it's counted once.
+fn unique_slugs<'a>(entries: impl Iterator<Item = &'a GameEntry>) -> impl Iterator<Item = &'a GameEntry> {
+    let mut seen = HashSet::new();
+    entries.filter(move |e| seen.insert(e.game.slug.as_str()))
+}
+
+/// Move a list cursor one step, wrapping past either end when `wrap` is set.
+fn step(index: usize, len: usize, forward: bool, wrap: bool) -> usize {
+    let last = len.saturating_sub(1);
+    match (forward, wrap) {
+        (true, true) if index >= last => 0,
+        (false, true) if index == 0 => last,
+        (true, _) => (index + 1).min(last),
+        (false, _) => index.saturating_sub(1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::lutris::Lutris;
+    use crate::provider::ArtProvider;
+    use crate::provider::fixture::{self, FixtureProvider};
+
+    #[test]
+    fn reloading_the_library_keeps_statuses() {
+        fixture::sandbox();
+        let games = vec![fixture::game("fixture-kept", "Kept"), fixture::game("fixture-gone", "Gone")];
+        let mut app = App::new(Config::default(), Arc::new(Lutris), games, HashSet::from([AssetType::Grid]), false);
+        app.games[0].grid_status = DownloadStatus::Skipped("already exists".into());
+
+        let new = Game { installed: false, ..fixture::game("fixture-new", "New") };
+        app.refresh_games(vec![fixture::game("fixture-kept", "Kept"), new]);
+        let slugs: Vec<&str> = app.games.iter().map(|e| e.game.slug.as_str()).collect();
+        assert_eq!(slugs, ["fixture-kept"]);
+        assert!(matches!(app.games[0].grid_status, DownloadStatus::Skipped(_)));
+        assert_eq!(app.hidden_uninstalled.len(), 1);
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn showing_uninstalled_games_keeps_the_selection() {
+        fixture::sandbox();
+        let games = vec![
+            fixture::game("fixture-b", "B"),
+            Game { installed: false, ..fixture::game("fixture-a", "A") },
+            fixture::game("fixture-c", "C"),
+        ];
+        let mut app = App::new(Config::default(), Arc::new(Lutris), games, HashSet::from([AssetType::Grid]), false);
+        app.list_state.select(Some(1));
+        let selected = |app: &App| app.games[app.list_state.selected().unwrap()].game.slug.clone();
+        assert_eq!(selected(&app), "fixture-c");
+
+        app.toggle_uninstalled();
+        assert_eq!(app.games.len(), 3);
+        assert_eq!(selected(&app), "fixture-c");
+        app.toggle_uninstalled();
+        assert_eq!(selected(&app), "fixture-c");
+    }
+
+    #[test]
+    fn most_played_puts_unplayed_games_last() {
+        let played = |slug, hours| Game { playtime: hours, ..fixture::game(slug, slug) };
+        let mut games = vec![played("never", None), played("little", Some(0.5)), played("lots", Some(120.0))];
+        SortOrder::MostPlayed.sort(&mut games, &Lutris, |g| g);
+        let slugs: Vec<&str> = games.iter().map(|g| g.slug.as_str()).collect();
+        assert_eq!(slugs, ["lots", "little", "never"]);
+    }
+
+    #[test]
+    fn a_run_that_cannot_start_returns_to_the_list() {
+        fixture::sandbox();
+        let mut app = App::new(Config::default(), Arc::new(Lutris), vec![fixture::game("fixture-unstarted", "Unstarted")], HashSet::from([AssetType::Grid]), false);
+        app.screen = AppScreen::Downloading { current: 0, total: 1, started_at: Instant::now() };
+
+        app.handle_pipeline_event(&PipelineEvent::NotStarted("IGDB needs igdb_client_id".into()));
+        assert!(matches!(app.screen, AppScreen::GameList), "{:?}", app.screen);
+        assert!(app.log.iter().any(|(_, line)| line.contains("igdb_client_id")));
+    }
+
+    #[tokio::test]
+    async fn pipeline_events_carry_the_app_to_the_summary() {
+        fixture::sandbox();
+        let _turn = fixture::pipeline_turn().await;
+        let games = vec![fixture::game("fixture-tui", "TUI")];
+        let assets = HashSet::from([AssetType::Grid]);
+        let config = Config {
+            api_key: Some("key".into()),
+            ..Config::default()
+        };
+        let mut app = App::new(config, Arc::new(Lutris), games.clone(), assets.clone(), false);
+        app.screen = AppScreen::Downloading {
+            current: 0,
+            total: 1,
+            started_at: Instant::now(),
+        };
+
+        let provider = FixtureProvider::default()
+            .with_game("fixture-tui", 3)
+            .with_art(3, AssetType::Grid, &fixture::png(1, 1));
+        let providers: Vec<Box<dyn ArtProvider>> = vec![Box::new(provider)];
+        let opts = download::DownloadOpts::new(&app.config, Arc::clone(&app.launcher), false, false);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        download::download_all(&providers, &games, &assets, &opts, 1, tx, None).await;
+        while let Ok(event) = rx.try_recv() {
+            app.handle_pipeline_event(&event);
+        }
+
+        assert!(
+            matches!(app.screen, AppScreen::Done { downloaded: 1, failed: 0, stopped: false, .. }),
+            "{:?}",
+            app.screen
+        );
+        assert!(app.transfer.is_none());
     }
 }
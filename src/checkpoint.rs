@@ -0,0 +1,131 @@
+/// Run checkpoints — which games a run has already finished.
+///
+/// Written after every chunk of games so a crash or Ctrl+C near the end of a huge
+/// library doesn't lose hours of work; `--resume` skips the games recorded here.
+/// Within a chunk, the [`Journal`] records each asset as it finishes, so resuming
+/// doesn't look those up again either. Both files are removed once a run completes.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Mutex, PoisonError};
+
+use color_eyre::eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::AssetType;
+use crate::config;
+
+/// Games finished by the current (or last interrupted) run.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Slugs of games whose assets were all saved or skipped; a game with an asset that
+    /// failed isn't done.
+    pub completed: BTreeSet<String>,
+}
+
+impl Checkpoint {
+    /// Location of the checkpoint file.
+    fn path() -> Result<PathBuf> {
+        Ok(config::app_data_dir()?.join("checkpoint.json"))
+    }
+
+    /// Load the last checkpoint, or an empty one if none exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint exists but cannot be read or parsed.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .wrap_err_with(|| format!("Failed to read checkpoint {}", path.display()))?;
+        serde_json::from_str(&content).wrap_err_with(|| format!("Failed to parse checkpoint {}", path.display()))
+    }
+
+    /// Persist the checkpoint, replacing any previous one.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).wrap_err("Failed to create data directory")?;
+        }
+        let content = serde_json::to_string(self).wrap_err("Failed to serialize checkpoint")?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, content)
+            .wrap_err_with(|| format!("Failed to write checkpoint to {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path).wrap_err("Failed to move checkpoint into place")?;
+        Ok(())
+    }
+
+    /// Remove the checkpoint after a run completes.
+    pub fn clear() {
+        if let Ok(path) = Self::path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Append-only log of the (game, asset) pairs a run has finished, one JSON line each.
+pub struct Journal {
+    /// The open journal; `None` if it couldn't be opened, in which case nothing is
+    /// recorded.
+    file: Mutex<Option<File>>,
+}
+
+impl Journal {
+    /// Location of the journal file.
+    fn path() -> Result<PathBuf> {
+        Ok(config::app_data_dir()?.join("journal.jsonl"))
+    }
+
+    /// Open the journal for a run — appending to the last one when resuming it,
+    /// otherwise starting afresh.
+    pub fn open(resume: bool) -> Self {
+        let file = Self::path().ok().and_then(|path| {
+            std::fs::create_dir_all(path.parent()?).ok()?;
+            File::options()
+                .create(true)
+                .append(resume)
+                .write(true)
+                .truncate(!resume)
+                .open(path)
+                .ok()
+        });
+        Self { file: Mutex::new(file) }
+    }
+
+    /// Note that an asset of a game is finished.
+    pub fn record(&self, slug: &str, asset: AssetType) {
+        let mut file = self.file.lock().unwrap_or_else(PoisonError::into_inner);
+        if let (Some(file), Ok(line)) = (file.as_mut(), serde_json::to_string(&(slug, asset))) {
+            let _ = writeln!(file, "{line}");
+        }
+    }
+
+    /// The pairs the last run finished, by game slug. Lines that don't parse — a write
+    /// cut short by a crash — are skipped.
+    pub fn load() -> BTreeMap<String, BTreeSet<AssetType>> {
+        let mut finished: BTreeMap<String, BTreeSet<AssetType>> = BTreeMap::new();
+        let content = Self::path()
+            .ok()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .unwrap_or_default();
+        for (slug, asset) in content.lines().filter_map(|l| serde_json::from_str(l).ok()) {
+            finished.entry(slug).or_default().insert(asset);
+        }
+        finished
+    }
+
+    /// Remove the journal after a run completes.
+    pub fn clear() {
+        if let Ok(path) = Self::path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
@@ -2,20 +2,42 @@
 ///
 /// Handles loading/saving the TOML config file at `~/.config/lutrisartfetcher/config.toml`
 /// and resolving Lutris XDG paths for the database and asset directories.
-use std::path::PathBuf;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 
 use color_eyre::eyre::{Context, Result, eyre};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::db::{Exclusions, Game};
+use crate::provider::Source;
+use crate::heuristics;
+use crate::launcher::Launcher;
 
 /// Application configuration persisted as TOML.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[allow(clippy::struct_excessive_bools)]
 pub struct Config {
     /// `SteamGridDB` API key (Bearer token).
     pub api_key: Option<String>,
 
-    /// Preferred grid dimension string, e.g. `"600x900"`.
-    #[serde(default = "default_grid_dimension")]
-    pub preferred_grid_dimension: String,
+    /// Grid dimensions to try in order, e.g. `["600x900", "342x482"]`. A single string is
+    /// accepted too; an empty list allows any size.
+    #[serde(default = "default_grid_dimension", deserialize_with = "string_or_list")]
+    pub preferred_grid_dimension: Vec<String>,
+
+    /// Banner dimensions to try in order; banners are wide grids.
+    #[serde(default = "default_banner_dimension", deserialize_with = "string_or_list")]
+    pub preferred_banner_dimension: Vec<String>,
+
+    /// Hero dimensions to try in order, e.g. `["3840x1240", "1920x620"]`; empty allows any.
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub preferred_hero_dimension: Vec<String>,
+
+    /// Icon sizes to try in order, e.g. `["256", "128"]`; empty allows any. `SteamGridDB`
+    /// has no size filter for logos, which come in free-form sizes.
+    #[serde(default, deserialize_with = "string_or_list")]
+    pub preferred_icon_dimension: Vec<String>,
 
     /// Maximum number of concurrent download tasks.
     #[serde(default = "default_concurrency")]
@@ -29,13 +51,381 @@ pub struct Config {
     #[serde(default = "default_true")]
     pub humor_filter: bool,
 
-    /// Delay in milliseconds between `SteamGridDB` API requests (rate-limit protection).
+    /// Filter out assets flagged as a photosensitive-epilepsy risk.
+    #[serde(default = "default_true")]
+    pub epilepsy_filter: bool,
+
+    /// Uploaders (Steam64 IDs) whose art is picked over anyone else's when available.
+    #[serde(default)]
+    pub preferred_uploaders: Vec<String>,
+
+    /// Uploaders (Steam64 IDs) whose art is never picked.
+    #[serde(default)]
+    pub blocked_uploaders: Vec<String>,
+
+    /// `SteamGridDB` asset IDs that are never picked, e.g. meme art that keeps winning.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub blocked_assets: BTreeSet<u64>,
+
+    /// `SteamGridDB` game IDs whose art is never used; games that match one are treated
+    /// as not found there and fall through to the other sources.
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub excluded_game_ids: BTreeSet<u64>,
+
+    /// `SteamGridDB` game IDs to use for Lutris slugs, for games the lookup keeps getting
+    /// wrong; no search is made for them.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, u64>,
+
+    /// `SteamGridDB` collection whose art is used ahead of everything else on the site
+    /// for the games it covers. The content filters apply to it; the dimensions don't.
+    /// Only the first `max_asset_pages` pages of it are read.
+    pub collection_id: Option<u64>,
+
+    /// Extra query parameters for `SteamGridDB` asset lists, per asset type — for
+    /// filters without a setting of their own, e.g. `oneoftag` for grids.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extra_query_params: BTreeMap<String, BTreeMap<String, String>>,
+
+    /// Lowest name similarity (0–1) at which a search result is accepted as the game;
+    /// anything below fails as an ambiguous match instead of fetching the wrong art.
+    #[serde(default = "default_min_match_confidence")]
+    pub min_match_confidence: f64,
+
+    /// How to pick among the acceptable candidates for an asset.
+    #[serde(default)]
+    pub ranking: Ranking,
+
+    /// Also download animated (WebP/APNG) grids, heroes, and logos.
+    #[serde(default)]
+    pub allow_animated: bool,
+
+    /// Transcode WebP and APNG grids, heroes, and logos to PNG (or JPEG, when they have no
+    /// transparency), which Lutris displays reliably. Animated art keeps only its first
+    /// frame.
+    #[serde(default)]
+    pub convert_images: bool,
+
+    /// Downscale grids and heroes bigger than their first preferred dimension (600x900
+    /// and 1920x620 without one): `fit` keeps the aspect ratio, `crop` fills the
+    /// dimension exactly and cuts off the overhang around the center.
+    #[serde(default)]
+    pub resize_art: ResizeArt,
+
+    /// Minimum spacing in milliseconds between `SteamGridDB` API requests, shared by all
+    /// concurrent downloads (rate-limit protection).
     #[serde(default = "default_request_delay")]
     pub request_delay_ms: u64,
+
+    /// Requests allowed back-to-back after an idle spell before the spacing applies.
+    #[serde(default = "default_request_burst")]
+    pub request_burst: u32,
+
+    /// Fall back to GOG's own store art for GOG games `SteamGridDB` has nothing for.
+    #[serde(default = "default_true")]
+    pub gog_store_art: bool,
+
+    /// Fall back to Steam's own store art for Steam games `SteamGridDB` has nothing for.
+    #[serde(default = "default_true")]
+    pub steam_cdn_fallback: bool,
+
+    /// Whole-request timeout for API calls, in seconds; image downloads get twice this.
+    #[serde(default = "default_http_timeout_secs")]
+    pub http_timeout_secs: u64,
+
+    /// Timeout for establishing a connection, in seconds.
+    #[serde(default = "default_connect_timeout_secs")]
+    pub connect_timeout_secs: u64,
+
+    /// How long cached search results and asset lists stay valid, in hours; `0` disables
+    /// the cache.
+    #[serde(default = "default_cache_ttl_hours")]
+    pub cache_ttl_hours: u64,
+
+    /// Retries for rate-limited (429), failed (5xx), or timed-out requests.
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+
+    /// First retry delay in milliseconds; doubles with each further retry.
+    #[serde(default = "default_retry_base_delay")]
+    pub retry_base_delay_ms: u64,
+
+    /// Free space (in MiB) on the art filesystem below which the pipeline switches to
+    /// preferring smaller images. `0` disables the check.
+    #[serde(default = "default_low_disk_space_mb")]
+    pub low_disk_space_mb: u64,
+
+    /// Free space (in MiB) a run must leave on the Lutris data filesystem, going by its
+    /// estimated download size; runs that wouldn't are refused up front. `0` (the
+    /// default) disables it, leaving only the `low_disk_space_mb` warning.
+    #[serde(default = "default_min_free_space_mb")]
+    pub min_free_space_mb: u64,
+
+    /// Pages of up to 50 candidates considered per asset type; each page is one API call,
+    /// and further pages are only requested when the previous one was full.
+    #[serde(default = "default_max_asset_pages")]
+    pub max_asset_pages: u32,
+
+    /// Number of games processed between checkpoints and progress summaries. `0` disables
+    /// chunking.
+    #[serde(default = "default_chunk_size")]
+    pub chunk_size: usize,
+
+    /// Games in a row whose every API request failed before the run is abandoned as the
+    /// art sources being unreachable. `0` never gives up.
+    #[serde(default = "default_max_failure_streak")]
+    pub max_failure_streak: u32,
+
+    /// Further passes, once the run is otherwise done, over assets that failed with a
+    /// timeout, connection error, or 429/5xx that outlasted `max_retries`. `0` fails
+    /// them straight away.
+    #[serde(default = "default_failed_asset_retries")]
+    pub failed_asset_retries: u32,
+
+    /// `SteamGridDB` API root — override to route through a caching proxy or mirror.
+    #[serde(default = "default_api_base_url")]
+    pub api_base_url: String,
+
+    /// Scheme + host (and optional path prefix) that replaces the CDN host in image URLs.
+    pub cdn_base_url: Option<String>,
+
+    /// Proxy for all traffic (`http://`, `https://`, `socks5://`, or `socks5h://`).
+    /// When unset, `HTTP_PROXY` / `HTTPS_PROXY` / `ALL_PROXY` are respected.
+    pub proxy_url: Option<String>,
+
+    /// `User-Agent` sent with every request. Forks should name themselves here; the
+    /// default identifies this project, as `SteamGridDB` asks of API consumers.
+    pub user_agent: Option<String>,
+
+    /// Twitch app credentials for the IGDB cover provider; with both set, IGDB is tried
+    /// after the other providers.
+    pub igdb_client_id: Option<String>,
+    pub igdb_client_secret: Option<String>,
+
+    /// Slugs of games that should never have art fetched (toggled with `x` in the TUI).
+    #[serde(default)]
+    pub excluded_games: BTreeSet<String>,
+
+    /// Skip entries that look like launchers or runner tools rather than games.
+    #[serde(default = "default_true")]
+    pub auto_exclude_non_games: bool,
+
+    /// Slugs that are always processed, even if the non-game heuristics or category filters
+    /// match.
+    #[serde(default)]
+    pub included_games: BTreeSet<String>,
+
+    /// Only process games in at least one of these Lutris categories; empty means all.
+    #[serde(default)]
+    pub categories: BTreeSet<String>,
+
+    /// Never process games in any of these Lutris categories, e.g. `.hidden`.
+    #[serde(default)]
+    pub excluded_categories: BTreeSet<String>,
+
+    /// Leave games with any of these Lutris runners out of the library altogether, e.g.
+    /// `browser` for web shortcuts.
+    #[serde(default)]
+    pub excluded_runners: BTreeSet<String>,
+
+    /// Leave games from any of these Lutris services out of the library altogether,
+    /// e.g. `humblebundle`.
+    #[serde(default)]
+    pub excluded_services: BTreeSet<String>,
+
+    /// Also fetch art for games in the library that aren't installed, e.g. a synced GOG
+    /// library. The TUI can show or hide them either way.
+    #[serde(default)]
+    pub include_uninstalled: bool,
+
+    /// Jump to the other end of a list when moving past its first or last entry.
+    #[serde(default)]
+    pub wrap_navigation: bool,
+
+    /// Leave grids and banners the user set in Lutris alone, even with `--force`.
+    #[serde(default = "default_true")]
+    pub respect_custom_art: bool,
+
+    /// After replacing grids and banners set in Lutris, clear their custom-art flags in
+    /// `pga.db` (backed up first) so Lutris shows the new files.
+    #[serde(default)]
+    pub clear_custom_art_flags: bool,
+
+    /// Where icons are installed — Lutris versions differ in where they look.
+    #[serde(default)]
+    pub icon_target: IconTarget,
+
+    /// With the hicolor icon target, also install each icon at the theme's other common
+    /// sizes, scaled down from the download (never up).
+    #[serde(default = "default_true")]
+    pub hicolor_icon_sizes: bool,
+
+    /// Run `gtk-update-icon-cache` on the hicolor theme after a run that saved icons, so
+    /// desktop menus show them without logging out.
+    #[serde(default)]
+    pub update_icon_cache: bool,
+
+    /// Flush each saved image (and its directory) to disk before and after moving it into
+    /// place, so a power cut can't leave an empty file behind. Slower, especially on
+    /// spinning disks.
+    #[serde(default)]
+    pub durable_writes: bool,
+
+    /// Launcher to fetch art for: `lutris`, `heroic`, `bottles`, `directory`, or `steam`.
+    #[serde(default)]
+    pub launcher: Launcher,
+
+    /// Heroic's data directory; found automatically when unset.
+    pub heroic_dir: Option<PathBuf>,
+
+    /// Bottles' directory of bottles; found automatically when unset.
+    pub bottles_dir: Option<PathBuf>,
+
+    /// Directory of game folders, one per game, for the `directory` launcher. Also used
+    /// when there's no Lutris database.
+    pub games_dir: Option<PathBuf>,
+
+    /// Which Lutris install to use — native or Flatpak. Detected from where `pga.db` is
+    /// by default.
+    #[serde(default)]
+    pub lutris_install: LutrisInstall,
+
+    /// Lutris database to read games from, instead of the install's `pga.db`.
+    pub lutris_db_path: Option<PathBuf>,
+
+    /// Save grids, banners, heroes, and logos (and icons, with the `lutris` icon target)
+    /// under this directory instead of Lutris's, in the same layout.
+    pub output_dir: Option<PathBuf>,
+
+    /// Also copy grids, banners, heroes, and logos into Steam's grid folder for games
+    /// added to Steam as non-Steam shortcuts.
+    #[serde(default)]
+    pub steam_grid_export: bool,
+
+    /// Steam installation to export to; found automatically when unset.
+    pub steam_dir: Option<PathBuf>,
+
+    /// Record HTTP traffic into this directory (`--capture`; never persisted).
+    #[serde(skip)]
+    pub capture_dir: Option<PathBuf>,
+
+    /// Serve HTTP responses from a capture in this directory (`--replay`; never persisted).
+    #[serde(skip)]
+    pub replay_dir: Option<PathBuf>,
+
+    /// Use only this provider instead of the fallback chain (`--source`; never persisted).
+    #[serde(skip)]
+    pub source: Option<Source>,
+
+    /// Replace saved art where better art is available now (`--update`; never persisted).
+    #[serde(skip)]
+    pub update: bool,
+}
+
+/// Strategy for choosing among acceptable assets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Ranking {
+    /// The API's own order.
+    #[default]
+    Api,
+    /// Highest community score.
+    Score,
+    /// Most upvotes net of downvotes.
+    Votes,
+    /// Most recently uploaded.
+    Newest,
+    /// Exactly the requested dimensions first.
+    ExactSize,
+}
+
+/// How oversized grids and heroes are shrunk to the preferred dimensions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResizeArt {
+    /// Saved as downloaded.
+    #[default]
+    Off,
+    /// Scaled down to fit within the dimensions.
+    Fit,
+    /// Scaled down to cover the dimensions, then center-cropped to them.
+    Crop,
+}
+
+/// Icon install location.
+///
+/// In TOML: `icon_target = "hicolor"`, `icon_target = "lutris"`, or
+/// `icon_target = { custom = "/some/dir" }`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IconTarget {
+    /// The user's hicolor icon theme: `$XDG_DATA_HOME/icons/hicolor/128x128/apps/`.
+    #[default]
+    Hicolor,
+    /// Lutris's own data directory, `$XDG_DATA_HOME/lutris/icons/` (or `output_dir`'s).
+    Lutris,
+    /// Any other directory.
+    Custom(PathBuf),
+}
+
+/// Lutris install whose database and data directories are used.
+///
+/// In TOML: `lutris_install = "auto"`, `"native"`, or `"flatpak"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LutrisInstall {
+    /// The native install if it has a database, else the Flatpak one if that does.
+    #[default]
+    Auto,
+    /// Lutris from the distribution: data under `$XDG_DATA_HOME/`.
+    Native,
+    /// The `net.lutris.Lutris` Flatpak: data under `~/.var/app/net.lutris.Lutris/data/`.
+    Flatpak,
+}
+
+impl LutrisInstall {
+    /// The data directory this install's Lutris sees as `$XDG_DATA_HOME`.
+    pub fn data_home(self) -> Result<PathBuf> {
+        let native = dirs::data_dir()
+            .ok_or_else(|| eyre!("Cannot determine XDG data directory"))?;
+        let flatpak = dirs::home_dir()
+            .ok_or_else(|| eyre!("Cannot determine home directory"))?
+            .join(".var/app/net.lutris.Lutris/data");
+        Ok(self.pick(native, flatpak))
+    }
+
+    /// Pick between the native and Flatpak data directories.
+    fn pick(self, native: PathBuf, flatpak: PathBuf) -> PathBuf {
+        let has_db = |dir: &Path| dir.join("lutris/pga.db").is_file();
+        match self {
+            Self::Flatpak => flatpak,
+            Self::Auto if !has_db(&native) && has_db(&flatpak) => flatpak,
+            Self::Native | Self::Auto => native,
+        }
+    }
 }
 
-fn default_grid_dimension() -> String {
-    "600x900".to_owned()
+fn default_grid_dimension() -> Vec<String> {
+    vec!["600x900".to_owned(), "342x482".to_owned(), "660x930".to_owned()]
+}
+
+fn default_banner_dimension() -> Vec<String> {
+    vec!["920x430".to_owned(), "460x215".to_owned()]
+}
+
+/// Accept either a single string or a list, so configs written before dimension
+/// fallbacks existed keep working.
+fn string_or_list<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(s) => vec![s],
+        OneOrMany::Many(v) => v,
+    })
 }
 
 const fn default_concurrency() -> u8 {
@@ -50,19 +440,142 @@ const fn default_request_delay() -> u64 {
     100
 }
 
+const fn default_request_burst() -> u32 {
+    1
+}
+
+const fn default_min_match_confidence() -> f64 {
+    0.6
+}
+
+const fn default_http_timeout_secs() -> u64 {
+    30
+}
+
+const fn default_connect_timeout_secs() -> u64 {
+    10
+}
+
+const fn default_cache_ttl_hours() -> u64 {
+    24
+}
+
+const fn default_max_retries() -> u32 {
+    3
+}
+
+const fn default_retry_base_delay() -> u64 {
+    500
+}
+
+const fn default_low_disk_space_mb() -> u64 {
+    500
+}
+
+const fn default_min_free_space_mb() -> u64 {
+    0
+}
+
+const fn default_max_asset_pages() -> u32 {
+    2
+}
+
+const fn default_chunk_size() -> usize {
+    200
+}
+
+const fn default_failed_asset_retries() -> u32 {
+    1
+}
+
+const fn default_max_failure_streak() -> u32 {
+    10
+}
+
+fn default_api_base_url() -> String {
+    crate::api::client::DEFAULT_BASE_URL.to_owned()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             api_key: None,
             preferred_grid_dimension: default_grid_dimension(),
+            preferred_banner_dimension: default_banner_dimension(),
+            preferred_hero_dimension: Vec::new(),
+            preferred_icon_dimension: Vec::new(),
             max_concurrent_downloads: default_concurrency(),
             nsfw_filter: true,
             humor_filter: true,
+            epilepsy_filter: true,
+            preferred_uploaders: Vec::new(),
+            blocked_uploaders: Vec::new(),
+            blocked_assets: BTreeSet::new(),
+            excluded_game_ids: BTreeSet::new(),
+            overrides: BTreeMap::new(),
+            collection_id: None,
+            extra_query_params: BTreeMap::new(),
+            min_match_confidence: default_min_match_confidence(),
+            ranking: Ranking::default(),
+            allow_animated: false,
+            convert_images: false,
+            resize_art: ResizeArt::default(),
             request_delay_ms: default_request_delay(),
+            request_burst: default_request_burst(),
+            gog_store_art: true,
+            steam_cdn_fallback: true,
+            http_timeout_secs: default_http_timeout_secs(),
+            connect_timeout_secs: default_connect_timeout_secs(),
+            cache_ttl_hours: default_cache_ttl_hours(),
+            max_retries: default_max_retries(),
+            retry_base_delay_ms: default_retry_base_delay(),
+            low_disk_space_mb: default_low_disk_space_mb(),
+            min_free_space_mb: default_min_free_space_mb(),
+            max_asset_pages: default_max_asset_pages(),
+            chunk_size: default_chunk_size(),
+            max_failure_streak: default_max_failure_streak(),
+            failed_asset_retries: default_failed_asset_retries(),
+            api_base_url: default_api_base_url(),
+            cdn_base_url: None,
+            proxy_url: None,
+            user_agent: None,
+            igdb_client_id: None,
+            igdb_client_secret: None,
+            excluded_games: BTreeSet::new(),
+            auto_exclude_non_games: true,
+            included_games: BTreeSet::new(),
+            categories: BTreeSet::new(),
+            excluded_categories: BTreeSet::new(),
+            excluded_runners: BTreeSet::new(),
+            excluded_services: BTreeSet::new(),
+            include_uninstalled: false,
+            wrap_navigation: false,
+            respect_custom_art: true,
+            clear_custom_art_flags: false,
+            icon_target: IconTarget::default(),
+            hicolor_icon_sizes: true,
+            update_icon_cache: false,
+            durable_writes: false,
+            launcher: Launcher::default(),
+            heroic_dir: None,
+            bottles_dir: None,
+            games_dir: None,
+            lutris_install: LutrisInstall::default(),
+            lutris_db_path: None,
+            output_dir: None,
+            steam_grid_export: false,
+            steam_dir: None,
+            capture_dir: None,
+            replay_dir: None,
+            source: None,
+            update: false,
         }
     }
 }
 
+/// Asset list parameters the app sets itself, from other settings or for paging.
+const RESERVED_QUERY_PARAMS: [&str; 7] = ["page", "limit", "dimensions", "types", "nsfw", "humor", "epilepsy"];
+
 impl Config {
     /// Load configuration from disk. Creates a default config file if none exists.
     ///
@@ -97,6 +610,95 @@ impl Config {
         }
     }
 
+    /// Check settings that can't be expressed through serde defaults alone.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error naming the first invalid setting.
+    pub fn validate(&self) -> Result<()> {
+        if self.http_timeout_secs == 0 || self.connect_timeout_secs == 0 {
+            return Err(eyre!("http_timeout_secs and connect_timeout_secs must be at least 1"));
+        }
+        crate::api::client::parse_endpoint(&self.api_base_url)
+            .wrap_err("Invalid api_base_url in config")?;
+        if let Some(ref cdn) = self.cdn_base_url {
+            crate::api::client::parse_endpoint(cdn).wrap_err("Invalid cdn_base_url in config")?;
+        }
+        if let Some(ref proxy) = self.proxy_url {
+            crate::api::client::parse_proxy(proxy).wrap_err("Invalid proxy_url in config")?;
+        }
+        if let Some(ref agent) = self.user_agent {
+            reqwest::header::HeaderValue::from_str(agent).wrap_err("Invalid user_agent in config")?;
+        }
+        for (asset, params) in &self.extra_query_params {
+            asset
+                .parse::<crate::api::models::AssetType>()
+                .wrap_err_with(|| format!("Invalid extra_query_params in config: no asset type '{asset}'"))?;
+            if let Some(name) = params.keys().find(|n| RESERVED_QUERY_PARAMS.contains(&n.as_str())) {
+                return Err(eyre!(
+                    "extra_query_params can't set '{name}': it has its own setting or is managed by the app"
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Path to the Lutris database: `lutris_db_path` if set, otherwise Lutris's own.
+    pub fn db_path(&self) -> Result<PathBuf> {
+        self.lutris_db_path.clone().map_or_else(|| Ok(self.lutris_data_dir()?.join("pga.db")), Ok)
+    }
+
+    /// Lutris data directory: `$XDG_DATA_HOME/lutris/`, or the Flatpak's.
+    pub fn lutris_data_dir(&self) -> Result<PathBuf> {
+        Ok(self.lutris_install.data_home()?.join("lutris"))
+    }
+
+    /// Directories Lutris keeps its per-game YAML configs in: `games/` under its data
+    /// directory (newer Lutris) and under its config directory (older Lutris), or the
+    /// Flatpak's.
+    pub fn lutris_game_config_dirs(&self) -> Result<Vec<PathBuf>> {
+        let data_home = self.lutris_install.data_home()?;
+        // The Flatpak keeps its config next to its data
+        let config_home = if data_home.ends_with("net.lutris.Lutris/data") {
+            data_home.with_file_name("config")
+        } else {
+            dirs::config_dir().ok_or_else(|| eyre!("Cannot determine XDG config directory"))?
+        };
+        Ok(vec![data_home.join("lutris/games"), config_home.join("lutris/games")])
+    }
+
+    /// Where art is written, for the configured Lutris install.
+    pub fn art_paths(&self) -> Result<ArtPaths> {
+        let data_home = self.lutris_install.data_home()?;
+        Ok(ArtPaths {
+            art: self.output_dir.clone().unwrap_or_else(|| data_home.join("lutris")),
+            icon_target: self.icon_target.clone(),
+            hicolor: data_home.join("icons/hicolor"),
+            icon_fallback: data_home.join("icons"),
+        })
+    }
+
+    /// The runners and services whose games aren't read from the Lutris database.
+    pub fn exclusions(&self) -> Exclusions {
+        Exclusions {
+            runners: self.excluded_runners.clone(),
+            services: self.excluded_services.clone(),
+        }
+    }
+
+    /// Whether art fetching is disabled for this game.
+    pub fn excludes(&self, game: &Game) -> bool {
+        if self.included_games.contains(&game.slug) {
+            return false;
+        }
+        self.excluded_games.contains(&game.slug)
+            || game.categories.iter().any(|c| self.excluded_categories.contains(c))
+            || (!self.categories.is_empty()
+                && !game.categories.iter().any(|c| self.categories.contains(c)))
+            || (self.auto_exclude_non_games
+                && heuristics::is_probably_not_a_game(&game.name, &game.slug))
+    }
+
     /// Persist the current configuration to disk.
     ///
     /// # Errors
@@ -139,30 +741,115 @@ pub fn config_path() -> PathBuf {
     config_dir().join("config.toml")
 }
 
-/// Lutris XDG data directory: `$XDG_DATA_HOME/lutris/`
-pub fn lutris_data_dir() -> Result<PathBuf> {
+/// Directory for our own state (checkpoints etc.): `$XDG_DATA_HOME/lutrisartfetcher/`
+pub fn app_data_dir() -> Result<PathBuf> {
     let data = dirs::data_dir()
         .ok_or_else(|| eyre!("Cannot determine XDG data directory"))?;
-    Ok(data.join("lutris"))
+    Ok(data.join("lutrisartfetcher"))
+}
+
+/// Directory holding art replaced by downloads, one subdirectory per run:
+/// `$XDG_DATA_HOME/lutrisartfetcher/backups/`
+pub fn backups_dir() -> Result<PathBuf> {
+    Ok(app_data_dir()?.join("backups"))
+}
+
+/// Directory for cached API responses: `$XDG_CACHE_HOME/lutrisartfetcher/`
+pub fn cache_dir() -> Result<PathBuf> {
+    let cache = dirs::cache_dir()
+        .ok_or_else(|| eyre!("Cannot determine XDG cache directory"))?;
+    Ok(cache.join("lutrisartfetcher"))
+}
+
+/// Where art is written, resolved from a [`Config`] (see [`Config::art_paths`]).
+#[derive(Debug, Clone)]
+pub struct ArtPaths {
+    /// Base directory art is saved under: `output_dir` if set, otherwise Lutris's data
+    /// directory.
+    pub art: PathBuf,
+    /// Where icons go.
+    pub icon_target: IconTarget,
+    /// The user's hicolor icon theme as Lutris sees it.
+    pub hicolor: PathBuf,
+    /// Fallback for icons when the icon directory isn't writable: the `icons/` directory
+    /// the hicolor theme is in. Icon themes look up unthemed icons there, so Lutris still
+    /// finds them.
+    pub icon_fallback: PathBuf,
+}
+
+impl ArtPaths {
+    /// The directory an icon target resolves to.
+    pub fn icon_dir(&self, target: &IconTarget) -> PathBuf {
+        match target {
+            IconTarget::Hicolor => self.hicolor.join("128x128/apps"),
+            IconTarget::Lutris => self.art.join("icons"),
+            IconTarget::Custom(dir) => dir.clone(),
+        }
+    }
+}
+
+/// Art locations from the config, set once at startup.
+static ART_PATHS: OnceLock<ArtPaths> = OnceLock::new();
+
+/// The art locations set at startup, or the default config's.
+fn art_paths() -> Result<ArtPaths> {
+    ART_PATHS.get().cloned().map_or_else(|| Config::default().art_paths(), Ok)
+}
+
+/// Use the configured art locations for the rest of the process. Only the first call
+/// has any effect.
+pub fn set_art_paths(paths: ArtPaths) {
+    let _ = ART_PATHS.set(paths);
 }
 
-/// Path to the Lutris `SQLite` database.
-pub fn lutris_db_path() -> Result<PathBuf> {
-    Ok(lutris_data_dir()?.join("pga.db"))
+/// Base directory art is saved under (see [`ArtPaths::art`]).
+pub fn art_dir() -> Result<PathBuf> {
+    Ok(art_paths()?.art)
 }
 
-/// Resolve the Lutris on-disk directory for a given asset type name.
+/// Resolve the on-disk directory for a given asset type name, under [`art_dir`].
 ///
 /// `subdir` is one of: `"banners"`, `"coverart"`, `"heroes"`, `"logos"`.
 pub fn lutris_asset_dir(subdir: &str) -> Result<PathBuf> {
-    Ok(lutris_data_dir()?.join(subdir))
+    Ok(art_dir()?.join(subdir))
 }
 
-/// Resolve the Lutris icons directory (separate XDG location).
+/// Icon directory picked by the startup preflight, when it differs from the target.
+static ICON_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Resolve the directory icons are written to (see [`IconTarget`]).
 pub fn lutris_icon_dir() -> Result<PathBuf> {
-    let data = dirs::data_dir()
-        .ok_or_else(|| eyre!("Cannot determine XDG data directory"))?;
-    Ok(data.join("icons/hicolor/128x128/apps"))
+    if let Some(dir) = ICON_DIR_OVERRIDE.get() {
+        return Ok(dir.clone());
+    }
+    let paths = art_paths()?;
+    Ok(paths.icon_dir(&paths.icon_target))
+}
+
+/// The user's hicolor icon theme as Lutris sees it (see [`ArtPaths::hicolor`]).
+pub fn hicolor_dir() -> Result<PathBuf> {
+    Ok(art_paths()?.hicolor)
+}
+
+/// The hicolor theme, if that's where icons are being installed — not when the target
+/// is another directory or the preflight redirected them.
+pub fn icon_theme_dir() -> Option<PathBuf> {
+    let paths = art_paths().ok()?;
+    if paths.icon_target != IconTarget::Hicolor || ICON_DIR_OVERRIDE.get().is_some() {
+        return None;
+    }
+    Some(paths.hicolor)
+}
+
+/// Fallback for icons when the configured icon directory isn't writable (see
+/// [`ArtPaths::icon_fallback`]).
+pub fn fallback_icon_dir() -> Result<PathBuf> {
+    Ok(art_paths()?.icon_fallback)
+}
+
+/// Redirect icon writes for the rest of the process. Only the first call has any effect.
+pub fn set_icon_dir_override(dir: PathBuf) {
+    let _ = ICON_DIR_OVERRIDE.set(dir);
 }
 
 #[cfg(test)]
@@ -180,9 +867,101 @@ mod tests {
         assert_eq!(config.nsfw_filter, deserialized.nsfw_filter);
         assert_eq!(config.humor_filter, deserialized.humor_filter);
         assert_eq!(config.request_delay_ms, deserialized.request_delay_ms);
+        assert_eq!(config.api_base_url, deserialized.api_base_url);
         assert!(deserialized.api_key.is_none());
     }
 
+    #[test]
+    fn icon_target_parses_keywords_and_custom_paths() {
+        let config: Config = toml::from_str(r#"icon_target = "lutris""#).unwrap();
+        assert_eq!(config.icon_target, IconTarget::Lutris);
+
+        let config: Config = toml::from_str(r#"icon_target = { custom = "/opt/icons" }"#).unwrap();
+        assert_eq!(config.icon_target, IconTarget::Custom(PathBuf::from("/opt/icons")));
+
+        let config: Config = toml::from_str("").unwrap();
+        assert_eq!(config.icon_target, IconTarget::Hicolor);
+    }
+
+    #[test]
+    fn flatpak_install_is_used_when_only_it_has_a_database() {
+        let dir = std::env::temp_dir().join(format!("lutrisartfetcher-install-{}", std::process::id()));
+        let (native, flatpak) = (dir.join("native"), dir.join("flatpak"));
+        std::fs::create_dir_all(flatpak.join("lutris")).unwrap();
+        std::fs::write(flatpak.join("lutris/pga.db"), b"").unwrap();
+
+        assert_eq!(LutrisInstall::Auto.pick(native.clone(), flatpak.clone()), flatpak);
+        assert_eq!(LutrisInstall::Native.pick(native.clone(), flatpak.clone()), native);
+
+        std::fs::create_dir_all(native.join("lutris")).unwrap();
+        std::fs::write(native.join("lutris/pga.db"), b"").unwrap();
+        assert_eq!(LutrisInstall::Auto.pick(native.clone(), flatpak.clone()), native);
+        assert_eq!(LutrisInstall::Flatpak.pick(native, flatpak.clone()), flatpak);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn paths_follow_the_configured_install() {
+        let config: Config = toml::from_str(r#"lutris_install = "flatpak""#).unwrap();
+        let data = Path::new(".var/app/net.lutris.Lutris/data");
+        assert!(config.db_path().unwrap().ends_with(data.join("lutris/pga.db")));
+        let paths = config.art_paths().unwrap();
+        assert!(paths.art.ends_with(data.join("lutris")));
+        assert!(paths.icon_dir(&IconTarget::Hicolor).ends_with(data.join("icons/hicolor/128x128/apps")));
+        let configs = config.lutris_game_config_dirs().unwrap();
+        assert!(configs[1].ends_with(".var/app/net.lutris.Lutris/config/lutris/games"));
+
+        let config = Config { output_dir: Some(PathBuf::from("/srv/art")), ..config };
+        let paths = config.art_paths().unwrap();
+        assert_eq!(paths.icon_dir(&IconTarget::Lutris), Path::new("/srv/art/icons"));
+        assert!(config.db_path().unwrap().ends_with(data.join("lutris/pga.db")));
+    }
+
+    #[test]
+    fn category_filters_exclude_games() {
+        let game = |categories: &[&str]| Game {
+            categories: categories.iter().map(|&c| c.to_owned()).collect(),
+            ..crate::provider::fixture::game("celeste", "Celeste")
+        };
+        let mut config = Config { excluded_categories: BTreeSet::from([".hidden".into()]), ..Config::default() };
+        assert!(!config.excludes(&game(&[])));
+        assert!(config.excludes(&game(&["favorite", ".hidden"])));
+
+        config.categories = BTreeSet::from(["favorite".into()]);
+        assert!(config.excludes(&game(&[])));
+        assert!(!config.excludes(&game(&["favorite"])));
+
+        config.included_games.insert("celeste".into());
+        assert!(!config.excludes(&game(&[".hidden"])));
+    }
+
+    #[test]
+    fn config_rejects_non_http_endpoints() {
+        let mut config = Config::default();
+        assert!(config.validate().is_ok());
+
+        config.cdn_base_url = Some("ftp://mirror.example.com".into());
+        assert!(config.validate().is_err());
+
+        config.cdn_base_url = None;
+        config.api_base_url = "not a url".into();
+        assert!(config.validate().is_err());
+
+        config.api_base_url = default_api_base_url();
+        config.proxy_url = Some("socks5h://127.0.0.1:1080".into());
+        assert!(config.validate().is_ok());
+        config.proxy_url = Some("ftp://proxy.example.com".into());
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn extra_query_params_are_checked() {
+        let parse = |toml: &str| toml::from_str::<Config>(toml).unwrap().validate();
+        assert!(parse("[extra_query_params.grids]\noneoftag = \"pixel-art\"").is_ok());
+        assert!(parse("[extra_query_params.covers]\noneoftag = \"pixel-art\"").is_err());
+        assert!(parse("[extra_query_params.heroes]\nnsfw = \"any\"").is_err());
+    }
+
     #[test]
     fn config_partial_toml_fills_defaults() {
         let partial = r#"api_key = "test123""#;
@@ -192,4 +971,24 @@ mod tests {
         assert_eq!(config.max_concurrent_downloads, 3);
         assert!(config.nsfw_filter);
     }
+
+    #[test]
+    fn overrides_round_trip() {
+        let config: Config = toml::from_str("[overrides]\nsome-game = 5248193").unwrap();
+        assert_eq!(config.overrides.get("some-game"), Some(&5_248_193));
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let deserialized: Config = toml::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.overrides, config.overrides);
+    }
+
+    #[test]
+    fn grid_dimension_accepts_legacy_string() {
+        let config: Config = toml::from_str(r#"preferred_grid_dimension = "920x430""#).unwrap();
+        assert_eq!(config.preferred_grid_dimension, ["920x430"]);
+
+        let config: Config =
+            toml::from_str(r#"preferred_grid_dimension = ["600x900", "342x482"]"#).unwrap();
+        assert_eq!(config.preferred_grid_dimension, ["600x900", "342x482"]);
+    }
 }
@@ -1,12 +1,29 @@
 /// Lutris `SQLite` database reader.
 ///
-/// Reads the `games` table from Lutris' `pga.db` to discover installed games.
+/// Reads the `games` table from Lutris' `pga.db` to discover installed (and optionally
+/// uninstalled) games. Its columns vary between Lutris versions, so the schema is read
+/// first (see [`Schema`]) and missing columns are read as stand-in values.
+/// Nothing is written back, except custom-art flags cleared on request (see
+/// [`clear_custom_art_flags`]).
 /// All database work is synchronous — we read everything into memory and drop
 /// the connection before any async work begins (rusqlite `Connection` is not `Send`).
-use std::path::Path;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use color_eyre::eyre::{Context, Result, eyre};
-use rusqlite::Connection;
+use color_eyre::eyre::{Context, Report, Result, eyre};
+use rusqlite::{Connection, ErrorCode, OpenFlags};
+
+use crate::api::models::AssetType;
+
+/// How many times to read a database Lutris has locked before giving up.
+const LOCKED_ATTEMPTS: u32 = 4;
+
+/// How long `SQLite` itself waits for a lock on each attempt.
+const BUSY_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How long a write waits for Lutris to finish with the database; writes aren't retried.
+const WRITE_BUSY_TIMEOUT: Duration = Duration::from_secs(2);
 
 /// A game entry read from the Lutris database.
 #[derive(Debug, Clone)]
@@ -21,9 +38,98 @@ pub struct Game {
     pub service_id: Option<String>,
     pub has_custom_banner: bool,
     pub has_custom_coverart: bool,
+    /// Whether the game is installed, rather than just in the library (e.g. from GOG).
+    pub installed: bool,
+    /// Unix timestamp of installation, if the schema records it.
+    pub installed_at: Option<i64>,
+    /// Unix timestamp of the last launch, if the game was ever played.
+    pub lastplayed: Option<i64>,
+    /// Hours played, if the game was ever played.
+    pub playtime: Option<f64>,
+    /// Release year, if Lutris knows it.
+    pub year: Option<i64>,
+    /// Names of the Lutris categories the game is in, e.g. `favorite` or `.hidden`.
+    pub categories: Vec<String>,
+    /// Name of the game's YAML config in Lutris's `games/` directory, without `.yml`.
+    pub configpath: Option<String>,
+    /// Title from the game's YAML config, when it has one.
+    pub config_name: Option<String>,
+    /// Game slug from the game's YAML config, when it has one; art is still saved under
+    /// `slug`.
+    pub config_slug: Option<String>,
+}
+
+impl Game {
+    /// An installed game with just a name and slug, for sources that know nothing else.
+    pub fn named(slug: String, name: String) -> Self {
+        Self {
+            id: 0,
+            name,
+            slug,
+            runner: None,
+            platform: None,
+            service: None,
+            service_id: None,
+            has_custom_banner: false,
+            has_custom_coverart: false,
+            installed: true,
+            installed_at: None,
+            lastplayed: None,
+            playtime: None,
+            year: None,
+            categories: Vec::new(),
+            configpath: None,
+            config_name: None,
+            config_slug: None,
+        }
+    }
+
+    /// Name to search art sources with: the config's title, or the database's.
+    pub fn search_name(&self) -> &str {
+        self.config_name.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Slug to search art sources with: the config's, or the database's.
+    pub fn search_slug(&self) -> &str {
+        self.config_slug.as_deref().unwrap_or(&self.slug)
+    }
+
+    /// Whether Lutris has the user's own art for this asset type — set in Lutris itself,
+    /// which flags it in the database.
+    pub fn has_custom_art(&self, asset: AssetType) -> bool {
+        match asset {
+            AssetType::Grid => self.has_custom_coverart,
+            AssetType::Banner => self.has_custom_banner,
+            AssetType::Hero | AssetType::Logo | AssetType::Icon => false,
+        }
+    }
+}
+
+/// The `games` column flagging the user's own art of this type, for the types Lutris
+/// flags.
+fn custom_art_column(asset: AssetType) -> Option<&'static str> {
+    match asset {
+        AssetType::Grid => Some("has_custom_coverart_big"),
+        AssetType::Banner => Some("has_custom_banner"),
+        AssetType::Hero | AssetType::Logo | AssetType::Icon => None,
+    }
 }
 
-/// Validate that the Lutris database file exists and is readable.
+/// Runners and services whose games are left out when reading the database.
+#[derive(Debug, Clone, Default)]
+pub struct Exclusions {
+    pub runners: BTreeSet<String>,
+    pub services: BTreeSet<String>,
+}
+
+impl Exclusions {
+    fn excludes(&self, game: &Game) -> bool {
+        game.runner.as_ref().is_some_and(|r| self.runners.contains(r))
+            || game.service.as_ref().is_some_and(|s| self.services.contains(s))
+    }
+}
+
+/// Validate that the Lutris database file exists, is readable, and has a `games` table.
 ///
 /// # Errors
 ///
@@ -47,7 +153,23 @@ pub fn validate_db(path: &Path) -> Result<()> {
     std::fs::metadata(path)
         .wrap_err_with(|| format!("Cannot read metadata for {}", path.display()))?;
 
-    Ok(())
+    // A path set by hand may point at anything
+    read_schema(path).map(|_| ())
+}
+
+/// Read the schema of the Lutris database at `path`.
+///
+/// # Errors
+///
+/// Returns an error if the database can't be read, or isn't a Lutris database.
+pub fn read_schema(path: &Path) -> Result<Schema> {
+    with_retry(path, |conn| {
+        let schema = Schema::read(conn)?;
+        if !schema.is_lutris() {
+            return Err(eyre!("{} is not a Lutris database", path.display()));
+        }
+        Ok(schema)
+    })
 }
 
 /// Read all installed games from the Lutris database, sorted alphabetically by name.
@@ -56,30 +178,55 @@ pub fn validate_db(path: &Path) -> Result<()> {
 ///
 /// Returns an error if the database cannot be opened or the query fails.
 pub fn get_installed_games(path: &Path) -> Result<Vec<Game>> {
-    let conn = Connection::open(path)
-        .wrap_err_with(|| format!("Failed to open Lutris database at {}", path.display()))?;
+    get_games(path, false, &Exclusions::default())
+}
 
-    // Discover available columns to handle schema variations gracefully
-    let has_coverart_big = table_has_column(&conn, "games", "has_custom_coverart_big");
+/// Read the games from the Lutris database — only the installed ones unless
+/// `include_uninstalled`, and none of the `excluded` runners and services — sorted
+/// alphabetically by name.
+///
+/// Games with no `service_id` are backfilled from the `service_games` table when the
+/// schema has one, so platform lookups work for more of the library.
+///
+/// # Errors
+///
+/// Returns an error if the database cannot be opened or the query fails.
+pub fn get_games(path: &Path, include_uninstalled: bool, excluded: &Exclusions) -> Result<Vec<Game>> {
+    with_retry(path, |conn| read_games(conn, include_uninstalled, excluded))
+}
 
-    let coverart_col = if has_coverart_big {
-        "has_custom_coverart_big"
-    } else {
-        "0" // default to false if column doesn't exist
-    };
+/// Read the games from an open database.
+fn read_games(conn: &Connection, include_uninstalled: bool, excluded: &Exclusions) -> Result<Vec<Game>> {
+    let schema = Schema::read(conn)?;
+    let column = |name| schema.column(name);
+    let installed = column("installed");
+    let filter = if include_uninstalled { String::new() } else { format!("WHERE {installed} = 1 ") };
 
     let query = format!(
-        "SELECT id, name, slug, runner, platform, service, service_id, \
-         COALESCE(has_custom_banner, 0), COALESCE({coverart_col}, 0) \
+        "SELECT id, name, slug, {}, {}, {}, {}, \
+         COALESCE({}, 0), COALESCE({}, 0), \
+         {}, {}, {}, COALESCE({installed}, 0), \
+         {}, {} \
          FROM games \
-         WHERE installed = 1 \
-         ORDER BY name COLLATE NOCASE"
+         {filter}\
+         ORDER BY name COLLATE NOCASE",
+        column("runner"),
+        column("platform"),
+        column("service"),
+        column("service_id"),
+        column("has_custom_banner"),
+        column("has_custom_coverart_big"),
+        numeric(column("installed_at"), "INTEGER"),
+        numeric(column("lastplayed"), "INTEGER"),
+        numeric(column("year"), "INTEGER"),
+        column("configpath"),
+        numeric(column("playtime"), "REAL"),
     );
 
     let mut stmt = conn.prepare(&query)
         .wrap_err("Failed to prepare games query")?;
 
-    let games = stmt
+    let mut games = stmt
         .query_map([], |row| {
             Ok(Game {
                 id: row.get(0)?,
@@ -91,15 +238,311 @@ pub fn get_installed_games(path: &Path) -> Result<Vec<Game>> {
                 service_id: row.get(6)?,
                 has_custom_banner: row.get::<_, i64>(7)? != 0,
                 has_custom_coverart: row.get::<_, i64>(8)? != 0,
+                installed: row.get::<_, i64>(12)? != 0,
+                installed_at: row.get::<_, Option<i64>>(9)?.filter(|&t| t > 0),
+                lastplayed: row.get::<_, Option<i64>>(10)?.filter(|&t| t > 0),
+                playtime: row.get::<_, Option<f64>>(14)?.filter(|&h| h > 0.0),
+                year: row.get::<_, Option<i64>>(11)?.filter(|&y| y > 0),
+                categories: Vec::new(),
+                configpath: row.get::<_, Option<String>>(13)?.filter(|p| !p.is_empty()),
+                config_name: None,
+                config_slug: None,
             })
         })
-        .wrap_err("Failed to query installed games")?
+        .wrap_err("Failed to query games")?
         .collect::<Result<Vec<_>, _>>()
         .wrap_err("Failed to read game row")?;
 
+    if schema.has_table("service_games") {
+        fill_service_ids(conn, &mut games)?;
+    }
+    // After the backfill, which can link a game to an excluded service
+    games.retain(|game| !excluded.excludes(game));
+    if schema.has_table("categories") && schema.has_table("games_categories") {
+        fill_categories(conn, &mut games)?;
+    }
+
     Ok(games)
 }
 
+/// Fill in each game's categories from the `categories` and `games_categories` tables.
+fn fill_categories(conn: &Connection, games: &mut [Game]) -> Result<()> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT gc.game_id, c.name FROM games_categories gc \
+             JOIN categories c ON c.id = gc.category_id \
+             WHERE c.name IS NOT NULL \
+             ORDER BY c.name COLLATE NOCASE",
+        )
+        .wrap_err("Failed to prepare categories query")?;
+
+    let mut categories: HashMap<i64, Vec<String>> = HashMap::new();
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+        .wrap_err("Failed to query categories")?;
+    for row in rows {
+        let (id, name) = row.wrap_err("Failed to read categories row")?;
+        categories.entry(id).or_default().push(name);
+    }
+
+    for game in games {
+        game.categories = categories.remove(&game.id).unwrap_or_default();
+    }
+    Ok(())
+}
+
+/// Backfill missing `service`/`service_id` values from the `service_games` table.
+///
+/// Newer Lutris versions keep the store catalogue there, linked to `games` via
+/// `lutris_slug`. Existing values from the `games` row always win.
+fn fill_service_ids(conn: &Connection, games: &mut [Game]) -> Result<()> {
+    if !table_has_column(conn, "service_games", "lutris_slug") {
+        return Ok(());
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT lutris_slug, service, appid FROM service_games \
+             WHERE lutris_slug IS NOT NULL AND appid IS NOT NULL",
+        )
+        .wrap_err("Failed to prepare service_games query")?;
+
+    let mut ids: HashMap<String, (String, String)> = HashMap::new();
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .wrap_err("Failed to query service_games")?;
+    for row in rows {
+        let (slug, service, appid) = row.wrap_err("Failed to read service_games row")?;
+        ids.entry(slug).or_insert((service, appid));
+    }
+
+    for game in games.iter_mut().filter(|g| g.service_id.is_none()) {
+        let Some((service, appid)) = ids.get(&game.slug) else {
+            continue;
+        };
+        // Don't pair an appid from one store with a game linked to another
+        if game.service.as_deref().is_some_and(|s| s != service) {
+            continue;
+        }
+        game.service = Some(service.clone());
+        game.service_id = Some(appid.clone());
+    }
+
+    Ok(())
+}
+
+/// Clear Lutris's custom-art flag for each (game ID, asset type) in `flags`, so Lutris
+/// shows the art saved over the user's. The database is first copied into `backup_dir`,
+/// as `pga.db` or — when runs share the directory — `pga-<n>.db`. Returns how many flags
+/// were cleared, and where the backup went.
+///
+/// # Errors
+///
+/// Returns an error if the backup can't be written, or the database can't be updated —
+/// in which case it's left as it was.
+pub fn clear_custom_art_flags(path: &Path, flags: &[(i64, AssetType)], backup_dir: &Path) -> Result<(usize, PathBuf)> {
+    let mut conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .wrap_err_with(|| format!("Failed to open Lutris database at {}", path.display()))?;
+    conn.busy_timeout(WRITE_BUSY_TIMEOUT).wrap_err("Failed to set database busy timeout")?;
+
+    std::fs::create_dir_all(backup_dir).wrap_err_with(|| format!("Failed to create {}", backup_dir.display()))?;
+    // VACUUM INTO won't overwrite a file
+    let mut backup = backup_dir.join("pga.db");
+    for n in 1.. {
+        if !backup.exists() {
+            break;
+        }
+        backup = backup_dir.join(format!("pga-{n}.db"));
+    }
+    conn.execute("VACUUM INTO ?1", [backup.to_string_lossy()])
+        .wrap_err_with(|| format!("Failed to back up Lutris database to {}", backup.display()))?;
+
+    let update = |conn: &mut Connection| -> rusqlite::Result<usize> {
+        let tx = conn.transaction_with_behavior(rusqlite::TransactionBehavior::Immediate)?;
+        let mut cleared = 0;
+        for &(id, asset) in flags {
+            if let Some(column) = custom_art_column(asset) {
+                cleared += tx.execute(&format!("UPDATE games SET {column} = 0 WHERE id = ?1 AND {column} != 0"), [id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(cleared)
+    };
+    let cleared = update(&mut conn).wrap_err_with(|| format!("Failed to update Lutris database at {}", path.display()))?;
+    Ok((cleared, backup))
+}
+
+// ---------------------------------------------------------------------------
+// Locking
+// ---------------------------------------------------------------------------
+
+/// Open the database read-only, so a running Lutris never waits on us, and start a read
+/// transaction: everything is read from one snapshot, and a lock shows up here rather
+/// than as tables that seem to be missing.
+fn open(path: &Path) -> Result<Connection> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .wrap_err_with(|| format!("Failed to open Lutris database at {}", path.display()))?;
+    conn.busy_timeout(BUSY_TIMEOUT).wrap_err("Failed to set database busy timeout")?;
+    match conn
+        .execute_batch("BEGIN")
+        .and_then(|()| conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(())))
+    {
+        Err(e) if e.sqlite_error_code() == Some(ErrorCode::NotADatabase) => {
+            Err(eyre!("{} is not a Lutris database", path.display()))
+        }
+        result => result
+            .map(|()| conn)
+            .wrap_err_with(|| format!("Failed to read Lutris database at {}", path.display())),
+    }
+}
+
+/// Open the database and `read` it, trying again with growing pauses while Lutris
+/// (or anything else) has it locked for writing.
+fn with_retry<T>(path: &Path, read: impl Fn(&Connection) -> Result<T>) -> Result<T> {
+    let mut delay = Duration::from_millis(100);
+    let mut attempt = 1;
+    loop {
+        match open(path).and_then(|conn| read(&conn)) {
+            Err(e) if is_locked(&e) && attempt < LOCKED_ATTEMPTS => {
+                std::thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            Err(e) if is_locked(&e) => {
+                return Err(e.wrap_err(format!(
+                    "Lutris database at {} is locked\nClose Lutris or try again in a moment.",
+                    path.display()
+                )));
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Select expression reading `column` as a number of type `ty` (`INTEGER` or `REAL`).
+///
+/// Older Lutris versions kept some numbers as text. Text holding just a number is read
+/// as one; anything else — e.g. "2 hours 5 minutes", which `CAST` alone would read as 2
+/// — as NULL.
+fn numeric(column: &str, ty: &str) -> String {
+    let digits = if ty == "REAL" { "0-9." } else { "0-9" };
+    format!(
+        "CASE WHEN typeof({column}) IN ('integer', 'real') THEN CAST({column} AS {ty}) \
+         WHEN typeof({column}) = 'text' AND trim({column}) GLOB '*[0-9]*' \
+         AND trim({column}) NOT GLOB '*[^{digits}]*' THEN CAST(trim({column}) AS {ty}) END"
+    )
+}
+
+/// Whether an error comes from the database being locked.
+fn is_locked(error: &Report) -> bool {
+    error.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<rusqlite::Error>(),
+            Some(rusqlite::Error::SqliteFailure(e, _))
+                if matches!(e.code, ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+        )
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Schema
+// ---------------------------------------------------------------------------
+
+/// Columns of `games` every Lutris version has; a table without them isn't Lutris's.
+const REQUIRED_COLUMNS: [&str; 3] = ["id", "name", "slug"];
+
+/// Columns of `games` that are read when they exist, with the value used when this
+/// Lutris version doesn't have them.
+const OPTIONAL_COLUMNS: [(&str, &str); 12] = [
+    ("runner", "NULL"),
+    ("platform", "NULL"),
+    ("service", "NULL"),
+    ("service_id", "NULL"),
+    ("has_custom_banner", "0"),
+    ("has_custom_coverart_big", "0"),
+    ("installed", "1"),
+    ("installed_at", "NULL"),
+    ("lastplayed", "NULL"),
+    ("year", "NULL"),
+    ("configpath", "NULL"),
+    ("playtime", "NULL"),
+];
+
+/// The tables of a Lutris database and the columns of its `games` table, which vary
+/// with the Lutris version that created (or last migrated) it.
+#[derive(Debug)]
+pub struct Schema {
+    tables: HashSet<String>,
+    columns: HashSet<String>,
+}
+
+impl Schema {
+    /// Read the schema of an open database.
+    fn read(conn: &Connection) -> Result<Self> {
+        let names = |query: &str| -> Result<HashSet<String>> {
+            let mut stmt = conn.prepare(query).wrap_err("Failed to read database schema")?;
+            let rows = stmt
+                .query_map([], |row| row.get::<_, String>(0))
+                .wrap_err("Failed to read database schema")?;
+            rows.collect::<Result<_, _>>().wrap_err("Failed to read database schema")
+        };
+        Ok(Self {
+            tables: names("SELECT name FROM sqlite_master WHERE type = 'table'")?,
+            columns: names("SELECT name FROM pragma_table_info('games')")?,
+        })
+    }
+
+    /// Whether this looks like a Lutris database at all.
+    fn is_lutris(&self) -> bool {
+        self.has_table("games") && REQUIRED_COLUMNS.iter().all(|c| self.columns.contains(*c))
+    }
+
+    fn has_table(&self, table: &str) -> bool {
+        self.tables.contains(table)
+    }
+
+    /// Select expression for an optional `games` column: the column itself, or its
+    /// stand-in when this Lutris version doesn't have it.
+    fn column(&self, name: &'static str) -> &'static str {
+        let fallback = OPTIONAL_COLUMNS.iter().find(|(c, _)| *c == name).map_or("NULL", |(_, f)| f);
+        if self.columns.contains(name) {
+            name
+        } else {
+            fallback
+        }
+    }
+
+    /// Optional columns this database doesn't have.
+    fn missing_columns(&self) -> Vec<&'static str> {
+        OPTIONAL_COLUMNS.iter().map(|(c, _)| *c).filter(|c| !self.columns.contains(*c)).collect()
+    }
+
+    /// The Lutris versions this schema comes from, as far as it tells: games got a
+    /// `service` when stores became services, in 0.5.9.
+    pub fn lutris_version(&self) -> &'static str {
+        if self.columns.contains("service") {
+            "0.5.9 or later"
+        } else {
+            "before 0.5.9"
+        }
+    }
+
+    /// One line for logs, e.g. "Lutris 0.5.9 or later database (no playtime)".
+    pub fn describe(&self) -> String {
+        let version = self.lutris_version();
+        match self.missing_columns() {
+            missing if missing.is_empty() => format!("Lutris {version} database"),
+            missing => format!("Lutris {version} database (no {})", missing.join(", ")),
+        }
+    }
+}
+
 /// Check whether a table has a specific column (for schema compatibility).
 fn table_has_column(conn: &Connection, table: &str, column: &str) -> bool {
     let query = format!("PRAGMA table_info({table})");
@@ -114,3 +557,119 @@ fn table_has_column(conn: &Connection, table: &str, column: &str) -> bool {
     let columns: Vec<String> = rows.filter_map(Result::ok).collect();
     columns.iter().any(|col_name| col_name == column)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn old_schemas_are_read_with_stand_ins() {
+        let path = std::env::temp_dir().join(format!("lutrisartfetcher-old-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE games (id INTEGER, name TEXT, slug TEXT, runner TEXT, playtime TEXT);
+                 INSERT INTO games VALUES (1, 'Doom', 'doom', 'linux', '2 hours 5 minutes'),
+                                          (2, 'Quake', 'quake', 'linux', ' 2.5 ');",
+            )
+            .unwrap();
+
+        let schema = read_schema(&path).unwrap();
+        assert_eq!(schema.lutris_version(), "before 0.5.9");
+        assert!(schema.missing_columns().contains(&"service_id"));
+        let games = get_installed_games(&path).unwrap();
+        assert_eq!((games[0].installed, games[0].playtime, games[0].service_id.as_deref()), (true, None, None));
+        // Text that is just a number still reads as one
+        assert_eq!(games[1].playtime, Some(2.5));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn uninstalled_games_are_read_on_request() {
+        let path = std::env::temp_dir().join(format!("lutrisartfetcher-uninstalled-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE games (id INTEGER, name TEXT, slug TEXT, installed INTEGER);
+                 INSERT INTO games VALUES (1, 'Doom', 'doom', 1), (2, 'Quake', 'quake', 0), (3, 'Hexen', 'hexen', NULL);",
+            )
+            .unwrap();
+
+        let installed = get_games(&path, false, &Exclusions::default()).unwrap();
+        assert_eq!(installed.iter().map(|g| g.slug.as_str()).collect::<Vec<_>>(), ["doom"]);
+        let all = get_games(&path, true, &Exclusions::default()).unwrap();
+        let read: Vec<_> = all.iter().map(|g| (g.slug.as_str(), g.installed)).collect();
+        assert_eq!(read, [("doom", true), ("hexen", false), ("quake", false)]);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn excluded_runners_and_services_are_left_out() {
+        let path = std::env::temp_dir().join(format!("lutrisartfetcher-excluded-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE games (id INTEGER, name TEXT, slug TEXT, runner TEXT, service TEXT, service_id TEXT);
+                 CREATE TABLE service_games (lutris_slug TEXT, service TEXT, appid TEXT);
+                 INSERT INTO games VALUES (1, 'Doom', 'doom', 'linux', NULL, NULL),
+                                          (2, 'Itch Jam', 'itch-jam', 'linux', NULL, NULL),
+                                          (3, 'Webmail', 'webmail', 'browser', NULL, NULL);
+                 INSERT INTO service_games VALUES ('itch-jam', 'itchio', '42');",
+            )
+            .unwrap();
+
+        let excluded = Exclusions { runners: BTreeSet::from(["browser".into()]), services: BTreeSet::from(["itchio".into()]) };
+        let games = get_games(&path, false, &excluded).unwrap();
+        assert_eq!(games.iter().map(|g| g.slug.as_str()).collect::<Vec<_>>(), ["doom"]);
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn locked_database_is_retried_then_reported() {
+        let path = std::env::temp_dir().join(format!("lutrisartfetcher-locked-{}.db", std::process::id()));
+        let lutris = Connection::open(&path).unwrap();
+        lutris
+            .execute_batch("CREATE TABLE games (id INTEGER, name TEXT, slug TEXT); BEGIN EXCLUSIVE;")
+            .unwrap();
+
+        let error = get_installed_games(&path).unwrap_err();
+        assert!(is_locked(&error));
+        assert!(error.to_string().contains("is locked"));
+
+        lutris.execute_batch("COMMIT").unwrap();
+        drop(lutris);
+        assert!(validate_db(&path).is_ok());
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn custom_art_flags_are_cleared_after_a_backup() {
+        let path = std::env::temp_dir().join(format!("lutrisartfetcher-flags-{}.db", std::process::id()));
+        let backups = std::env::temp_dir().join(format!("lutrisartfetcher-flags-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        Connection::open(&path)
+            .unwrap()
+            .execute_batch(
+                "CREATE TABLE games (id INTEGER, name TEXT, slug TEXT, has_custom_banner INTEGER, has_custom_coverart_big INTEGER);
+                 INSERT INTO games VALUES (1, 'Doom', 'doom', 1, 1);",
+            )
+            .unwrap();
+
+        let flags = [(1, AssetType::Banner), (1, AssetType::Hero)];
+        let (cleared, backup) = clear_custom_art_flags(&path, &flags, &backups).unwrap();
+        assert_eq!((cleared, backup), (1, backups.join("pga.db")));
+        let game = &get_installed_games(&path).unwrap()[0];
+        assert_eq!((game.has_custom_banner, game.has_custom_coverart), (false, true));
+        assert!(get_installed_games(&backups.join("pga.db")).unwrap()[0].has_custom_banner);
+
+        // A second run sharing the backup directory keeps the first backup
+        let (cleared, backup) = clear_custom_art_flags(&path, &flags, &backups).unwrap();
+        assert_eq!((cleared, backup), (0, backups.join("pga-1.db")));
+        assert!(get_installed_games(&backups.join("pga.db")).unwrap()[0].has_custom_banner);
+        let _ = std::fs::remove_file(path);
+        let _ = std::fs::remove_dir_all(backups);
+    }
+}
@@ -2,16 +2,32 @@
 ///
 /// Each download task sends progress updates through an `mpsc` channel so the
 /// TUI can display real-time status.
-use std::collections::HashSet;
-use std::path::PathBuf;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
-use color_eyre::eyre::{Context, Result};
-use tokio::sync::{Semaphore, mpsc};
+use color_eyre::eyre::{Context, Result, eyre};
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncReadExt;
+use futures::stream::{FuturesOrdered, FuturesUnordered, StreamExt};
+use tokio::sync::{OnceCell, Semaphore, mpsc, watch};
+use tokio_util::sync::CancellationToken;
 
-use crate::api::models::{AssetType, DownloadProgress, DownloadStatus, ImageAsset};
-use crate::api::SteamGridDbClient;
-use crate::config;
-use crate::db::Game;
+use crate::api::models::{AssetType, DownloadProgress, DownloadStatus, ImageAsset, PipelineEvent};
+use crate::api::client::{AssetQuery, FileSink, ImageDownload, Validators, is_transient, is_unauthorized, validator_path};
+use crate::checkpoint::{Checkpoint, Journal};
+use crate::config::{self, Config, Ranking, ResizeArt};
+use crate::db::{self, Game};
+use crate::failures::FailedAssets;
+use crate::history::{RunHistory, RunTimings};
+use crate::launcher::LauncherBackend;
+use crate::manifest::{self, Manifest, ManifestEntry};
+use crate::provider::{ArtProvider, Resolution};
+use crate::steam_grid;
 
 /// Entry combining a game and per-asset download status.
 #[derive(Debug, Clone)]
@@ -19,6 +35,7 @@ use crate::db::Game;
 pub struct GameEntry {
     pub game: Game,
     pub grid_status: DownloadStatus,
+    pub banner_status: DownloadStatus,
     pub hero_status: DownloadStatus,
     pub logo_status: DownloadStatus,
     pub icon_status: DownloadStatus,
@@ -31,6 +48,7 @@ impl GameEntry {
         Self {
             game,
             grid_status: DownloadStatus::Pending,
+            banner_status: DownloadStatus::Pending,
             hero_status: DownloadStatus::Pending,
             logo_status: DownloadStatus::Pending,
             icon_status: DownloadStatus::Pending,
@@ -42,6 +60,7 @@ impl GameEntry {
     pub fn status_mut(&mut self, asset: AssetType) -> &mut DownloadStatus {
         match asset {
             AssetType::Grid => &mut self.grid_status,
+            AssetType::Banner => &mut self.banner_status,
             AssetType::Hero => &mut self.hero_status,
             AssetType::Logo => &mut self.logo_status,
             AssetType::Icon => &mut self.icon_status,
@@ -52,6 +71,7 @@ impl GameEntry {
     pub fn status(&self, asset: AssetType) -> &DownloadStatus {
         match asset {
             AssetType::Grid => &self.grid_status,
+            AssetType::Banner => &self.banner_status,
             AssetType::Hero => &self.hero_status,
             AssetType::Logo => &self.logo_status,
             AssetType::Icon => &self.icon_status,
@@ -66,7 +86,7 @@ impl GameEntry {
             .collect();
 
         // Any downloading? Show downloading
-        if statuses.iter().any(|s| matches!(s, DownloadStatus::Downloading | DownloadStatus::Searching)) {
+        if statuses.iter().any(|s| matches!(s, DownloadStatus::Downloading { .. } | DownloadStatus::Searching)) {
             return "↓";
         }
         // Any failed? Show failed
@@ -86,256 +106,2663 @@ impl GameEntry {
 // Path resolution
 // ---------------------------------------------------------------------------
 
+/// Extensions grids, heroes, and logos may be saved with, matching their image format.
+const ART_EXTENSIONS: [&str; 4] = ["jpg", "png", "webp", "apng"];
+
 /// Resolve the full filesystem path where an asset should be saved.
-pub fn asset_path(asset: AssetType, slug: &str) -> Result<PathBuf> {
+pub fn asset_path(launcher: &dyn LauncherBackend, asset: AssetType, slug: &str) -> Result<PathBuf> {
+    let ext = if asset == AssetType::Icon { "png" } else { "jpg" };
+    asset_path_with_extension(launcher, asset, slug, ext)
+}
+
+/// Like [`asset_path`], but with a specific file extension.
+pub fn asset_path_with_extension(launcher: &dyn LauncherBackend, asset: AssetType, slug: &str, ext: &str) -> Result<PathBuf> {
+    launcher.art_path(asset, slug, ext)
+}
+
+/// Every path an asset may have been saved under, default extension first.
+///
+/// Icons always use `.png`, which is what Lutris looks for.
+fn candidate_paths(launcher: &dyn LauncherBackend, asset: AssetType, slug: &str) -> Vec<PathBuf> {
     if asset == AssetType::Icon {
-        let dir = config::lutris_icon_dir()?;
-        Ok(dir.join(format!("lutris_{slug}.png")))
-    } else {
-        let dir = config::lutris_asset_dir(asset.lutris_subdir())?;
-        Ok(dir.join(format!("{slug}.jpg")))
+        return asset_path(launcher, asset, slug).into_iter().collect();
     }
+    let mut paths: Vec<PathBuf> = ART_EXTENSIONS
+        .iter()
+        .filter_map(|ext| asset_path_with_extension(launcher, asset, slug, ext).ok())
+        .collect();
+    // Launchers that don't go by extension have a single path
+    paths.dedup();
+    paths
+}
+
+/// The path of the saved file for an asset, whatever its extension.
+pub fn existing_asset_path(launcher: &dyn LauncherBackend, asset: AssetType, slug: &str) -> Option<PathBuf> {
+    candidate_paths(launcher, asset, slug).into_iter().find(|p| p.exists())
 }
 
 /// Check if an asset file already exists on disk.
-pub fn asset_exists(asset: AssetType, slug: &str) -> bool {
-    asset_path(asset, slug)
-        .map(|p| p.exists())
-        .unwrap_or(false)
+pub fn asset_exists(launcher: &dyn LauncherBackend, asset: AssetType, slug: &str) -> bool {
+    existing_asset_path(launcher, asset, slug).is_some()
+}
+
+/// The extension image bytes should be saved with.
+///
+/// The bytes' magic number wins; the API-reported MIME type is the fallback, and `.jpg`
+/// the last resort.
+fn image_extension(bytes: &[u8], mime: &str) -> &'static str {
+    match bytes {
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "webp",
+        [0x89, b'P', b'N', b'G', ..] if is_apng(bytes) => "apng",
+        [0x89, b'P', b'N', b'G', ..] => "png",
+        [0xff, 0xd8, 0xff, ..] => "jpg",
+        _ => match mime {
+            "image/png" => "png",
+            "image/webp" => "webp",
+            "image/apng" => "apng",
+            _ => "jpg",
+        },
+    }
+}
+
+/// Whether downloaded bytes are markup or JSON — an error page served in place of the
+/// image — rather than image data. No image format starts with `<` or `{`.
+fn is_error_page(bytes: &[u8]) -> bool {
+    let text = bytes.strip_prefix(b"\xef\xbb\xbf").unwrap_or(bytes);
+    matches!(text.trim_ascii_start().first(), Some(b'<' | b'{'))
+}
+
+/// Whether PNG bytes carry an animation control chunk (`acTL`), which must precede the
+/// image data.
+fn is_apng(bytes: &[u8]) -> bool {
+    let mut pos = 8; // PNG signature
+    while let Some(header) = bytes.get(pos..pos + 8) {
+        let len = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        match &header[4..8] {
+            b"acTL" => return true,
+            b"IDAT" | b"IEND" => return false,
+            _ => pos = pos.saturating_add(len).saturating_add(12),
+        }
+    }
+    false
+}
+
+/// Total size in bytes of all art currently on disk for a game, across every asset type.
+pub fn art_size_on_disk(launcher: &dyn LauncherBackend, slug: &str) -> u64 {
+    AssetType::all()
+        .iter()
+        .filter_map(|&a| existing_asset_path(launcher, a, slug))
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .map(|m| m.len())
+        .sum()
+}
+
+/// Format a byte count for display, e.g. `"1.4 MB"`.
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < UNITS.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Download pipeline
 // ---------------------------------------------------------------------------
 
-/// Filter assets based on NSFW / humor preferences.
-fn filter_assets(assets: &[ImageAsset], nsfw_filter: bool, humor_filter: bool) -> Option<&ImageAsset> {
-    assets.iter().find(|a| {
-        (!nsfw_filter || !a.nsfw) && (!humor_filter || !a.humor)
-    })
+/// Games looked up at once in a chunk's resolve phase.
+const RESOLVE_CONCURRENCY: usize = 8;
+
+/// Filter assets based on NSFW / humor / epilepsy-risk preferences and blocked
+/// uploaders. When any of the rest comes from a preferred uploader, only those remain.
+///
+/// `SteamGridDB` already leaves the tagged images out; other providers don't filter.
+fn filter_assets<'a>(assets: &'a [ImageAsset], opts: &DownloadOpts) -> Vec<&'a ImageAsset> {
+    let (nsfw, humor, epilepsy) = (opts.nsfw_filter, opts.humor_filter, opts.epilepsy_filter);
+    let by = |a: &ImageAsset, uploaders: &[String]| {
+        a.author.as_ref().is_some_and(|u| uploaders.contains(&u.steam64))
+    };
+    let acceptable: Vec<&ImageAsset> = assets
+        .iter()
+        .filter(|a| (!nsfw || !a.nsfw) && (!humor || !a.humor) && (!epilepsy || !a.epilepsy))
+        .filter(|a| !by(a, &opts.blocked_uploaders))
+        .collect();
+    if acceptable.iter().any(|a| by(a, &opts.preferred_uploaders)) {
+        acceptable.into_iter().filter(|a| by(a, &opts.preferred_uploaders)).collect()
+    } else {
+        acceptable
+    }
 }
 
-/// Resolve a game's `SteamGridDB` ID — using platform lookup if available, otherwise text search.
-async fn resolve_game_id(
-    client: &SteamGridDbClient,
-    game: &Game,
-) -> Result<Option<u64>> {
-    // Try platform-specific lookup first (more accurate)
-    if game.service.as_deref() == Some("steam") {
-        if let Some(ref _sid) = game.service_id {
-            // Search endpoint to get the SteamGridDB game ID from a Steam app ID
-            let search_term = game.name.as_str();
-            let results = client.search(search_term).await?;
-            if let Some(first) = results.first() {
-                return Ok(Some(first.id));
-            }
+/// Pick the asset to download from the acceptable candidates.
+///
+/// Candidates are ranked by the configured [`Ranking`], keeping the API's order on ties.
+/// In low-space mode the smallest image is preferred instead — pixel count is the best
+/// size proxy the API exposes. `dimensions` is the size that was asked for, if any.
+fn choose_asset<'a>(
+    mut candidates: impl Iterator<Item = &'a ImageAsset>,
+    opts: &DownloadOpts,
+    dimensions: Option<&str>,
+) -> Option<&'a ImageAsset> {
+    if opts.low_space.load(Ordering::Relaxed) {
+        return candidates.min_by_key(|a| u64::from(a.width) * u64::from(a.height));
+    }
+    match opts.ranking {
+        Ranking::Api => candidates.next(),
+        Ranking::Score => candidates.min_by_key(|a| Reverse(a.score)),
+        Ranking::Votes => {
+            candidates.min_by_key(|a| Reverse(i64::from(a.upvotes) - i64::from(a.downvotes)))
+        }
+        Ranking::Newest => candidates.min_by_key(|a| Reverse(a.id)),
+        Ranking::ExactSize => {
+            // Icon sizes are a single number for a square
+            let wanted = dimensions.map(|d| if d.contains('x') { d.to_owned() } else { format!("{d}x{d}") });
+            let exact = |a: &ImageAsset| wanted == Some(format!("{}x{}", a.width, a.height));
+            candidates.min_by_key(|a| !exact(a))
         }
     }
-
-    // Fallback: text search using the slug converted to a human-readable name
-    let search_term = game.slug.replace('-', " ");
-    let results = client.search(&search_term).await?;
-    Ok(results.first().map(|r| r.id))
 }
 
 /// Shared download configuration passed to pipeline functions.
+#[allow(clippy::struct_excessive_bools)]
 pub struct DownloadOpts {
-    pub grid_dim: String,
+    /// The launcher art is fetched for, which decides where it's saved.
+    pub launcher: Arc<dyn LauncherBackend>,
+    /// Grid, banner, hero, and icon dimensions to try, in order of preference; empty means
+    /// any size.
+    pub grid_dims: Vec<String>,
+    pub banner_dims: Vec<String>,
+    pub hero_dims: Vec<String>,
+    pub icon_dims: Vec<String>,
     pub nsfw_filter: bool,
     pub humor_filter: bool,
+    pub epilepsy_filter: bool,
+    /// Extra asset list query parameters per asset type, from `extra_query_params`.
+    pub extra_query: HashMap<AssetType, Vec<(String, String)>>,
+    /// Uploaders (Steam64 IDs) to pick first, and ones never to pick.
+    pub preferred_uploaders: Vec<String>,
+    pub blocked_uploaders: Vec<String>,
+    /// How acceptable candidates are ranked.
+    pub ranking: Ranking,
+    /// Lowest name similarity (0–1) at which a text-search result is accepted.
+    pub min_match_confidence: f64,
     pub force: bool,
+    /// Re-check saved art and replace it only where the pick has changed.
+    pub update: bool,
+    /// Free-space threshold in bytes; `0` disables low-space handling.
+    pub min_free_bytes: u64,
+    /// Set once free space drops below `min_free_bytes`; switches ranking to smaller images.
+    pub low_space: AtomicBool,
+    /// Games per checkpoint chunk; `0` processes everything as one chunk.
+    pub chunk_size: usize,
+    /// Games in a row that only got request errors before the run is aborted; `0` never.
+    pub max_failure_streak: u32,
+    /// Extra passes over assets that failed with a transient error, at the end of the run.
+    pub failed_asset_retries: u32,
+    /// Skip what was finished by the previous, interrupted run.
+    pub resume: bool,
+    /// Assets the interrupted run finished in games it hadn't completed, when resuming.
+    pub finished: BTreeMap<String, BTreeSet<AssetType>>,
+    /// Fetch only these (game, asset) pairs, for retrying a run's failures.
+    pub retry_only: Option<FailedAssets>,
+    /// Slug of the game to fetch before all others, e.g. the one highlighted in the TUI.
+    pub priority: Option<String>,
+    /// Also consider animated (WebP/APNG) grids, heroes, and logos.
+    pub allow_animated: bool,
+    /// Transcode WebP and APNG art to PNG or JPEG before saving it.
+    pub convert_images: bool,
+    /// How oversized grids and heroes are shrunk; see [`DownloadOpts::resize_target`].
+    pub resize_art: ResizeArt,
+    /// Keep grids and banners Lutris has flagged as the user's own.
+    pub respect_custom_art: bool,
+    /// Clear the custom-art flags of the flagged grids and banners the run replaced, in
+    /// the Lutris database, once it's over (see [`clear_custom_art_flags`]).
+    pub clear_custom_art_flags: bool,
+    /// The Lutris database to clear them in, if it could be located.
+    pub db_path: Option<PathBuf>,
+    /// Assets whose saved art has been replaced, while clearing custom-art flags.
+    pub replaced: Mutex<HashSet<(String, AssetType)>>,
+    /// Install icons at the other hicolor sizes too (see [`install_icon_sizes`]).
+    pub hicolor_icon_sizes: bool,
+    /// Refresh the hicolor icon cache once the run is over, if it saved any icons.
+    pub update_icon_cache: bool,
+    /// Set once an icon has been saved into the icon theme.
+    pub icons_saved: AtomicBool,
+    /// Problems that didn't fail an asset, e.g. an extra icon size that couldn't be
+    /// saved, for the caller to pass on (see [`DownloadOpts::take_warnings`]).
+    warnings: Mutex<Vec<String>>,
+    /// Sync saved images and their directories to disk (see [`sync_to_disk`]).
+    pub durable_writes: bool,
+    /// Copy the run's art to Steam's grid folder at the end, from this Steam installation
+    /// if one is configured (see [`steam_grid::export`]).
+    pub steam_grid_export: bool,
+    pub steam_dir: Option<PathBuf>,
+    /// Pages of candidates fetched per asset list (each page costs one API call).
+    pub max_asset_pages: u32,
+    /// Assets saved so far; written out after every chunk.
+    pub manifest: Mutex<Manifest>,
+    /// This run's backup directory, for art that gets replaced; `None` if the data
+    /// directory is unknown.
+    pub backup_dir: Option<PathBuf>,
+    /// Stops the run when cancelled; see [`download_all`].
+    pub cancel: CancellationToken,
+    /// While `true`, no further lookups or asset downloads are started; ones already
+    /// under way finish.
+    pub pause: watch::Sender<bool>,
+}
+
+impl DownloadOpts {
+    /// Build pipeline options from the user's config, saving art where `launcher` keeps
+    /// it. A manifest that can't be read is set aside, with a warning, and a new one
+    /// started.
+    pub fn new(config: &Config, launcher: Arc<dyn LauncherBackend>, force: bool, resume: bool) -> Self {
+        let (manifest, manifest_warning) = Manifest::load_or_set_aside();
+        Self {
+            launcher,
+            grid_dims: config.preferred_grid_dimension.clone(),
+            banner_dims: config.preferred_banner_dimension.clone(),
+            hero_dims: config.preferred_hero_dimension.clone(),
+            icon_dims: config.preferred_icon_dimension.clone(),
+            nsfw_filter: config.nsfw_filter,
+            humor_filter: config.humor_filter,
+            epilepsy_filter: config.epilepsy_filter,
+            extra_query: config
+                .extra_query_params
+                .iter()
+                // Config::validate rejects unknown asset types up front
+                .filter_map(|(asset, params)| {
+                    let params = params.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+                    Some((asset.parse().ok()?, params))
+                })
+                .collect(),
+            preferred_uploaders: config.preferred_uploaders.clone(),
+            blocked_uploaders: config.blocked_uploaders.clone(),
+            ranking: config.ranking,
+            min_match_confidence: config.min_match_confidence,
+            force,
+            update: config.update,
+            min_free_bytes: config.low_disk_space_mb * 1024 * 1024,
+            low_space: AtomicBool::new(false),
+            chunk_size: config.chunk_size,
+            max_failure_streak: config.max_failure_streak,
+            failed_asset_retries: config.failed_asset_retries,
+            resume,
+            finished: if resume { Journal::load() } else { BTreeMap::new() },
+            retry_only: None,
+            priority: None,
+            allow_animated: config.allow_animated,
+            convert_images: config.convert_images,
+            resize_art: config.resize_art,
+            respect_custom_art: config.respect_custom_art,
+            clear_custom_art_flags: config.clear_custom_art_flags,
+            db_path: config.db_path().ok(),
+            replaced: Mutex::new(HashSet::new()),
+            hicolor_icon_sizes: config.hicolor_icon_sizes,
+            update_icon_cache: config.update_icon_cache,
+            icons_saved: AtomicBool::new(false),
+            warnings: Mutex::new(manifest_warning.into_iter().collect()),
+            durable_writes: config.durable_writes,
+            steam_grid_export: config.steam_grid_export,
+            steam_dir: config.steam_dir.clone(),
+            max_asset_pages: config.max_asset_pages,
+            manifest: Mutex::new(manifest),
+            backup_dir: run_backup_dir(),
+            cancel: CancellationToken::new(),
+            pause: watch::Sender::new(false),
+        }
+    }
+
+    /// Preferred dimensions for an asset type, best first. Logos have none.
+    fn dimensions(&self, asset: AssetType) -> &[String] {
+        match asset {
+            AssetType::Grid => &self.grid_dims,
+            AssetType::Banner => &self.banner_dims,
+            AssetType::Hero => &self.hero_dims,
+            AssetType::Icon => &self.icon_dims,
+            AssetType::Logo => &[],
+        }
+    }
+
+    /// The size oversized art of this type is shrunk to, if it is resized at all: the first
+    /// preferred dimension, or the common `SteamGridDB` size when none is set.
+    fn resize_target(&self, asset: AssetType) -> Option<(u32, u32)> {
+        let fallback = match asset {
+            _ if self.resize_art == ResizeArt::Off => return None,
+            AssetType::Grid => "600x900",
+            AssetType::Hero => "1920x620",
+            AssetType::Banner | AssetType::Logo | AssetType::Icon => return None,
+        };
+        let dims = self.dimensions(asset).first().map_or(fallback, String::as_str);
+        let (width, height) = dims.split_once('x')?;
+        Some((width.parse().ok()?, height.parse().ok()?))
+    }
+
+    /// The asset types to fetch for a game: those of `assets` that the interrupted run
+    /// didn't finish, narrowed to the ones in `retry_only` if set.
+    fn assets_for<'a>(
+        &'a self,
+        slug: &'a str,
+        assets: &'a HashSet<AssetType>,
+    ) -> impl Iterator<Item = AssetType> + 'a {
+        let finished = self.finished.get(slug);
+        assets.iter().copied().filter(move |&asset| {
+            !finished.is_some_and(|done| done.contains(&asset))
+                && self.retry_only.as_ref().map_or(true, |only| only.contains(slug, asset))
+        })
+    }
+
+    /// Whether `game`'s art of this type was set in Lutris and must be kept.
+    fn keeps_custom_art(&self, game: &Game, asset: AssetType) -> bool {
+        self.respect_custom_art && game.has_custom_art(asset)
+    }
+
+    /// Wait until the run isn't paused.
+    async fn wait_while_paused(&self) {
+        let _ = self.pause.subscribe().wait_for(|paused| !paused).await;
+    }
+
+    /// Record a saved asset in the manifest.
+    fn record(&self, slug: &str, asset: AssetType, entry: ManifestEntry) {
+        if let Ok(mut manifest) = self.manifest.lock() {
+            manifest.record(slug, asset, entry);
+        }
+    }
+
+    /// The manifest entry for a previously saved asset.
+    fn recorded(&self, slug: &str, asset: AssetType) -> Option<ManifestEntry> {
+        self.manifest.lock().ok()?.get(slug, asset).cloned()
+    }
+
+    /// Whether the file saved for an asset differs from the one downloaded.
+    async fn changed_by_user(&self, slug: &str, asset: AssetType) -> bool {
+        let Some(path) = existing_asset_path(&*self.launcher, asset, slug) else {
+            return false;
+        };
+        match self.recorded(slug, asset) {
+            Some(entry) => entry.changed_on_disk(&path).await,
+            None => false,
+        }
+    }
+
+    /// Art another game has saved for `asset` whose manifest entry `matches`, and whose
+    /// file is still the one recorded — e.g. the same game installed under two runners.
+    async fn identical_art(
+        &self,
+        slug: &str,
+        asset: AssetType,
+        matches: impl Fn(&ManifestEntry) -> bool,
+    ) -> Option<(PathBuf, ManifestEntry)> {
+        let candidates: Vec<_> = {
+            let manifest = self.manifest.lock().ok()?;
+            manifest
+                .games
+                .iter()
+                .filter(|(other, _)| *other != slug)
+                .filter_map(|(other, entries)| Some((other.clone(), entries.get(&asset)?)))
+                .filter(|(_, entry)| entry.sha256.is_some() && matches(entry))
+                .map(|(other, entry)| (other, entry.clone()))
+                .collect()
+        };
+        for (other, entry) in candidates {
+            let Some(path) = existing_asset_path(&*self.launcher, asset, &other) else {
+                continue;
+            };
+            if !entry.changed_on_disk(&path).await {
+                return Some((path, entry));
+            }
+        }
+        None
+    }
+
+    /// Whether another game has saved art for `asset` that could be identical to a
+    /// download of `size` bytes — or whose size wasn't recorded.
+    fn has_art_of_size(&self, slug: &str, asset: AssetType, size: u64) -> bool {
+        self.manifest.lock().is_ok_and(|manifest| {
+            manifest
+                .games
+                .iter()
+                .filter(|(other, _)| *other != slug)
+                .filter_map(|(_, entries)| entries.get(&asset))
+                .any(|entry| entry.sha256.is_some() && entry.size.map_or(true, |s| s == size))
+        })
+    }
+
+    /// Write the manifest to disk.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest cannot be written.
+    pub fn save_manifest(&self) -> Result<()> {
+        match self.manifest.lock() {
+            Ok(manifest) => manifest.save(),
+            Err(_) => Ok(()),
+        }
+    }
+
+    fn warn(&self, message: String) {
+        self.warnings.lock().unwrap_or_else(PoisonError::into_inner).push(message);
+    }
+
+    /// The warnings collected since the last call.
+    pub fn take_warnings(&self) -> Vec<String> {
+        std::mem::take(&mut *self.warnings.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
+    /// Re-check free space for `target` and latch into low-space mode when it runs short.
+    ///
+    /// Returns `true` only on the transition, so the caller can warn exactly once.
+    fn check_low_space(&self, target: &Path) -> bool {
+        if self.min_free_bytes == 0 || self.low_space.load(Ordering::Relaxed) {
+            return false;
+        }
+        let low = free_space(target).is_some_and(|free| free < self.min_free_bytes);
+        low && !self.low_space.swap(true, Ordering::Relaxed)
+    }
+}
+
+/// Free bytes available to unprivileged users on the filesystem holding `path`.
+///
+/// Walks up to the nearest existing ancestor, since art directories may not exist yet.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let existing = path.ancestors().find(|p| p.exists())?;
+    let stat = nix::sys::statvfs::statvfs(existing).ok()?;
+    // statvfs field widths differ between platforms
+    #[allow(clippy::useless_conversion)]
+    Some(u64::from(stat.blocks_available()) * u64::from(stat.fragment_size()))
+}
+
+/// Where icons will be written, as decided by [`preflight_icon_dir`].
+pub struct IconDirPreflight {
+    /// Directory icons will be saved to.
+    pub dir: PathBuf,
+    /// The default directory, if it was unwritable and `dir` is the fallback.
+    pub fell_back_from: Option<PathBuf>,
+}
+
+/// Check that the icons directory is writable, switching to the fallback if it isn't.
+///
+/// Some distros leave the hicolor theme tree root-owned; without this every icon would
+/// fail with a bare "permission denied".
+///
+/// # Errors
+///
+/// Returns an error if neither the default nor the fallback directory is writable.
+pub fn preflight_icon_dir() -> Result<IconDirPreflight> {
+    let default = config::lutris_icon_dir()?;
+    if is_writable_dir(&default) {
+        return Ok(IconDirPreflight {
+            dir: default,
+            fell_back_from: None,
+        });
+    }
+
+    let fallback = config::fallback_icon_dir()?;
+    if !is_writable_dir(&fallback) {
+        return Err(eyre!(
+            "Neither {} nor {} is writable",
+            default.display(),
+            fallback.display()
+        ));
+    }
+    config::set_icon_dir_override(fallback.clone());
+    Ok(IconDirPreflight {
+        dir: fallback,
+        fell_back_from: Some(default),
+    })
+}
+
+/// Check there's room for a run's downloads before it starts, going by an estimate of
+/// their size.
+///
+/// Returns a warning when the run would leave less than `low_disk_space_mb` free, the
+/// point where the pipeline starts preferring smaller images.
+///
+/// # Errors
+///
+/// Returns an error if the run would leave less than `min_free_space_mb` free.
+pub fn preflight_disk_space(
+    launcher: &dyn LauncherBackend,
+    games: &[Game],
+    assets: &HashSet<AssetType>,
+    force: bool,
+    config: &Config,
+) -> Result<Option<String>> {
+    const MIB: u64 = 1024 * 1024;
+    if config.min_free_space_mb == 0 && config.low_disk_space_mb == 0 {
+        return Ok(None);
+    }
+    // The launcher's own art directory, or where it will be created
+    let dirs = launcher.art_dirs();
+    let Some(dir) = dirs.iter().find(|d| d.exists()).or_else(|| dirs.first()) else {
+        return Ok(None);
+    };
+    let Some(free) = free_space(dir) else {
+        return Ok(None);
+    };
+
+    let sizes: HashMap<AssetType, u64> = assets.iter().map(|&a| (a, average_asset_size(launcher, a, games))).collect();
+    let needed: u64 = games
+        .iter()
+        .filter(|g| !config.excludes(g))
+        .flat_map(|g| assets.iter().filter(move |&&a| force || !asset_exists(launcher, a, &g.slug)))
+        .map(|a| sizes[a])
+        .sum();
+    if needed == 0 {
+        return Ok(None);
+    }
+    let left = free.saturating_sub(needed);
+
+    if left < config.min_free_space_mb * MIB {
+        return Err(eyre!(
+            "Not enough disk space: about {} to download with {} free on {}, and \
+             min_free_space_mb keeps {} MB free. Free up some space or lower the setting.",
+            format_size(needed),
+            format_size(free),
+            dir.display(),
+            config.min_free_space_mb
+        ));
+    }
+    if left < config.low_disk_space_mb * MIB {
+        return Ok(Some(format!(
+            "About {} to download with {} free — smaller images will be preferred once \
+             less than {} MB is left",
+            format_size(needed),
+            format_size(free),
+            config.low_disk_space_mb
+        )));
+    }
+    Ok(None)
+}
+
+/// Average size of the art of this type already saved for `games`, or a typical size
+/// for it on `SteamGridDB` if there is none yet. Only the games' own files are counted,
+/// not downloads in progress or other programs' files sharing the directory.
+fn average_asset_size(launcher: &dyn LauncherBackend, asset: AssetType, games: &[Game]) -> u64 {
+    let sizes: Vec<u64> = games
+        .iter()
+        .filter_map(|g| existing_asset_path(launcher, asset, &g.slug))
+        .filter_map(|p| std::fs::metadata(p).ok())
+        .filter(std::fs::Metadata::is_file)
+        .map(|m| m.len())
+        .collect();
+    if sizes.is_empty() {
+        return match asset {
+            AssetType::Grid => 400 * 1024,
+            AssetType::Banner => 250 * 1024,
+            AssetType::Hero => 1536 * 1024,
+            AssetType::Logo => 150 * 1024,
+            AssetType::Icon => 64 * 1024,
+        };
+    }
+    sizes.iter().sum::<u64>() / sizes.len() as u64
+}
+
+/// Whether files can be created in `dir`, creating it if needed.
+fn is_writable_dir(dir: &Path) -> bool {
+    if std::fs::create_dir_all(dir).is_err() {
+        return false;
+    }
+    let probe = dir.join(".lutrisartfetcher-probe");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
+}
+
+/// Whether an asset reached a terminal status or was cut short, to be tried again.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum AssetOutcome {
+    /// The asset was saved or skipped.
+    Finished,
+    /// A failure was reported. It's left out of the journal, so `--resume` tries again.
+    Failed,
+    /// The API returned 401; nothing terminal was reported, so the asset can be retried.
+    AuthExpired,
+    /// A transient error outlasted the request retries; the asset went back to pending,
+    /// for another try once the rest of the run is done.
+    Transient,
+}
+
+/// Why a game or an asset came up empty.
+#[derive(Debug, Clone)]
+struct Miss {
+    reason: String,
+    /// Caused by a request error worth trying again later (see [`is_transient`]).
+    transient: bool,
+}
+
+impl Miss {
+    fn new(reason: impl Into<String>) -> Self {
+        Self {
+            reason: reason.into(),
+            transient: false,
+        }
+    }
+
+    /// A miss caused by a failed request.
+    fn error(context: &str, error: &color_eyre::Report) -> Self {
+        Self {
+            reason: format!("{context}: {error}"),
+            transient: is_transient(error),
+        }
+    }
+}
+
+/// How a game's processing went.
+enum GameOutcome {
+    Finished,
+    /// Processed, but no request for the game succeeded; holds the first provider to
+    /// fail and its error.
+    Unreachable { provider: &'static str, error: String },
 }
 
 /// Download a single asset for a game, sending progress through the channel.
+///
+/// With `retry_later`, a transient failure puts the asset back to pending instead of
+/// failing it (see [`AssetOutcome::Transient`]).
 async fn download_single_asset(
-    client: &SteamGridDbClient,
-    game_id: u64,
+    lookup: &GameLookup<'_>,
     game: &Game,
     asset: AssetType,
     opts: &DownloadOpts,
-    tx: &mpsc::UnboundedSender<DownloadProgress>,
-) {
+    tx: &mpsc::UnboundedSender<PipelineEvent>,
+    retry_later: bool,
+) -> AssetOutcome {
     let slug = &game.slug;
 
-    // Check existence
-    if !opts.force && asset_exists(asset, slug) {
-        let _ = tx.send(DownloadProgress {
-            game_slug: slug.clone(),
-            asset_type: asset,
-            status: DownloadStatus::Skipped("already exists".into()),
-        });
-        return;
+    // Check existence; --update re-checks art whose origin the manifest knows
+    let exists = asset_exists(&*opts.launcher, asset, slug);
+    let saved = opts.recorded(slug, asset).filter(|_| opts.update && exists);
+    if let Some(reason) = reason_to_keep(game, asset, opts, exists, saved.is_some()).await {
+        report(tx, slug, asset, DownloadStatus::Skipped(reason));
+        return AssetOutcome::Finished;
     }
 
     // Notify: downloading
-    let _ = tx.send(DownloadProgress {
-        game_slug: slug.clone(),
-        asset_type: asset,
-        status: DownloadStatus::Downloading,
-    });
+    report(tx, slug, asset, DownloadStatus::Downloading { received: 0, total: None });
 
     // Fetch asset list
-    let dimensions: Option<&str> = if asset == AssetType::Grid { Some(&opts.grid_dim) } else { None };
-
-    // Try platform-specific endpoint first for steam games
-    let assets_result = if game.service.as_deref() == Some("steam") {
-        if let Some(ref sid) = game.service_id {
-            client.get_assets_by_platform(asset, "steam", sid.as_str(), dimensions).await
-        } else {
-            client.get_assets(asset, game_id, dimensions).await
+    if let Ok(target) = asset_path(&*opts.launcher, asset, slug) {
+        if opts.check_low_space(&target) {
+            let _ = tx.send(PipelineEvent::Warning(
+                "Disk space is low — preferring smaller images for the rest of the run".into(),
+            ));
         }
-    } else {
-        client.get_assets(asset, game_id, dimensions).await
-    };
+    }
 
-    let assets = match assets_result {
-        Ok(a) => a,
-        Err(e) => {
-            let _ = tx.send(DownloadProgress {
-                game_slug: slug.clone(),
-                asset_type: asset,
-                status: DownloadStatus::Failed(format!("fetch error: {e}")),
-            });
-            return;
-        }
+    // Fetch candidates and pick the best, falling back through the providers
+    let (provider, chosen) = match pick_asset(lookup, game, asset, opts).await {
+        Pick::Chosen(provider, chosen) => (provider, chosen),
+        Pick::Missing(miss) => return report_miss(tx, slug, asset, miss, retry_later),
+        Pick::AuthExpired => return AssetOutcome::AuthExpired,
     };
+    if saved.is_some_and(|e| e.url == chosen.url) {
+        report(tx, slug, asset, DownloadStatus::Skipped("no newer art".into()));
+        return AssetOutcome::Finished;
+    }
+    // Another game already has this image: link it instead of downloading it again
+    if let Some((source, entry)) = opts.identical_art(slug, asset, |e| e.url == chosen.url).await {
+        let status = match save_identical(asset, slug, &source, entry, opts).await {
+            Ok(target) => DownloadStatus::Done(target),
+            Err(e) => DownloadStatus::Failed(format!("{e}")),
+        };
+        return finish(tx, slug, asset, status);
+    }
 
-    // Pick best asset
-    let Some(chosen) = filter_assets(&assets, opts.nsfw_filter, opts.humor_filter) else {
-        let _ = tx.send(DownloadProgress {
-            game_slug: slug.clone(),
-            asset_type: asset,
-            status: DownloadStatus::Failed("no art found".into()),
-        });
-        return;
+    // Download image bytes — conditionally when re-fetching the same image we already have
+    let image_url = chosen.url.clone();
+    let known = opts
+        .recorded(slug, asset)
+        .filter(|e| e.url == image_url && asset_exists(&*opts.launcher, asset, slug))
+        .map(|e| Validators {
+            etag: e.etag,
+            last_modified: e.last_modified,
+        })
+        .unwrap_or_default();
+    let tmp_path = match prepare_temp_path(&*opts.launcher, asset, slug, &image_url).await {
+        Ok(path) => path,
+        Err(e) => return finish(tx, slug, asset, DownloadStatus::Failed(format!("{e}"))),
+    };
+    let progress = |received, total| report(tx, slug, asset, DownloadStatus::Downloading { received, total });
+    let sink = FileSink {
+        path: &tmp_path,
+        progress: &progress,
+    };
+    let outcome = match provider.download(&image_url, &known, &sink).await {
+        Ok(ImageDownload::Modified { size: 0, .. }) => Err(DownloadStatus::Failed("downloaded 0 bytes".into())),
+        Ok(ImageDownload::Modified { validators, .. }) => Ok(validators),
+        Ok(ImageDownload::NotModified) => Err(DownloadStatus::Skipped("up to date".into())),
+        // Keep whatever arrived: the next attempt resumes from it
+        Err(e) => return report_miss(tx, slug, asset, Miss::error("download error", &e), retry_later),
+    };
+    let validators = match outcome {
+        Ok(validators) => validators,
+        Err(status) => {
+            let _ = tokio::fs::remove_file(&tmp_path).await;
+            let _ = tokio::fs::remove_file(validator_path(&tmp_path)).await;
+            return finish(tx, slug, asset, status);
+        }
     };
 
-    // Download image bytes
-    let image_url = chosen.url.clone();
-    let bytes: Vec<u8> = match client.download_image(&image_url).await {
-        Ok(b) => b,
-        Err(e) => {
-            let _ = tx.send(DownloadProgress {
-                game_slug: slug.clone(),
-                asset_type: asset,
-                status: DownloadStatus::Failed(format!("download error: {e}")),
-            });
-            return;
+    // Move into place, named after the image format
+    match save_downloaded(asset, slug, &tmp_path, &chosen.mime, opts).await {
+        Ok((target, backup)) => {
+            let entry = ManifestEntry {
+                asset_id: chosen.id,
+                url: image_url,
+                etag: validators.etag,
+                last_modified: validators.last_modified,
+                saved_at: None,
+                sha256: None,
+                size: None,
+                modified_ns: None,
+                replaced: opts.recorded(slug, asset).filter(|_| backup.is_some()).map(Box::new),
+                backup,
+            };
+            opts.record(slug, asset, entry.stamped(&target));
+            finish(tx, slug, asset, DownloadStatus::Done(target))
         }
+        Err(e) => finish(tx, slug, asset, DownloadStatus::Failed(format!("{e}"))),
+    }
+}
+
+/// Report an asset's terminal status.
+fn finish(tx: &mpsc::UnboundedSender<PipelineEvent>, slug: &str, asset: AssetType, status: DownloadStatus) -> AssetOutcome {
+    let outcome = if matches!(status, DownloadStatus::Failed(_)) {
+        AssetOutcome::Failed
+    } else {
+        AssetOutcome::Finished
     };
+    report(tx, slug, asset, status);
+    outcome
+}
 
-    if bytes.is_empty() {
-        let _ = tx.send(DownloadProgress {
-            game_slug: slug.clone(),
-            asset_type: asset,
-            status: DownloadStatus::Failed("downloaded 0 bytes".into()),
-        });
-        return;
+/// Why an asset is left as it is without a lookup, if it is. `rechecking` is set for
+/// saved art `--update` looks for newer art for.
+pub async fn reason_to_keep(game: &Game, asset: AssetType, opts: &DownloadOpts, exists: bool, rechecking: bool) -> Option<String> {
+    let launcher = &*opts.launcher;
+    if !launcher.shows(game, asset) {
+        return Some(format!("not shown by {}", launcher.name()));
+    }
+    if exists && !opts.force && !rechecking {
+        return Some("already exists".into());
+    }
+    // Even --force keeps art the user replaced or edited after it was downloaded
+    if exists && opts.changed_by_user(&game.slug, asset).await {
+        return Some("changed by hand — kept".into());
+    }
+    if exists && opts.keeps_custom_art(game, asset) {
+        return Some("custom art set in Lutris — kept".into());
     }
+    None
+}
 
-    // Save to disk atomically
-    match save_asset_to_disk(asset, slug, &bytes).await {
-        Ok(target) => {
-            let _ = tx.send(DownloadProgress {
-                game_slug: slug.clone(),
-                asset_type: asset,
-                status: DownloadStatus::Done(target),
-            });
-        }
-        Err(e) => {
-            let _ = tx.send(DownloadProgress {
-                game_slug: slug.clone(),
-                asset_type: asset,
-                status: DownloadStatus::Failed(format!("{e}")),
-            });
-        }
+/// Fail an asset — or, for a transient miss with `retry_later`, put it back to pending.
+fn report_miss(
+    tx: &mpsc::UnboundedSender<PipelineEvent>,
+    slug: &str,
+    asset: AssetType,
+    miss: Miss,
+    retry_later: bool,
+) -> AssetOutcome {
+    if retry_later && miss.transient {
+        report(tx, slug, asset, DownloadStatus::Pending);
+        return AssetOutcome::Transient;
     }
+    finish(tx, slug, asset, DownloadStatus::Failed(miss.reason))
 }
 
-/// Write bytes to disk atomically: write to `.tmp` then rename.
-async fn save_asset_to_disk(
+/// Download the exact asset recorded in a manifest entry and save it for `slug`.
+///
+/// # Errors
+///
+/// Returns an error if the download fails, is empty, or cannot be saved.
+pub async fn fetch_pinned(
+    provider: &dyn ArtProvider,
+    slug: &str,
     asset: AssetType,
+    entry: &ManifestEntry,
+    opts: &DownloadOpts,
+) -> Result<PathBuf> {
+    let tmp_path = prepare_temp_path(&*opts.launcher, asset, slug, &entry.url).await?;
+    let sink = FileSink {
+        path: &tmp_path,
+        progress: &|_, _| {},
+    };
+    let size = match provider.download(&entry.url, &Validators::default(), &sink).await? {
+        ImageDownload::Modified { size, .. } => size,
+        ImageDownload::NotModified => 0,
+    };
+    if size == 0 {
+        let _ = tokio::fs::remove_file(&tmp_path).await;
+        return Err(eyre!("downloaded 0 bytes"));
+    }
+    let (target, backup) = save_downloaded(asset, slug, &tmp_path, "", opts).await?;
+    let entry = ManifestEntry {
+        replaced: opts.recorded(slug, asset).filter(|_| backup.is_some()).map(Box::new),
+        backup,
+        ..entry.clone()
+    };
+    opts.record(slug, asset, entry.stamped(&target));
+    Ok(target)
+}
+
+/// Save an image taken from an art pack (see [`crate::pack`]) for `slug`, recording
+/// `entry` — the packing machine's manifest entry for it, if it had one.
+///
+/// # Errors
+///
+/// Returns an error if the image doesn't decode or cannot be saved.
+pub async fn save_packed(
     slug: &str,
+    asset: AssetType,
     bytes: &[u8],
+    entry: Option<&ManifestEntry>,
+    opts: &DownloadOpts,
 ) -> Result<PathBuf> {
-    let target = asset_path(asset, slug)?;
+    let tmp_path = prepare_temp_path(&*opts.launcher, asset, slug, "pack").await?;
+    tokio::fs::write(&tmp_path, bytes).await.wrap_err("write failed")?;
+    let (target, backup) = save_downloaded(asset, slug, &tmp_path, "", opts).await?;
+    let replaced = opts.recorded(slug, asset).filter(|_| backup.is_some()).map(Box::new);
+    match entry {
+        Some(entry) => {
+            let entry = ManifestEntry { backup, replaced, ..entry.clone() };
+            opts.record(slug, asset, entry.stamped(&target));
+        }
+        // The entry of the art replaced would no longer match the file
+        None => {
+            if let Ok(mut manifest) = opts.manifest.lock() {
+                manifest.remove(slug, asset);
+            }
+        }
+    }
+    Ok(target)
+}
 
-    if let Some(parent) = target.parent() {
-        tokio::fs::create_dir_all(parent)
-            .await
-            .wrap_err("mkdir failed")?;
+/// The saved assets of a game that would be picked differently today — because better
+/// ranked or newer art has been uploaded since — with what would replace each.
+///
+/// Only assets recorded in the manifest are checked, so it's clear which asset is on
+/// disk; nothing is downloaded.
+///
+/// # Errors
+///
+/// Returns an error if the API key is rejected or no provider could be reached.
+pub async fn check_for_updates(
+    providers: &[Box<dyn ArtProvider>],
+    game: &Game,
+    assets: &HashSet<AssetType>,
+    opts: &DownloadOpts,
+) -> Result<Vec<(AssetType, ManifestEntry, ImageAsset)>> {
+    let saved: Vec<(AssetType, ManifestEntry)> = {
+        let manifest = opts.manifest.lock().unwrap_or_else(PoisonError::into_inner);
+        assets
+            .iter()
+            .filter(|&&asset| asset_exists(&*opts.launcher, asset, &game.slug) && !opts.keeps_custom_art(game, asset))
+            .filter_map(|&asset| Some((asset, manifest.get(&game.slug, asset)?.clone())))
+            .collect()
+    };
+    let lookup = GameLookup::new(providers, game, opts.min_match_confidence);
+    let mut updates = Vec::new();
+    for (asset, entry) in saved {
+        match pick_asset(&lookup, game, asset, opts).await {
+            Pick::Chosen(_, best) if best.url != entry.url => updates.push((asset, entry, best)),
+            Pick::Chosen(..) | Pick::Missing(_) => {}
+            Pick::AuthExpired => return Err(eyre!("The API key was rejected")),
+        }
     }
+    if let GameOutcome::Unreachable { provider, error } = lookup.outcome() {
+        return Err(eyre!("{provider} unreachable: {error}"));
+    }
+    Ok(updates)
+}
 
-    let tmp_path = target.with_extension("tmp");
-    tokio::fs::write(&tmp_path, bytes)
-        .await
-        .wrap_err("write failed")?;
-    tokio::fs::rename(&tmp_path, &target)
-        .await
-        .wrap_err("rename failed")?;
-    Ok(target)
+/// Where an asset of a game stands, as reported by `audit`.
+pub enum Coverage {
+    /// Saved already.
+    Local,
+    /// Missing, but this provider has acceptable art for it.
+    Available(&'static str, ImageAsset),
+    /// Missing, and no provider has acceptable art for it; holds why.
+    Unavailable(String),
 }
 
-/// Run the entire download pipeline for all games and selected asset types.
+/// Where each asset of a game stands: saved, or for missing ones, whether any provider
+/// has acceptable art for it. Nothing is downloaded.
 ///
-/// Spawns concurrent tasks limited by a semaphore. Sends progress updates
-/// through `tx` for each asset of each game.
-pub async fn download_all(
-    client: &SteamGridDbClient,
-    games: &[Game],
+/// # Errors
+///
+/// Returns an error if the API key is rejected or no provider could be reached.
+pub async fn audit_game(
+    providers: &[Box<dyn ArtProvider>],
+    game: &Game,
     assets: &HashSet<AssetType>,
     opts: &DownloadOpts,
-    max_concurrent: usize,
-    tx: mpsc::UnboundedSender<DownloadProgress>,
-) {
-    let semaphore = std::sync::Arc::new(Semaphore::new(max_concurrent));
+) -> Result<Vec<(AssetType, Coverage)>> {
+    let lookup = GameLookup::new(providers, game, opts.min_match_confidence);
+    let mut coverage = Vec::new();
+    for &asset in AssetType::all().iter().filter(|a| assets.contains(a)) {
+        if asset_exists(&*opts.launcher, asset, &game.slug) {
+            coverage.push((asset, Coverage::Local));
+            continue;
+        }
+        match pick_asset(&lookup, game, asset, opts).await {
+            Pick::Chosen(provider, best) => coverage.push((asset, Coverage::Available(provider.name(), best))),
+            Pick::Missing(miss) => coverage.push((asset, Coverage::Unavailable(miss.reason))),
+            Pick::AuthExpired => return Err(eyre!("The API key was rejected")),
+        }
+    }
+    if let GameOutcome::Unreachable { provider, error } = lookup.outcome() {
+        return Err(eyre!("{provider} unreachable: {error}"));
+    }
+    Ok(coverage)
+}
 
-    // We process game-by-game so we can share the resolved SteamGridDB ID
-    // across asset types for the same game.
-    for game in games {
-        let permit = semaphore.clone().acquire_owned().await;
-        let Ok(_permit) = permit else { break };
-
-        // Notify: searching
-        for &asset in assets {
-            let _ = tx.send(DownloadProgress {
-                game_slug: game.slug.clone(),
-                asset_type: asset,
-                status: DownloadStatus::Searching,
-            });
+/// A game's resolution with each provider, looked up on first use so fallback
+/// providers cost nothing while the ones before them have art.
+///
+/// Shared by the game's concurrently downloading assets: whichever needs a provider's
+/// ID first looks it up, and the others wait for that answer.
+struct GameLookup<'a> {
+    /// The providers that serve this game, in order.
+    providers: Vec<&'a dyn ArtProvider>,
+    game: &'a Game,
+    min_confidence: f64,
+    /// Per provider: empty until looked up, then the game's ID or why there is none.
+    ids: Vec<OnceCell<Result<u64, Miss>>>,
+    /// Whether any provider answered a request for this game.
+    answered: AtomicBool,
+    /// The first provider whose request failed outright, and its error.
+    error: Mutex<Option<(&'static str, String)>>,
+}
+
+impl<'a> GameLookup<'a> {
+    fn new(providers: &'a [Box<dyn ArtProvider>], game: &'a Game, min_confidence: f64) -> Self {
+        let providers: Vec<&dyn ArtProvider> = providers
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|p| p.serves(game))
+            .collect();
+        Self {
+            ids: providers.iter().map(|_| OnceCell::new()).collect(),
+            providers,
+            game,
+            min_confidence,
+            answered: AtomicBool::new(false),
+            error: Mutex::new(None),
         }
+    }
 
-        // Resolve game ID once per game
-        let game_id = match resolve_game_id(client, game).await {
-            Ok(Some(id)) => id,
-            Ok(None) => {
-                for &asset in assets {
-                    let _ = tx.send(DownloadProgress {
-                        game_slug: game.slug.clone(),
-                        asset_type: asset,
-                        status: DownloadStatus::Failed("game not found on `SteamGridDB`".into()),
-                    });
-                }
-                continue;
-            }
+    /// Note whether a request to provider `index` got an answer.
+    fn record<T>(&self, index: usize, result: &Result<T>) {
+        match result {
+            Ok(_) => self.answered.store(true, Ordering::Relaxed),
             Err(e) => {
-                for &asset in assets {
-                    let _ = tx.send(DownloadProgress {
-                        game_slug: game.slug.clone(),
-                        asset_type: asset,
-                        status: DownloadStatus::Failed(format!("search error: {e}")),
-                    });
-                }
-                continue;
+                let name = self.providers[index].name();
+                let mut error = self.error.lock().unwrap_or_else(PoisonError::into_inner);
+                error.get_or_insert_with(|| (name, e.to_string()));
             }
-        };
+        }
+    }
 
-        // Download each selected asset type for this game
-        for &asset in assets {
-            download_single_asset(
-                client, game_id, game, asset, opts, &tx,
-            )
-            .await;
+    /// How the game went: unreachable if requests were made and none was answered.
+    fn outcome(&self) -> GameOutcome {
+        let error = self.error.lock().unwrap_or_else(PoisonError::into_inner).take();
+        match error {
+            Some((provider, error)) if !self.answered.load(Ordering::Relaxed) => {
+                GameOutcome::Unreachable { provider, error }
+            }
+            _ => GameOutcome::Finished,
         }
     }
+
+    /// The game's ID with provider `index`, or why it has none.
+    ///
+    /// # Errors
+    ///
+    /// Only a rejected API key is returned as an error (and the ID left to be looked up
+    /// again); other failures become the reason.
+    async fn id(&self, index: usize) -> Result<Result<u64, Miss>> {
+        let provider = self.providers[index];
+        let id = self.ids[index]
+            .get_or_try_init(|| async {
+                let found = provider.find_game(self.game, self.min_confidence).await;
+                self.record(index, &found);
+                match found {
+                    Ok(Resolution::Found(id)) => Ok(Ok(id)),
+                    Ok(Resolution::NotFound) => Ok(Err(Miss::new(format!("game not found on {}", provider.name())))),
+                    Ok(Resolution::Rejected(reason)) => Ok(Err(Miss::new(reason))),
+                    Err(e) if is_unauthorized(&e) => Err(e),
+                    Err(e) => Ok(Err(Miss::error("search error", &e))),
+                }
+            })
+            .await?;
+        Ok(id.clone())
+    }
+
+    /// Resolve with each provider in turn until one knows the game; returns the first
+    /// provider's reason if none does.
+    async fn resolve_any(&self) -> Result<Result<(), Miss>> {
+        let mut first_miss = None;
+        for index in 0..self.providers.len() {
+            match self.id(index).await? {
+                Ok(_) => return Ok(Ok(())),
+                Err(reason) => {
+                    first_miss.get_or_insert(reason);
+                }
+            }
+        }
+        Ok(Err(first_miss.unwrap_or_else(|| Miss::new("no art source for this game"))))
+    }
+}
+
+/// The asset picked for download, or why there is none.
+enum Pick<'a> {
+    Chosen(&'a dyn ArtProvider, ImageAsset),
+    Missing(Miss),
+    AuthExpired,
+}
+
+/// Ask each provider that knows the game for acceptable art, in order, and take the
+/// first provider's best candidate.
+async fn pick_asset<'a>(lookup: &GameLookup<'a>, game: &Game, asset: AssetType, opts: &DownloadOpts) -> Pick<'a> {
+    // A fetch error or empty list explains a miss better than a provider not knowing the game
+    let mut miss = None;
+    let mut unresolved = None;
+    for index in 0..lookup.providers.len() {
+        let id = match lookup.id(index).await {
+            Ok(Ok(id)) => id,
+            Ok(Err(reason)) => {
+                unresolved.get_or_insert(reason);
+                continue;
+            }
+            Err(_) => return Pick::AuthExpired,
+        };
+        let provider = lookup.providers[index];
+        let found = find_asset(provider, game, id, asset, opts).await;
+        lookup.record(index, &found);
+        match found {
+            Ok(Some(chosen)) => return Pick::Chosen(provider, chosen),
+            Ok(None) => {
+                miss.get_or_insert_with(|| Miss::new("no art found"));
+            }
+            Err(e) if is_unauthorized(&e) => return Pick::AuthExpired,
+            Err(e) => {
+                miss.get_or_insert_with(|| Miss::error("fetch error", &e));
+            }
+        }
+    }
+    Pick::Missing(miss.or(unresolved).unwrap_or_else(|| Miss::new("no art found")))
+}
+
+/// Fetch the candidates for one asset type and pick the best acceptable one.
+///
+/// Asset types with preferred dimensions try each in order until one yields acceptable art.
+async fn find_asset(
+    provider: &dyn ArtProvider,
+    game: &Game,
+    game_id: u64,
+    asset: AssetType,
+    opts: &DownloadOpts,
+) -> Result<Option<ImageAsset>> {
+    let preferred = opts.dimensions(asset);
+    let dimensions: Vec<Option<&str>> = if preferred.is_empty() {
+        vec![None]
+    } else {
+        preferred.iter().map(|d| Some(d.as_str())).collect()
+    };
+
+    for dims in dimensions {
+        let query = AssetQuery {
+            dimensions: dims,
+            animated: opts.allow_animated && asset != AssetType::Icon,
+            exclude_nsfw: opts.nsfw_filter,
+            exclude_humor: opts.humor_filter,
+            exclude_epilepsy: opts.epilepsy_filter,
+            extra: opts.extra_query.get(&asset).map_or(&[], Vec::as_slice),
+        };
+        let assets = provider
+            .list_assets(game, game_id, asset, query, opts.max_asset_pages)
+            .await?;
+        let candidates = filter_assets(&assets, opts);
+        if let Some(chosen) = choose_asset(candidates.into_iter(), opts, dims) {
+            return Ok(Some(chosen.clone()));
+        }
+    }
+    Ok(None)
+}
+
+/// `.tmp` files in use by this process, so no two downloads ever write the same one.
+static TEMP_FILES: Mutex<BTreeSet<PathBuf>> = Mutex::new(BTreeSet::new());
+
+/// How long a `.tmp` file may sit untouched before [`remove_stale_temp_files`] deletes it.
+const STALE_TEMP_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// A `.tmp` file claimed by one download until dropped (see [`prepare_temp_path`]).
+struct TempPath(PathBuf);
+
+impl std::ops::Deref for TempPath {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl AsRef<Path> for TempPath {
+    fn as_ref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempPath {
+    fn drop(&mut self) {
+        TEMP_FILES.lock().unwrap_or_else(PoisonError::into_inner).remove(&self.0);
+    }
+}
+
+/// The `.tmp` file an image is streamed into before being renamed into place, next to
+/// it, with its directory created.
+///
+/// The name carries a hash of the image URL, so an interrupted download is only ever
+/// resumed from a partial copy of the same image. Should another download be using that
+/// file already — the same image for the same game twice — this one gets a numbered
+/// name of its own.
+async fn prepare_temp_path(launcher: &dyn LauncherBackend, asset: AssetType, slug: &str, url: &str) -> Result<TempPath> {
+    let digest = Sha256::digest(url.as_bytes());
+    let tag = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    let target = asset_path(launcher, asset, slug)?;
+    let tmp_path = {
+        let mut claimed = TEMP_FILES.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut path = target.with_extension(format!("{tag:08x}.tmp"));
+        for n in 1.. {
+            if !claimed.contains(&path) {
+                break;
+            }
+            path = target.with_extension(format!("{tag:08x}-{n}.tmp"));
+        }
+        claimed.insert(path.clone());
+        TempPath(path)
+    };
+    if let Some(parent) = tmp_path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .wrap_err("mkdir failed")?;
+    }
+    Ok(tmp_path)
+}
+
+/// Delete the `.tmp` files left in the art and icon size directories by downloads that
+/// crashed or were killed and haven't been resumed within [`STALE_TEMP_AGE`]. Some of
+/// those directories are shared with other programs, so only our own are touched.
+pub fn remove_stale_temp_files(launcher: &dyn LauncherBackend) {
+    let icon_sizes = launcher
+        .icon_theme_dir()
+        .into_iter()
+        .flat_map(|theme| ICON_SIZES.map(|size| theme.join(format!("{size}x{size}/apps"))));
+    for dir in launcher.art_dirs().into_iter().chain(icon_sizes) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.filter_map(std::result::Result::ok) {
+            let ours = entry.file_name().to_str().is_some_and(is_own_temp_file);
+            let stale = entry
+                .metadata()
+                .and_then(|m| m.modified())
+                .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age > STALE_TEMP_AGE));
+            if ours && stale {
+                let _ = std::fs::remove_file(entry.path());
+            }
+        }
+    }
+}
+
+/// Whether a file name is one of our temporary files: a download's
+/// `<name>.<url tag>[-<n>].tmp` (see [`prepare_temp_path`]), or a scaled icon's
+/// `lutris_<slug>.png.tmp` (see [`install_icon_sizes`]) — or a download's validator
+/// kept beside it (see [`validator_path`]).
+fn is_own_temp_file(name: &str) -> bool {
+    let name = name.strip_suffix(".validator").unwrap_or(name);
+    let Some(stem) = name.strip_suffix(".tmp") else {
+        return false;
+    };
+    if name.starts_with("lutris_") && Path::new(stem).extension().is_some_and(|ext| ext == "png") {
+        return true;
+    }
+    let Some((_, tag)) = stem.rsplit_once('.') else {
+        return false;
+    };
+    let tag = match tag.split_once('-') {
+        Some((tag, n)) if !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit()) => tag,
+        Some(_) => return false,
+        None => tag,
+    };
+    tag.len() == 8 && tag.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Bytes read from the start of a download to tell its image format.
+const SNIFF_BYTES: u64 = 64 * 1024;
+
+/// Finish an atomic write: rename the fully downloaded `tmp_path` into place. Returns
+/// where it went, and where the art it replaced was backed up to.
+///
+/// The extension follows the image format (see [`image_extension`]); `mime` is the
+/// API-reported type, or empty if unknown. An error page saved in place of the image is
+/// deleted and reported as a failure instead, as is one that doesn't decode. With
+/// `convert_images`, WebP and APNG art is transcoded to PNG or JPEG first; icons in any
+/// format but PNG always are, to PNG. With `resize_art` oversized PNGs and JPEGs are
+/// shrunk (see [`resize`]). Icons going into the hicolor theme are installed at its other
+/// sizes as well. Art identical to what another game has saved becomes a copy of that
+/// file, sharing its blocks where the filesystem can (see [`link_or_copy`]).
+///
+/// Existing art for the asset is first moved into the run's backup directory; if that
+/// fails, nothing is replaced. Without one it is simply overwritten.
+async fn save_downloaded(
+    asset: AssetType,
+    slug: &str,
+    tmp_path: &Path,
+    mime: &str,
+    opts: &DownloadOpts,
+) -> Result<(PathBuf, Option<PathBuf>)> {
+    let mut head = Vec::new();
+    tokio::fs::File::open(tmp_path)
+        .await
+        .wrap_err("read failed")?
+        .take(SNIFF_BYTES)
+        .read_to_end(&mut head)
+        .await
+        .wrap_err("read failed")?;
+    if is_error_page(&head) {
+        let _ = tokio::fs::remove_file(tmp_path).await;
+        return Err(eyre!("got an HTML/error page instead of an image"));
+    }
+    let ext = image_extension(&head, mime);
+    let convert = opts.convert_images && asset != AssetType::Icon && matches!(ext, "webp" | "apng");
+    // Icons are always saved as .png, where anything but a PNG would be unreadable
+    let icon_to_png = asset == AssetType::Icon && !matches!(ext, "png" | "apng");
+    let resize_to = opts.resize_target(asset).map(|size| (opts.resize_art, size));
+    let checked = tokio::task::spawn_blocking({
+        let tmp_path = tmp_path.to_path_buf();
+        move || {
+            let mut image = decode_checked(&tmp_path)?;
+            let mut ext = ext;
+            let mut changed = false;
+            if convert {
+                ext = if image.color().has_alpha() { "png" } else { "jpg" };
+                changed = true;
+            }
+            if icon_to_png {
+                ext = "png";
+                changed = true;
+            }
+            // WebP and APNG stay as they are: they may be animated, and the only WebP
+            // encoder available is lossless
+            let resized = resize_to
+                .filter(|_| matches!(ext, "png" | "jpg"))
+                .and_then(|(mode, size)| resize(&image, mode, size));
+            if let Some(resized) = resized {
+                image = resized;
+                changed = true;
+            }
+            if changed {
+                write_image(&image, &tmp_path, ext)?;
+            }
+            Ok(ext)
+        }
+    })
+    .await
+    .wrap_err("image check panicked")?;
+    let ext = match checked {
+        Ok(ext) => ext,
+        Err(e) => {
+            let _ = tokio::fs::remove_file(tmp_path).await;
+            return Err(e);
+        }
+    };
+    // Identical art saved for another game is shared rather than stored twice; the
+    // download is only hashed if another game's art is the same size
+    let size = tokio::fs::metadata(tmp_path).await.map(|m| m.len()).ok();
+    if let Some(size) = size.filter(|&size| opts.has_art_of_size(slug, asset, size)) {
+        let path = tmp_path.to_owned();
+        let sha256 = tokio::task::spawn_blocking(move || manifest::file_sha256(&path))
+            .await
+            .ok()
+            .flatten();
+        if let Some(sha256) = sha256 {
+            let same = |e: &ManifestEntry| e.size.map_or(true, |s| s == size) && e.sha256.as_ref() == Some(&sha256);
+            if let Some((source, _)) = opts.identical_art(slug, asset, same).await {
+                link_or_copy(&source, tmp_path).await?;
+            }
+        }
+    }
+    put_in_place(asset, slug, tmp_path, ext, opts).await
+}
+
+/// Save a copy of the art another game has saved (see [`DownloadOpts::identical_art`])
+/// for `slug`, recording `entry` — that game's manifest entry — for it.
+async fn save_identical(
+    asset: AssetType,
+    slug: &str,
+    source: &Path,
+    entry: ManifestEntry,
+    opts: &DownloadOpts,
+) -> Result<PathBuf> {
+    let tmp_path = prepare_temp_path(&*opts.launcher, asset, slug, &entry.url).await?;
+    link_or_copy(source, &tmp_path).await?;
+    let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("png");
+    let (target, backup) = put_in_place(asset, slug, &tmp_path, ext, opts).await?;
+    let replaced = opts.recorded(slug, asset).filter(|_| backup.is_some()).map(Box::new);
+    let entry = ManifestEntry { backup, replaced, ..entry };
+    opts.record(slug, asset, entry.stamped(&target));
+    Ok(target)
+}
+
+/// Replace `tmp_path` with a copy of `source`: a reflink sharing its blocks where the
+/// filesystem supports them (Btrfs, XFS), so the copy takes no extra space until either
+/// file changes, or a plain copy elsewhere. Unlike a hard link, editing either file
+/// never touches the other.
+async fn link_or_copy(source: &Path, tmp_path: &Path) -> Result<()> {
+    let (source, tmp_path) = (source.to_owned(), tmp_path.to_owned());
+    tokio::task::spawn_blocking(move || {
+        let _ = std::fs::remove_file(&tmp_path);
+        if reflink(&source, &tmp_path).is_err() {
+            let _ = std::fs::remove_file(&tmp_path);
+            std::fs::copy(&source, &tmp_path).wrap_err("copy failed")?;
+        }
+        Ok(())
+    })
+    .await
+    .wrap_err("copy panicked")?
+}
+
+/// Create `target` as a clone of `source` sharing its blocks (`FICLONE`).
+fn reflink(source: &Path, target: &Path) -> std::io::Result<()> {
+    use std::os::fd::AsRawFd;
+
+    let source = std::fs::File::open(source)?;
+    let target = std::fs::OpenOptions::new().write(true).create_new(true).open(target)?;
+    // SAFETY: both descriptors stay open for the duration of the call, and FICLONE
+    // takes the source descriptor as its argument by value.
+    let result = unsafe { nix::libc::ioctl(target.as_raw_fd(), nix::libc::FICLONE, source.as_raw_fd()) };
+    if result == -1 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+/// Rename the checked `tmp_path` into place as the art for an asset, backing up the art
+/// it replaces. Returns where it went, and where the old art was backed up to.
+///
+/// Files are only ever replaced by renaming, never written in place.
+async fn put_in_place(
+    asset: AssetType,
+    slug: &str,
+    tmp_path: &Path,
+    ext: &str,
+    opts: &DownloadOpts,
+) -> Result<(PathBuf, Option<PathBuf>)> {
+    let target = if asset == AssetType::Icon {
+        asset_path(&*opts.launcher, asset, slug)?
+    } else {
+        asset_path_with_extension(&*opts.launcher, asset, slug, ext)?
+    };
+
+    // Only the file that is the art is backed up; copies under other extensions are
+    // stale, and dropped below
+    let existing = existing_asset_path(&*opts.launcher, asset, slug);
+    let backup = match (&existing, &opts.backup_dir) {
+        (Some(existing), Some(dir)) => Some(back_up(existing, asset, dir).await?),
+        _ => None,
+    };
+
+    if opts.durable_writes {
+        sync_to_disk(tmp_path).await?;
+    }
+    tokio::fs::rename(tmp_path, &target)
+        .await
+        .wrap_err("rename failed")?;
+    // Renaming a link onto another link of the same file leaves both in place
+    let _ = tokio::fs::remove_file(tmp_path).await;
+    if let Some(dir) = target.parent().filter(|_| opts.durable_writes) {
+        sync_to_disk(dir).await?;
+    }
+
+    // Drop a copy saved under another extension (e.g. a .jpg replaced by a .png)
+    for stale in candidate_paths(&*opts.launcher, asset, slug).into_iter().filter(|p| *p != target) {
+        let _ = tokio::fs::remove_file(stale).await;
+    }
+
+    if existing.is_some() && opts.clear_custom_art_flags {
+        let mut replaced = opts.replaced.lock().unwrap_or_else(PoisonError::into_inner);
+        replaced.insert((slug.to_owned(), asset));
+    }
+    let theme = opts.launcher.icon_theme_dir().filter(|_| asset == AssetType::Icon);
+    if let Some(theme) = theme {
+        opts.icons_saved.store(true, Ordering::Relaxed);
+        // The other sizes are extras: the icon itself is in place either way
+        if opts.hicolor_icon_sizes {
+            let (icon, owned_slug) = (target.clone(), slug.to_owned());
+            let installed = tokio::task::spawn_blocking(move || install_icon_sizes(&icon, &theme, &owned_slug))
+                .await
+                .unwrap_or_else(|_| Err(eyre!("icon resize panicked")));
+            if let Err(e) = installed {
+                opts.warn(format!("Could not install the other icon sizes for {slug}: {e}"));
+            }
+        }
+    }
+    Ok((target, backup))
+}
+
+/// Flush a file's contents — or for a directory, its entries, e.g. a rename into it — to
+/// disk.
+async fn sync_to_disk(path: &Path) -> Result<()> {
+    let file = tokio::fs::File::open(path)
+        .await
+        .wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+    file.sync_all()
+        .await
+        .wrap_err_with(|| format!("Failed to sync {} to disk", path.display()))
+}
+
+/// Hicolor sizes icons are installed at besides the primary `128x128`.
+const ICON_SIZES: [u32; 4] = [32, 48, 64, 256];
+
+/// Install `icon` in the hicolor `theme` at each of [`ICON_SIZES`], scaled down from it.
+/// Sizes bigger than the icon are removed instead of scaled up, so a smaller replacement
+/// leaves no stale ones behind.
+fn install_icon_sizes(icon: &Path, theme: &Path, slug: &str) -> Result<()> {
+    let image = decode_checked(icon)?;
+    for size in ICON_SIZES {
+        let path = theme.join(format!("{size}x{size}/apps/lutris_{slug}.png"));
+        if image.width().max(image.height()) < size {
+            let _ = std::fs::remove_file(&path);
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).wrap_err("Failed to create icon directory")?;
+        }
+        let tmp = path.with_extension("png.tmp");
+        write_image(&image.resize(size, size, image::imageops::FilterType::Lanczos3), &tmp, "png")?;
+        std::fs::rename(&tmp, &path).wrap_err("rename failed")?;
+    }
+    Ok(())
+}
+
+/// Rebuild the hicolor theme's icon cache, so menus pick up new icons right away.
+/// Returns what went wrong, if anything.
+fn update_icon_cache() -> Option<String> {
+    let theme = config::hicolor_dir().ok()?;
+    // Captured rather than inherited, so nothing gets printed over the TUI
+    let output = std::process::Command::new("gtk-update-icon-cache")
+        .args(["--force", "--ignore-theme-index"])
+        .arg(&theme)
+        .output();
+    match output {
+        Ok(out) if out.status.success() => None,
+        Ok(out) => Some(String::from_utf8_lossy(&out.stderr).trim().to_owned()),
+        Err(e) => Some(e.to_string()),
+    }
+}
+
+/// Fully decode a downloaded image, so a corrupt or truncated file is never renamed into
+/// place for Lutris to show as broken art.
+fn decode_checked(path: &Path) -> Result<image::DynamicImage> {
+    let image = image::ImageReader::open(path)
+        .and_then(image::ImageReader::with_guessed_format)
+        .wrap_err("read failed")?
+        .decode()
+        .map_err(|e| eyre!("corrupt image: {e}"))?;
+    if image.width() == 0 || image.height() == 0 {
+        return Err(eyre!("corrupt image: it has no pixels"));
+    }
+    Ok(image)
+}
+
+/// Shrink an image to `size`, or `None` if it is no bigger. [`ResizeArt::Crop`] only
+/// applies when both sides are bigger, so nothing is ever scaled up.
+fn resize(image: &image::DynamicImage, mode: ResizeArt, (width, height): (u32, u32)) -> Option<image::DynamicImage> {
+    let filter = image::imageops::FilterType::Lanczos3;
+    match mode {
+        ResizeArt::Fit if image.width() > width || image.height() > height => {
+            Some(image.resize(width, height, filter))
+        }
+        ResizeArt::Crop if image.width() > width && image.height() > height => {
+            Some(image.resize_to_fill(width, height, filter))
+        }
+        _ => None,
+    }
+}
+
+/// Rewrite `path` as a PNG or JPEG, per `ext`.
+fn write_image(image: &image::DynamicImage, path: &Path, ext: &str) -> Result<()> {
+    let mut out = std::io::BufWriter::new(std::fs::File::create(path).wrap_err("write failed")?);
+    if ext == "png" {
+        image.write_to(&mut out, image::ImageFormat::Png)
+    } else {
+        // The JPEG encoder only takes 8-bit channels
+        image
+            .to_rgb8()
+            .write_with_encoder(image::codecs::jpeg::JpegEncoder::new_with_quality(&mut out, 90))
+    }
+    .wrap_err("encoding the image failed")?;
+    out.flush().wrap_err("write failed")
+}
+
+/// This run's backup directory, named after when the run started: seconds and
+/// nanoseconds since the Unix epoch, and the process ID, so runs started within the same
+/// second (e.g. a retry) don't share one.
+fn run_backup_dir() -> Option<PathBuf> {
+    let started = SystemTime::now().duration_since(UNIX_EPOCH).ok()?;
+    let name = format!("{}-{:09}-{}", started.as_secs(), started.subsec_nanos(), std::process::id());
+    Some(config::backups_dir().ok()?.join(name))
+}
+
+/// When the run a backup directory belongs to started, from its name (see
+/// [`run_backup_dir`]); older versions named them after the second alone.
+fn backup_run_started(name: &str) -> Option<(u64, u32)> {
+    let mut parts = name.split('-');
+    let secs = parts.next()?.parse().ok()?;
+    let nanos = parts.next().map_or(Some(0), |nanos| nanos.parse().ok())?;
+    Some((secs, nanos))
+}
+
+/// The backup directory of the most recent run that replaced any art.
+pub fn last_backup_run() -> Option<PathBuf> {
+    std::fs::read_dir(config::backups_dir().ok()?)
+        .ok()?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let started = backup_run_started(path.file_name()?.to_str()?)?;
+            Some((started, path))
+        })
+        .max_by_key(|(started, _)| *started)
+        .map(|(_, path)| path)
+}
+
+/// Put back the art downloads replaced for a game: every asset whose manifest entry has
+/// a backup, or only those backed up into `run` if given. The downloaded file is
+/// deleted and the backup moved back into place.
+///
+/// Each restored asset's manifest entry goes back to the one its backup had, or is
+/// dropped if that art's origin isn't known. Returns how each restore went.
+pub fn restore_backups(
+    launcher: &dyn LauncherBackend,
+    manifest: &mut Manifest,
+    slug: &str,
+    run: Option<&Path>,
+) -> Vec<(AssetType, Result<Restored>)> {
+    let mut restored = Vec::new();
+    for &asset in AssetType::all() {
+        let Some(entry) = manifest.get(slug, asset).filter(|e| e.backup.is_some()).cloned() else {
+            continue;
+        };
+        let backup = entry.backup.unwrap_or_default();
+        if run.is_some_and(|run| !backup.starts_with(run)) {
+            continue;
+        }
+        let result = restore_backup(launcher, asset, slug, &backup);
+        if result.is_ok() {
+            match entry.replaced {
+                Some(previous) => manifest.record(slug, asset, *previous),
+                None => {
+                    manifest.remove(slug, asset);
+                }
+            }
+        }
+        restored.push((asset, result));
+    }
+    restored
+}
+
+/// A restored asset.
+#[derive(Debug)]
+pub struct Restored {
+    pub path: PathBuf,
+    /// What went wrong putting back the other icon sizes, if anything.
+    pub warning: Option<String>,
+}
+
+fn restore_backup(launcher: &dyn LauncherBackend, asset: AssetType, slug: &str, backup: &Path) -> Result<Restored> {
+    if !backup.exists() {
+        return Err(eyre!("backup {} no longer exists", backup.display()));
+    }
+    let target = match backup.extension().and_then(|e| e.to_str()) {
+        Some(ext) if asset != AssetType::Icon => asset_path_with_extension(launcher, asset, slug, ext)?,
+        _ => asset_path(launcher, asset, slug)?,
+    };
+    // Staged next to the art and renamed over it, so the current art stays until the
+    // backup is safely in its place
+    let digest = Sha256::digest(backup.as_os_str().as_encoded_bytes());
+    let tag = u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    let staged = target.with_extension(format!("{tag:08x}.tmp"));
+    let moved = std::fs::rename(backup, &staged).is_ok();
+    if !moved {
+        if let Err(e) = std::fs::copy(backup, &staged) {
+            let _ = std::fs::remove_file(&staged);
+            return Err(e).wrap_err_with(|| format!("Failed to restore {}", backup.display()));
+        }
+    }
+    if let Err(e) = std::fs::rename(&staged, &target) {
+        let _ = if moved { std::fs::rename(&staged, backup) } else { std::fs::remove_file(&staged) };
+        return Err(e).wrap_err_with(|| format!("Failed to restore {}", backup.display()));
+    }
+    for stale in candidate_paths(launcher, asset, slug).into_iter().filter(|p| *p != target && p.exists()) {
+        let _ = std::fs::remove_file(stale);
+    }
+    if !moved {
+        let _ = std::fs::remove_file(backup);
+    }
+    // Bring back the other hicolor sizes too, where the replaced icon had them
+    let mut warning = None;
+    if let Some(theme) = launcher.icon_theme_dir().filter(|_| asset == AssetType::Icon) {
+        let sized = ICON_SIZES
+            .iter()
+            .any(|size| theme.join(format!("{size}x{size}/apps/lutris_{slug}.png")).exists());
+        if sized {
+            warning = install_icon_sizes(&target, &theme, slug)
+                .err()
+                .map(|e| format!("Could not restore the other icon sizes: {e}"));
+        }
+    }
+    // Drop the run's directories once emptied, so the run before becomes the last one
+    for dir in backup.ancestors().skip(1).take(2) {
+        let _ = std::fs::remove_dir(dir);
+    }
+    Ok(Restored { path: target, warning })
+}
+
+/// Move an art file into `dir`, under a subdirectory for its asset type, returning its
+/// new path. Falls back to copying when `dir` is on another filesystem.
+async fn back_up(path: &Path, asset: AssetType, dir: &Path) -> Result<PathBuf> {
+    let file_name = path.file_name().ok_or_else(|| eyre!("{} has no file name", path.display()))?;
+    let backup = dir.join(asset.lutris_subdir()).join(file_name);
+    if let Some(parent) = backup.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .wrap_err("Failed to create backup directory")?;
+    }
+    if tokio::fs::rename(path, &backup).await.is_err() {
+        tokio::fs::copy(path, &backup)
+            .await
+            .wrap_err_with(|| format!("Failed to back up {}", path.display()))?;
+        tokio::fs::remove_file(path)
+            .await
+            .wrap_err_with(|| format!("Failed to back up {}", path.display()))?;
+    }
+    Ok(backup)
+}
+
+/// Send a status update for one (game, asset) pair.
+fn report(
+    tx: &mpsc::UnboundedSender<PipelineEvent>,
+    slug: &str,
+    asset: AssetType,
+    status: DownloadStatus,
+) {
+    let _ = tx.send(PipelineEvent::Progress(DownloadProgress {
+        game_slug: slug.to_owned(),
+        asset_type: asset,
+        status,
+    }));
+}
+
+/// Run the entire download pipeline for all games and selected asset types.
+///
+/// Games are processed in chunks of `opts.chunk_size`; after each chunk the
+/// checkpoint is saved and a [`PipelineEvent::ChunkFinished`] is sent. With
+/// `opts.resume`, games recorded in the previous checkpoint are skipped. Within a chunk,
+/// every (game, asset) pair downloads independently, up to `max_concurrent` at a time.
+///
+/// If the API key is rejected mid-run, a [`PipelineEvent::AuthExpired`] is sent and the
+/// pipeline waits for a replacement on `new_keys`, then carries on where it stopped.
+/// Without a key source (or once it closes) the run is aborted, leaving the checkpoint
+/// in place for `--resume`.
+///
+/// Once `opts.max_failure_streak` games in a row get nothing but request errors, the
+/// sources are taken to be down: a [`PipelineEvent::Aborted`] is sent and the run
+/// stops the same way, rather than failing every remaining game one by one.
+///
+/// Cancelling `opts.cancel` stops the run on the spot: requests in flight are dropped,
+/// the manifest is saved, and the checkpoint and partial downloads are kept for
+/// `--resume`. Nothing more is reported.
+pub async fn download_all(
+    providers: &[Box<dyn ArtProvider>],
+    games: &[Game],
+    assets: &HashSet<AssetType>,
+    opts: &DownloadOpts,
+    max_concurrent: usize,
+    tx: mpsc::UnboundedSender<PipelineEvent>,
+    new_keys: Option<mpsc::UnboundedReceiver<String>>,
+) {
+    let warnings = tx.clone();
+    tokio::select! {
+        biased;
+        () = opts.cancel.cancelled() => save_manifest(opts, &warnings),
+        () = run_pipeline(providers, games, assets, opts, max_concurrent, tx, new_keys) => {}
+    }
+    send_warnings(providers, opts, &warnings);
+    if opts.update_icon_cache && opts.icons_saved.load(Ordering::Relaxed) && !opts.cancel.is_cancelled() {
+        if let Ok(Some(problem)) = tokio::task::spawn_blocking(update_icon_cache).await {
+            let _ = warnings.send(PipelineEvent::Warning(format!("Could not update the icon cache: {problem}")));
+        }
+    }
+    if opts.steam_grid_export && !opts.cancel.is_cancelled() {
+        let (launcher, games, steam_dir) = (Arc::clone(&opts.launcher), games.to_vec(), opts.steam_dir.clone());
+        let exported = tokio::task::spawn_blocking(move || steam_grid::export(&*launcher, &games, steam_dir.as_deref())).await;
+        let message = match exported {
+            Ok(Ok(0)) | Err(_) => None,
+            Ok(Ok(copied)) => Some(format!("Copied {copied} images to Steam's grid folder")),
+            Ok(Err(e)) => Some(format!("Could not copy art to Steam: {e}")),
+        };
+        if let Some(message) = message {
+            let _ = warnings.send(PipelineEvent::Warning(message));
+        }
+    }
+    if opts.clear_custom_art_flags {
+        if let Some(message) = clear_custom_art_flags(games, opts).await {
+            let _ = warnings.send(PipelineEvent::Warning(message));
+        }
+    }
+}
+
+/// Clear Lutris's custom-art flags of the flagged grids and banners the run replaced,
+/// backing up its database into the run's backup directory first. Returns what to tell
+/// the user, if anything was replaced.
+async fn clear_custom_art_flags(games: &[Game], opts: &DownloadOpts) -> Option<String> {
+    let flags: Vec<(i64, AssetType)> = {
+        let replaced = opts.replaced.lock().unwrap_or_else(PoisonError::into_inner);
+        games
+            .iter()
+            .flat_map(|game| [AssetType::Grid, AssetType::Banner].map(|asset| (game, asset)))
+            .filter(|&(game, asset)| game.has_custom_art(asset) && replaced.contains(&(game.slug.clone(), asset)))
+            .map(|(game, asset)| (game.id, asset))
+            .collect()
+    };
+    if flags.is_empty() {
+        return None;
+    }
+    let (Some(path), Some(dir)) = (opts.db_path.clone(), opts.backup_dir.clone()) else {
+        return Some("Left Lutris's custom-art flags set: nowhere to back up its database".into());
+    };
+    // Backing up and writing the database blocks, for up to the busy timeout
+    let cleared = tokio::task::spawn_blocking(move || db::clear_custom_art_flags(&path, &flags, &dir))
+        .await
+        .unwrap_or_else(|e| Err(e.into()));
+    Some(match cleared {
+        Ok((cleared, backup)) => format!("Cleared Lutris's custom-art flag of {cleared} images (database backed up to {})", backup.display()),
+        Err(e) => format!("Could not clear Lutris's custom-art flags: {e}"),
+    })
+}
+
+/// The pipeline behind [`download_all`], run until it finishes or is cancelled.
+///
+/// Assets that fail with a transient error are set aside and, once every chunk is
+/// done, tried again in up to `opts.failed_asset_retries` further passes.
+async fn run_pipeline(
+    providers: &[Box<dyn ArtProvider>],
+    games: &[Game],
+    assets: &HashSet<AssetType>,
+    opts: &DownloadOpts,
+    max_concurrent: usize,
+    tx: mpsc::UnboundedSender<PipelineEvent>,
+    new_keys: Option<mpsc::UnboundedReceiver<String>>,
+) {
+    let mut run = Run {
+        providers,
+        opts,
+        tx,
+        new_keys,
+        semaphore: Semaphore::new(max_concurrent),
+        timings: RunTimings::default(),
+        journal: Journal::open(opts.resume),
+        failed: HashSet::new(),
+        streak: FailureStreak {
+            games: 0,
+            limit: opts.max_failure_streak,
+        },
+    };
+    let retry_later = opts.failed_asset_retries > 0;
+
+    let mut checkpoint = if opts.resume {
+        Checkpoint::load().unwrap_or_else(|e| {
+            let _ = run.tx.send(PipelineEvent::Warning(format!("{e:#} — resuming from the journal alone")));
+            Checkpoint::default()
+        })
+    } else {
+        Checkpoint::default()
+    };
+
+    let chunk_size = if opts.chunk_size == 0 {
+        games.len().max(1)
+    } else {
+        opts.chunk_size
+    };
+    let unique = unique_by_slug(games);
+    let games = prioritized(&unique, opts.priority.as_deref());
+    let chunks = games.len().div_ceil(chunk_size);
+    let mut retries: Vec<(&Game, AssetType)> = Vec::new();
+
+    for (index, chunk) in games.chunks(chunk_size).enumerate() {
+        let todo = skip_finished(&run, &checkpoint, chunk, assets);
+        let lookups = match resolve_chunk(&mut run, todo, assets, retry_later).await {
+            Ok(lookups) => lookups,
+            Err(reason) => return run.abort(reason),
+        };
+
+        // Every (game, asset) pair is its own task; a game's lookup is shared by its assets
+        let pending = (0..lookups.len())
+            .flat_map(|game| {
+                opts.assets_for(&lookups[game].game.slug, assets)
+                    .map(move |asset| (game, asset))
+            })
+            .collect();
+        let Some(transient) = run.download(&lookups, pending, retry_later).await else {
+            return;
+        };
+        retries.extend(transient.into_iter().map(|(game, asset)| (lookups[game].game, asset)));
+
+        // Games with assets that failed or are left to retry aren't done yet
+        let retrying: HashSet<&str> = retries.iter().map(|(game, _)| game.slug.as_str()).collect();
+        checkpoint.completed.extend(
+            chunk
+                .iter()
+                .filter(|g| !run.failed.contains(&g.slug) && !retrying.contains(g.slug.as_str()))
+                .map(|g| g.slug.clone()),
+        );
+        if let Err(e) = checkpoint.save() {
+            let _ = run.tx.send(PipelineEvent::Warning(format!("Could not save checkpoint: {e}")));
+        }
+        save_manifest(opts, &run.tx);
+        let _ = run.tx.send(PipelineEvent::ChunkFinished {
+            chunk: index + 1,
+            chunks,
+        });
+    }
+
+    for attempt in 1..=opts.failed_asset_retries {
+        if retries.is_empty() {
+            break;
+        }
+        let _ = run.tx.send(PipelineEvent::Warning(format!(
+            "Retrying {} assets that hit a network or server error ({attempt}/{})",
+            retries.len(),
+            opts.failed_asset_retries
+        )));
+        tokio::time::sleep(RETRY_PASS_DELAY).await;
+
+        // Look the games up afresh, in case that is what failed
+        let mut lookups: Vec<GameLookup<'_>> = Vec::new();
+        let mut pending = Vec::new();
+        for (game, asset) in retries.drain(..) {
+            let index = lookups.iter().position(|l| l.game.slug == game.slug).unwrap_or_else(|| {
+                lookups.push(GameLookup::new(providers, game, opts.min_match_confidence));
+                lookups.len() - 1
+            });
+            pending.push((index, asset));
+        }
+        let retry_later = attempt < opts.failed_asset_retries;
+        let Some(transient) = run.download(&lookups, pending, retry_later).await else {
+            return;
+        };
+        retries.extend(transient.into_iter().map(|(game, asset)| (lookups[game].game, asset)));
+        save_manifest(opts, &run.tx);
+    }
+
+    Checkpoint::clear();
+    Journal::clear();
+
+    let mut history = RunHistory::load();
+    history.merge(&run.timings);
+    if let Err(e) = history.save() {
+        let _ = run.tx.send(PipelineEvent::Warning(format!("Could not save run history: {e}")));
+    }
+}
+
+/// One game per slug. A game added under several runners shares its art files, so it's
+/// fetched once: as the first of its entries, with a store ID and custom-art flags from
+/// the others if they have them.
+fn unique_by_slug(games: &[Game]) -> Vec<Game> {
+    let mut unique: Vec<Game> = Vec::with_capacity(games.len());
+    let mut index = HashMap::new();
+    for game in games {
+        let Some(&i) = index.get(game.slug.as_str()) else {
+            index.insert(game.slug.as_str(), unique.len());
+            unique.push(game.clone());
+            continue;
+        };
+        let kept = &mut unique[i];
+        if kept.service_id.is_none() && game.service_id.is_some() {
+            kept.service.clone_from(&game.service);
+            kept.service_id.clone_from(&game.service_id);
+        }
+        kept.has_custom_banner |= game.has_custom_banner;
+        kept.has_custom_coverart |= game.has_custom_coverart;
+    }
+    unique
+}
+
+/// The games in the order they're fetched: as given, but with the `priority` game first.
+fn prioritized<'a>(games: &'a [Game], priority: Option<&str>) -> Vec<&'a Game> {
+    let mut games: Vec<&Game> = games.iter().collect();
+    if let Some(index) = games.iter().position(|g| Some(g.slug.as_str()) == priority) {
+        games[..=index].rotate_right(1);
+    }
+    games
+}
+
+/// Report the assets of `chunk` that the interrupted run finished as skipped, and return
+/// the games with assets left to fetch.
+fn skip_finished<'a>(
+    run: &Run<'_>,
+    checkpoint: &Checkpoint,
+    chunk: &[&'a Game],
+    assets: &HashSet<AssetType>,
+) -> Vec<&'a Game> {
+    let opts = run.opts;
+    let mut todo = Vec::new();
+    for &game in chunk {
+        let completed = checkpoint.completed.contains(&game.slug);
+        let done = |asset: &&AssetType| {
+            completed || opts.finished.get(&game.slug).is_some_and(|done| done.contains(*asset))
+        };
+        for &asset in assets.iter().filter(done) {
+            report(&run.tx, &game.slug, asset, DownloadStatus::Skipped("done in previous run".into()));
+        }
+        if !completed && opts.assets_for(&game.slug, assets).next().is_some() {
+            todo.push(game);
+        }
+    }
+    todo
+}
+
+/// Pause before each pass over assets that failed transiently, to give a struggling
+/// server a moment. Tests don't wait.
+const RETRY_PASS_DELAY: Duration = Duration::from_secs(if cfg!(test) { 0 } else { 5 });
+
+/// A run's state, shared by its chunks and retry passes.
+struct Run<'a> {
+    providers: &'a [Box<dyn ArtProvider>],
+    opts: &'a DownloadOpts,
+    tx: mpsc::UnboundedSender<PipelineEvent>,
+    /// Replacement API keys, when the user can be asked for one.
+    new_keys: Option<mpsc::UnboundedReceiver<String>>,
+    /// Limits downloads in flight across all games.
+    semaphore: Semaphore,
+    timings: RunTimings,
+    /// Records each asset as it finishes, for `--resume`.
+    journal: Journal,
+    /// Slugs of games with an asset that failed, kept out of the checkpoint.
+    failed: HashSet<String>,
+    streak: FailureStreak,
+}
+
+impl Run<'_> {
+    /// Download the `pending` (game index, asset) pairs of `lookups`, asking for a new
+    /// API key whenever the current one is rejected.
+    ///
+    /// Returns the pairs put back to pending by a transient error (only with
+    /// `retry_later`), or `None` once the run has been stopped.
+    async fn download(
+        &mut self,
+        lookups: &[GameLookup<'_>],
+        mut pending: Vec<(usize, AssetType)>,
+        retry_later: bool,
+    ) -> Option<Vec<(usize, AssetType)>> {
+        let mut transient = Vec::new();
+        let mut unfinished = vec![0usize; lookups.len()];
+        for &(game, _) in &pending {
+            unfinished[game] += 1;
+        }
+        while !pending.is_empty() {
+            let mut running: FuturesUnordered<_> = pending
+                .drain(..)
+                .map(|(game, asset)| {
+                    download_task(&self.semaphore, &lookups[game], game, asset, self.opts, &self.tx, retry_later)
+                })
+                .collect();
+            while let Some((game, asset, outcome, elapsed)) = running.next().await {
+                match outcome {
+                    AssetOutcome::AuthExpired => {
+                        pending.push((game, asset));
+                        continue;
+                    }
+                    AssetOutcome::Transient => transient.push((game, asset)),
+                    AssetOutcome::Finished => self.journal.record(&lookups[game].game.slug, asset),
+                    AssetOutcome::Failed => {
+                        self.failed.insert(lookups[game].game.slug.clone());
+                    }
+                }
+                send_warnings(self.providers, self.opts, &self.tx);
+                if let Some(elapsed) = elapsed {
+                    self.timings.assets.push((asset, elapsed));
+                }
+                unfinished[game] -= 1;
+                if unfinished[game] > 0 {
+                    continue;
+                }
+                if let Some(reason) = self.streak.record(&lookups[game].outcome()) {
+                    self.abort(reason);
+                    return None;
+                }
+            }
+
+            // Everything cut short by a rejected key is retried once a new one is in
+            if !pending.is_empty() && !renew_api_key(self.providers, &self.tx, self.new_keys.as_mut()).await {
+                for &(game, asset) in &pending {
+                    let slug = &lookups[game].game.slug;
+                    report(&self.tx, slug, asset, DownloadStatus::Failed("API key rejected".into()));
+                }
+                save_manifest(self.opts, &self.tx);
+                return None;
+            }
+        }
+        Some(transient)
+    }
+
+    /// Stop the run for `reason`, keeping its checkpoint for `--resume`.
+    fn abort(&self, reason: String) {
+        let _ = self.tx.send(PipelineEvent::Aborted(reason));
+        save_manifest(self.opts, &self.tx);
+    }
+}
+
+/// Pass on the warnings collected in `opts` and by the providers.
+fn send_warnings(providers: &[Box<dyn ArtProvider>], opts: &DownloadOpts, tx: &mpsc::UnboundedSender<PipelineEvent>) {
+    let from_providers = providers.iter().flat_map(|p| p.take_warnings());
+    for message in opts.take_warnings().into_iter().chain(from_providers) {
+        let _ = tx.send(PipelineEvent::Warning(message));
+    }
+}
+
+/// Persist the manifest, reporting failure as a warning.
+fn save_manifest(opts: &DownloadOpts, tx: &mpsc::UnboundedSender<PipelineEvent>) {
+    if let Err(e) = opts.save_manifest() {
+        let _ = tx.send(PipelineEvent::Warning(format!("Could not save manifest: {e}")));
+    }
+}
+
+/// Ask for a new API key after the current one was rejected, and hand it to every
+/// provider. Returns whether one took it.
+async fn renew_api_key(
+    providers: &[Box<dyn ArtProvider>],
+    tx: &mpsc::UnboundedSender<PipelineEvent>,
+    new_keys: Option<&mut mpsc::UnboundedReceiver<String>>,
+) -> bool {
+    let _ = tx.send(PipelineEvent::AuthExpired);
+    let new_key = match new_keys {
+        Some(rx) => rx.recv().await,
+        None => None,
+    };
+    // Offer the key to every provider; those without one just decline it
+    new_key.is_some_and(|key| {
+        let accepting = providers.iter().filter(|p| p.set_api_key(&key).is_ok());
+        accepting.count() > 0
+    })
+}
+
+/// Counts games in a row that got nothing but request errors, to notice the art
+/// sources being down.
+struct FailureStreak {
+    games: u32,
+    /// Streak at which to give up; `0` never does.
+    limit: u32,
+}
+
+impl FailureStreak {
+    /// Note how a game went; once `limit` games in a row were unreachable, returns why
+    /// the run should stop.
+    fn record(&mut self, outcome: &GameOutcome) -> Option<String> {
+        let GameOutcome::Unreachable { provider, error } = outcome else {
+            self.games = 0;
+            return None;
+        };
+        self.games += 1;
+        (self.limit > 0 && self.games >= self.limit).then(|| {
+            format!(
+                "{provider} appears unreachable — the last {} games all failed (last error: {error})",
+                self.games
+            )
+        })
+    }
+}
+
+/// Look up every game of a chunk before anything is downloaded, several at a time —
+/// the shared throttle still paces the requests. Games that can't be matched are
+/// reported straight away, unless a transient error is to blame and `retry_later` is
+/// set; the rest are returned, in order, for the download phase.
+///
+/// # Errors
+///
+/// Returns why to stop the run if the failure streak shows the sources to be unreachable.
+async fn resolve_chunk<'a>(
+    run: &mut Run<'a>,
+    games: Vec<&'a Game>,
+    assets: &HashSet<AssetType>,
+    retry_later: bool,
+) -> Result<Vec<GameLookup<'a>>, String> {
+    let (providers, opts, tx) = (run.providers, run.opts, &run.tx);
+    let mut queue = games.into_iter();
+    let mut running = FuturesOrdered::new();
+    let mut lookups = Vec::new();
+    loop {
+        while running.len() < RESOLVE_CONCURRENCY {
+            let Some(game) = queue.next() else { break };
+            running.push_back(resolve_game(providers, game, assets, opts, tx));
+        }
+        let Some((lookup, resolved, elapsed)) = running.next().await else { break };
+        run.timings.searches.push(elapsed);
+        match resolved {
+            Ok(Ok(())) => {
+                run.streak.record(&GameOutcome::Finished);
+            }
+            // Its assets fail the same way when downloaded, and are set aside for a retry
+            Ok(Err(miss)) if retry_later && miss.transient => {}
+            Ok(Err(miss)) => {
+                for asset in opts.assets_for(&lookup.game.slug, assets) {
+                    report(tx, &lookup.game.slug, asset, DownloadStatus::Failed(miss.reason.clone()));
+                }
+                run.failed.insert(lookup.game.slug.clone());
+                if let Some(reason) = run.streak.record(&lookup.outcome()) {
+                    return Err(reason);
+                }
+                continue;
+            }
+            // The download phase looks it up again and asks for a new key
+            Err(_) => {}
+        }
+        lookups.push(lookup);
+    }
+    Ok(lookups)
+}
+
+/// Look one game up with each provider in turn, timing the search.
+async fn resolve_game<'a>(
+    providers: &'a [Box<dyn ArtProvider>],
+    game: &'a Game,
+    assets: &HashSet<AssetType>,
+    opts: &DownloadOpts,
+    tx: &mpsc::UnboundedSender<PipelineEvent>,
+) -> (GameLookup<'a>, Result<Result<(), Miss>>, Duration) {
+    for asset in opts.assets_for(&game.slug, assets) {
+        report(tx, &game.slug, asset, DownloadStatus::Searching);
+    }
+    opts.wait_while_paused().await;
+    let lookup = GameLookup::new(providers, game, opts.min_match_confidence);
+    let started = Instant::now();
+    let resolved = lookup.resolve_any().await;
+    (lookup, resolved, started.elapsed())
+}
+
+/// Download one asset of a game looked up by [`resolve_chunk`] once the semaphore has
+/// a permit free. Returns the game's index and the asset with how it went, and how long
+/// it took unless it was skipped or cut short.
+async fn download_task(
+    semaphore: &Semaphore,
+    lookup: &GameLookup<'_>,
+    index: usize,
+    asset: AssetType,
+    opts: &DownloadOpts,
+    tx: &mpsc::UnboundedSender<PipelineEvent>,
+    retry_later: bool,
+) -> (usize, AssetType, AssetOutcome, Option<Duration>) {
+    // The semaphore is never closed
+    let _permit = semaphore.acquire().await;
+    opts.wait_while_paused().await;
+    // Only time real downloads; "already exists" skips would drag the averages down
+    let timed = opts.force || !asset_exists(&*opts.launcher, asset, &lookup.game.slug);
+    let started = Instant::now();
+    let outcome = download_single_asset(lookup, lookup.game, asset, opts, tx, retry_later).await;
+    let ended = matches!(outcome, AssetOutcome::Finished | AssetOutcome::Failed);
+    let elapsed = (timed && ended).then(|| started.elapsed());
+    (index, asset, outcome, elapsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::lutris::Lutris;
+    use crate::provider::fixture::{self, FixtureProvider};
+
+    /// A minimal PNG chunk with an empty body (the CRC is never checked).
+    fn chunk(kind: [u8; 4]) -> Vec<u8> {
+        let mut bytes = 0u32.to_be_bytes().to_vec();
+        bytes.extend_from_slice(&kind);
+        bytes.extend_from_slice(&[0; 4]);
+        bytes
+    }
+
+    #[test]
+    fn image_extension_follows_format() {
+        assert_eq!(image_extension(b"RIFF\0\0\0\0WEBPVP8X", ""), "webp");
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend(chunk(*b"IHDR"));
+        let mut apng = png.clone();
+        apng.extend(chunk(*b"acTL"));
+        apng.extend(chunk(*b"IDAT"));
+        png.extend(chunk(*b"IDAT"));
+
+        assert_eq!(image_extension(&apng, "image/png"), "apng");
+        assert_eq!(image_extension(&png, "image/jpeg"), "png");
+        assert_eq!(image_extension(b"\xff\xd8\xff\xe0", "image/png"), "jpg");
+        assert_eq!(image_extension(b"", "image/webp"), "webp");
+        assert_eq!(image_extension(b"", ""), "jpg");
+    }
+
+    #[test]
+    fn error_pages_are_not_images() {
+        assert!(is_error_page(b"\n  <!DOCTYPE html><html>"));
+        assert!(is_error_page(b"\xef\xbb\xbf<html>"));
+        assert!(is_error_page(br#"{"error":"not found"}"#));
+        assert!(!is_error_page(b"\x89PNG\r\n\x1a\n"));
+        assert!(!is_error_page(b"\xff\xd8\xff\xe0"));
+        assert!(!is_error_page(b""));
+
+        assert!(crate::api::client::is_error_page_type("text/html; charset=utf-8"));
+        assert!(crate::api::client::is_error_page_type("application/json"));
+        assert!(!crate::api::client::is_error_page_type("image/png"));
+    }
+
+    #[test]
+    fn filter_assets_applies_uploader_lists() {
+        let by = |id: u64, uploader: &str| ImageAsset {
+            id,
+            author: Some(crate::api::models::Author {
+                steam64: uploader.to_owned(),
+                ..Default::default()
+            }),
+            ..ImageAsset::default()
+        };
+        let assets = [by(1, "anyone"), by(2, "fan"), by(3, "troll"), by(4, "fan")];
+        let ids = |opts: &DownloadOpts| filter_assets(&assets, opts).iter().map(|a| a.id).collect::<Vec<_>>();
+
+        let mut opts = DownloadOpts::new(&Config::default(), Arc::new(Lutris), false, false);
+        opts.blocked_uploaders = vec!["troll".into()];
+        assert_eq!(ids(&opts), [1, 2, 4]);
+
+        opts.preferred_uploaders = vec!["fan".into()];
+        assert_eq!(ids(&opts), [2, 4]);
+
+        // Preferring someone with no art here changes nothing
+        opts.preferred_uploaders = vec!["absent".into()];
+        assert_eq!(ids(&opts), [1, 2, 4]);
+    }
+
+    /// A fixture provider offering one image of type `asset` for one game.
+    fn serving(slug: &str, id: u64, asset: AssetType, image: &[u8]) -> Vec<Box<dyn ArtProvider>> {
+        vec![Box::new(FixtureProvider::default().with_game(slug, id).with_art(id, asset, image))]
+    }
+
+    /// Pipeline options for a test run, with paths in the sandbox.
+    fn opts(config: &Config, force: bool, resume: bool) -> DownloadOpts {
+        fixture::sandbox();
+        DownloadOpts::new(config, Arc::new(Lutris), force, resume)
+    }
+
+    /// Run the whole pipeline for grids and collect what it reports.
+    async fn run(providers: &[Box<dyn ArtProvider>], games: &[Game], config: &Config) -> Vec<PipelineEvent> {
+        let _turn = fixture::pipeline_turn().await;
+        let opts = opts(config, false, false);
+        run_with(providers, games, &HashSet::from([AssetType::Grid]), &opts).await
+    }
+
+    /// Run the whole pipeline with `opts` and collect what it reports. The caller waits
+    /// for its [`fixture::pipeline_turn`].
+    async fn run_with(
+        providers: &[Box<dyn ArtProvider>],
+        games: &[Game],
+        assets: &HashSet<AssetType>,
+        opts: &DownloadOpts,
+    ) -> Vec<PipelineEvent> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        download_all(providers, games, assets, opts, 2, tx, None).await;
+        let mut events = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            events.push(event);
+        }
+        events
+    }
+
+    /// The last status reported for a game.
+    fn final_status<'a>(events: &'a [PipelineEvent], slug: &str) -> Option<&'a DownloadStatus> {
+        events.iter().rev().find_map(|e| match e {
+            PipelineEvent::Progress(p) if p.game_slug == slug => Some(&p.status),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn resize_only_shrinks() {
+        let size = |image: Option<image::DynamicImage>| image.map(|i| (i.width(), i.height()));
+        let hero = image::DynamicImage::new_rgb8(384, 100);
+
+        assert_eq!(size(resize(&hero, ResizeArt::Fit, (192, 62))), Some((192, 50)));
+        assert_eq!(size(resize(&hero, ResizeArt::Crop, (192, 62))), Some((192, 62)));
+        assert_eq!(size(resize(&hero, ResizeArt::Crop, (192, 124))), None);
+        assert_eq!(size(resize(&hero, ResizeArt::Fit, (384, 124))), None);
+    }
+
+    #[test]
+    fn only_our_temp_files_are_cleaned_up() {
+        assert!(is_own_temp_file("hollow-knight.0a1b2c3d.tmp"));
+        assert!(is_own_temp_file("lutris_celeste.0a1b2c3d-2.tmp"));
+        assert!(is_own_temp_file("lutris_celeste.png.tmp"));
+        assert!(is_own_temp_file("hollow-knight.0a1b2c3d.tmp.validator"));
+        assert!(!is_own_temp_file("firefox.tmp"));
+        assert!(!is_own_temp_file("org.gnome.Maps.tmp"));
+        assert!(!is_own_temp_file("steam.0a1b2c3d-x.tmp"));
+    }
+
+    #[tokio::test]
+    async fn temp_paths_are_never_shared() {
+        fixture::sandbox();
+        let first = prepare_temp_path(&Lutris, AssetType::Grid, "fixture-tmp", "u").await.unwrap();
+        let second = prepare_temp_path(&Lutris, AssetType::Grid, "fixture-tmp", "u").await.unwrap();
+        assert_ne!(*first, *second);
+
+        // Released once done with, so a later download resumes from the same file
+        let path = first.to_path_buf();
+        drop(first);
+        assert_eq!(*prepare_temp_path(&Lutris, AssetType::Grid, "fixture-tmp", "u").await.unwrap(), *path);
+    }
+
+    #[tokio::test]
+    async fn download_all_saves_fixture_art() {
+        let provider = FixtureProvider::default()
+            .with_game("fixture-found", 7)
+            .with_art(7, AssetType::Grid, &fixture::png(1, 1));
+        let providers: Vec<Box<dyn ArtProvider>> = vec![Box::new(provider)];
+        let games = [fixture::game("fixture-found", "Found"), fixture::game("fixture-missing", "Missing")];
+
+        let events = run(&providers, &games, &Config::default()).await;
+
+        let Some(DownloadStatus::Done(path)) = final_status(&events, "fixture-found") else {
+            panic!("grid not saved: {events:?}");
+        };
+        assert_eq!(path.extension().unwrap(), "png");
+        assert_eq!(std::fs::read(path).unwrap(), fixture::png(1, 1));
+        assert!(matches!(
+            final_status(&events, "fixture-missing"),
+            Some(DownloadStatus::Failed(reason)) if reason == "game not found on Fixture"
+        ));
+    }
+
+    #[tokio::test]
+    async fn games_sharing_a_slug_are_fetched_once() {
+        let providers = serving("fixture-twice", 15, AssetType::Grid, &fixture::png(1, 2));
+        let wine = Game { runner: Some("wine".into()), ..fixture::game("fixture-twice", "Twice") };
+        let native = Game { runner: Some("linux".into()), ..fixture::game("fixture-twice", "Twice") };
+
+        let events = run(&providers, &[wine, native], &Config::default()).await;
+
+        let statuses: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                PipelineEvent::Progress(p) if p.status.is_terminal() => Some(&p.status),
+                _ => None,
+            })
+            .collect();
+        assert!(matches!(statuses[..], [DownloadStatus::Done(_)]), "{statuses:?}");
+    }
+
+    #[tokio::test]
+    async fn download_all_rejects_corrupt_art() {
+        let mut truncated = fixture::png(4, 4);
+        truncated.truncate(truncated.len() - 20);
+        let providers = serving("fixture-corrupt", 8, AssetType::Grid, &truncated);
+
+        let events = run(&providers, &[fixture::game("fixture-corrupt", "Corrupt")], &Config::default()).await;
+
+        assert!(matches!(
+            final_status(&events, "fixture-corrupt"),
+            Some(DownloadStatus::Failed(reason)) if reason.contains("corrupt image")
+        ), "{events:?}");
+        assert!(!asset_exists(&Lutris, AssetType::Grid, "fixture-corrupt"));
+    }
+
+    #[tokio::test]
+    async fn webp_art_is_converted_when_asked() {
+        let mut webp = std::io::Cursor::new(Vec::new());
+        image::RgbaImage::new(2, 2).write_to(&mut webp, image::ImageFormat::WebP).unwrap();
+        let providers = serving("fixture-webp", 12, AssetType::Grid, webp.get_ref());
+        let config = Config {
+            convert_images: true,
+            ..Config::default()
+        };
+
+        let events = run(&providers, &[fixture::game("fixture-webp", "WebP")], &config).await;
+
+        let Some(DownloadStatus::Done(path)) = final_status(&events, "fixture-webp") else {
+            panic!("grid not saved: {events:?}");
+        };
+        assert_eq!(path.extension().unwrap(), "png");
+        assert_eq!(image::open(path).unwrap().color(), image::ColorType::Rgba8);
+    }
+
+    #[tokio::test]
+    async fn jpeg_icons_are_saved_as_png() {
+        let _turn = fixture::pipeline_turn().await;
+        let mut jpeg = std::io::Cursor::new(Vec::new());
+        image::RgbImage::new(2, 2).write_to(&mut jpeg, image::ImageFormat::Jpeg).unwrap();
+        let providers = serving("fixture-jpeg-icon", 14, AssetType::Icon, jpeg.get_ref());
+        let games = [fixture::game("fixture-jpeg-icon", "JPEG Icon")];
+        run_with(&providers, &games, &HashSet::from([AssetType::Icon]), &opts(&Config::default(), false, false)).await;
+
+        let saved = std::fs::read(asset_path(&Lutris, AssetType::Icon, "fixture-jpeg-icon").unwrap()).unwrap();
+        assert_eq!(image_extension(&saved, ""), "png");
+    }
+
+    #[tokio::test]
+    async fn download_all_gives_up_on_unreachable_sources() {
+        let providers: Vec<Box<dyn ArtProvider>> = vec![Box::new(FixtureProvider::unreachable())];
+        let games: Vec<Game> = (0..5).map(|i| fixture::game(&format!("fixture-down-{i}"), "Down")).collect();
+        let config = Config {
+            max_failure_streak: 3,
+            ..Config::default()
+        };
+
+        let events = run(&providers, &games, &config).await;
+
+        assert!(events.iter().any(|e| matches!(e, PipelineEvent::Aborted(_))));
+        assert!(final_status(&events, "fixture-down-2").is_some());
+        assert!(final_status(&events, "fixture-down-3").is_none());
+    }
+
+    #[tokio::test]
+    async fn failed_assets_are_tried_again_on_resume() {
+        let _turn = fixture::pipeline_turn().await;
+        let games = [fixture::game("fixture-resumed", "Resumed"), fixture::game("fixture-outage", "Outage")];
+        let assets = HashSet::from([AssetType::Grid]);
+        let config = Config { max_failure_streak: 1, ..Config::default() };
+
+        // Not found, then the run stops at an outage before it completes
+        let providers: Vec<Box<dyn ArtProvider>> = vec![Box::new(FixtureProvider::default().with_outage("fixture-outage"))];
+        let events = run_with(&providers, &games, &assets, &opts(&config, false, false)).await;
+        assert!(events.iter().any(|e| matches!(e, PipelineEvent::Aborted(_))), "{events:?}");
+        assert!(matches!(final_status(&events, "fixture-resumed"), Some(DownloadStatus::Failed(_))));
+
+        let providers = serving("fixture-resumed", 16, AssetType::Grid, &fixture::png(1, 3));
+        let events = run_with(&providers, &games, &assets, &opts(&config, false, true)).await;
+        assert!(matches!(final_status(&events, "fixture-resumed"), Some(DownloadStatus::Done(_))), "{events:?}");
+    }
+
+    #[tokio::test]
+    async fn transient_misses_are_retried_at_the_end() {
+        let provider = FixtureProvider::default()
+            .with_game("fixture-hiccup", 19)
+            .with_art(19, AssetType::Grid, &fixture::png(1, 5))
+            .with_hiccup("fixture-hiccup");
+        let providers: Vec<Box<dyn ArtProvider>> = vec![Box::new(provider)];
+
+        let events = run(&providers, &[fixture::game("fixture-hiccup", "Hiccup")], &Config::default()).await;
+        let statuses: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                PipelineEvent::Progress(p) if p.game_slug == "fixture-hiccup" => Some(&p.status),
+                _ => None,
+            })
+            .collect();
+        assert!(statuses.iter().any(|s| matches!(s, DownloadStatus::Pending)), "{events:?}");
+        assert!(events.iter().any(|e| matches!(e, PipelineEvent::Warning(w) if w.starts_with("Retrying 1 assets"))));
+        assert!(matches!(final_status(&events, "fixture-hiccup"), Some(DownloadStatus::Done(_))), "{events:?}");
+    }
+
+    #[tokio::test]
+    async fn games_with_failed_assets_are_not_checkpointed() {
+        let _turn = fixture::pipeline_turn().await;
+        let games = ["fixture-checkpointed", "fixture-unfound", "fixture-down"].map(|slug| fixture::game(slug, "Chunked"));
+        let config = Config { max_failure_streak: 1, chunk_size: 1, ..Config::default() };
+        let provider = FixtureProvider::default()
+            .with_game("fixture-checkpointed", 18)
+            .with_art(18, AssetType::Grid, &fixture::png(1, 4))
+            .with_outage("fixture-down");
+        let providers: Vec<Box<dyn ArtProvider>> = vec![Box::new(provider)];
+
+        // Stops at the outage, leaving the checkpoint of the chunks before it
+        let events = run_with(&providers, &games, &HashSet::from([AssetType::Grid]), &opts(&config, false, false)).await;
+        assert!(events.iter().any(|e| matches!(e, PipelineEvent::Aborted(_))), "{events:?}");
+        let checkpoint = Checkpoint::load().unwrap();
+        assert_eq!(checkpoint.completed, BTreeSet::from(["fixture-checkpointed".to_owned()]));
+    }
+
+    #[tokio::test]
+    async fn replaced_art_is_backed_up_and_restored() {
+        let _turn = fixture::pipeline_turn().await;
+        let games = [fixture::game("fixture-restore", "Restore")];
+        let assets = HashSet::from([AssetType::Grid]);
+        let (first, newer) = (fixture::png(1, 1), fixture::png(2, 1));
+        for (art, force) in [(&first, false), (&newer, true)] {
+            let providers = serving("fixture-restore", 11, AssetType::Grid, art);
+            run_with(&providers, &games, &assets, &opts(&Config::default(), force, false)).await;
+        }
+        let saved = existing_asset_path(&Lutris, AssetType::Grid, "fixture-restore").unwrap();
+        assert_eq!(std::fs::read(&saved).unwrap(), newer);
+
+        let mut manifest = Manifest::load().unwrap();
+        let restored = restore_backups(&Lutris, &mut manifest, "fixture-restore", None);
+
+        assert!(matches!(restored.as_slice(), [(AssetType::Grid, Ok(_))]));
+        assert_eq!(std::fs::read(&saved).unwrap(), first);
+    }
+
+    #[test]
+    fn disk_space_estimates_count_only_our_art() {
+        fixture::sandbox();
+        let games = [fixture::game("fixture-estimate", "Estimate")];
+        let grid = asset_path(&Lutris, AssetType::Grid, "fixture-estimate").unwrap();
+        std::fs::create_dir_all(grid.parent().unwrap()).unwrap();
+        std::fs::write(&grid, [0; 1000]).unwrap();
+        let others = [grid.with_extension("0badc0de.tmp"), grid.with_file_name("another-app.jpg")];
+        for other in &others {
+            std::fs::write(other, [0; 10_000]).unwrap();
+        }
+        assert_eq!(average_asset_size(&Lutris, AssetType::Grid, &games), 1000);
+        for other in others {
+            std::fs::remove_file(other).unwrap();
+        }
+
+        // Running low only warns, unless min_free_space_mb is set
+        let heroes = HashSet::from([AssetType::Hero]);
+        let config = Config { low_disk_space_mb: 1 << 40, ..Config::default() };
+        assert!(preflight_disk_space(&Lutris, &games, &heroes, false, &config).unwrap().is_some());
+        let config = Config { min_free_space_mb: 1 << 40, ..config };
+        assert!(preflight_disk_space(&Lutris, &games, &heroes, false, &config).is_err());
+    }
+
+    #[tokio::test]
+    async fn updates_are_art_that_would_be_picked_differently() {
+        let _turn = fixture::pipeline_turn().await;
+        let game = fixture::game("fixture-updates", "Updates");
+        let assets = HashSet::from([AssetType::Grid]);
+        let saved = serving("fixture-updates", 21, AssetType::Grid, &fixture::png(1, 1));
+        let opts = opts(&Config::default(), false, false);
+        run_with(&saved, std::slice::from_ref(&game), &assets, &opts).await;
+
+        assert!(check_for_updates(&saved, &game, &assets, &opts).await.unwrap().is_empty());
+
+        let newer = serving("fixture-updates", 22, AssetType::Grid, &fixture::png(2, 1));
+        let updates = check_for_updates(&newer, &game, &assets, &opts).await.unwrap();
+        let [(AssetType::Grid, entry, best)] = updates.as_slice() else {
+            panic!("expected one grid update, got {}", updates.len());
+        };
+        assert_eq!((entry.url.as_str(), best.url.as_str()), ("fixture://21/coverart", "fixture://22/coverart"));
+        // Nothing is downloaded
+        let path = existing_asset_path(&Lutris, AssetType::Grid, "fixture-updates").unwrap();
+        assert_eq!(std::fs::read(path).unwrap(), fixture::png(1, 1));
+    }
+
+    #[tokio::test]
+    async fn only_the_art_itself_is_backed_up() {
+        let _turn = fixture::pipeline_turn().await;
+        let path = |ext| asset_path_with_extension(&Lutris, AssetType::Grid, "fixture-stale", ext).unwrap();
+        std::fs::create_dir_all(path("jpg").parent().unwrap()).unwrap();
+        std::fs::write(path("jpg"), fixture::png(1, 1)).unwrap();
+        std::fs::write(path("png"), fixture::png(1, 2)).unwrap();
+
+        let providers = serving("fixture-stale", 17, AssetType::Grid, &fixture::png(2, 2));
+        let opts = opts(&Config::default(), true, false);
+        run_with(&providers, &[fixture::game("fixture-stale", "Stale")], &HashSet::from([AssetType::Grid]), &opts).await;
+
+        // The .jpg was the art; the stale .png is gone, not backed up over it
+        assert!(!path("jpg").exists());
+        assert_eq!(std::fs::read(path("png")).unwrap(), fixture::png(2, 2));
+        let backups = opts.backup_dir.as_ref().unwrap().join(AssetType::Grid.lutris_subdir());
+        let backed_up: Vec<_> = std::fs::read_dir(backups).unwrap().map(|e| e.unwrap().file_name()).collect();
+        assert_eq!(backed_up, ["fixture-stale.jpg"]);
+
+        let restored = restore_backups(&Lutris, &mut Manifest::load().unwrap(), "fixture-stale", None);
+        assert!(matches!(restored.as_slice(), [(AssetType::Grid, Ok(_))]));
+        assert_eq!(std::fs::read(path("jpg")).unwrap(), fixture::png(1, 1));
+        assert!(!path("png").exists());
+    }
+
+    #[test]
+    fn runs_get_backup_directories_of_their_own() {
+        let config = Config::default();
+        assert_ne!(opts(&config, false, false).backup_dir, opts(&config, false, false).backup_dir);
+        assert_eq!(backup_run_started("1700000000"), Some((1_700_000_000, 0)));
+        assert_eq!(backup_run_started("1700000000-000000042-1234"), Some((1_700_000_000, 42)));
+        assert_eq!(backup_run_started("notes"), None);
+    }
+
+    #[tokio::test]
+    async fn identical_art_is_copied_not_downloaded_again() {
+        let _turn = fixture::pipeline_turn().await;
+        let provider = FixtureProvider::default()
+            .with_game("fixture-wine", 13)
+            .with_game("fixture-native", 13)
+            .with_art(13, AssetType::Grid, &fixture::png(3, 2));
+        let providers: Vec<Box<dyn ArtProvider>> = vec![Box::new(provider)];
+        let assets = HashSet::from([AssetType::Grid]);
+        let opts = opts(&Config::default(), false, false);
+        for slug in ["fixture-wine", "fixture-native"] {
+            run_with(&providers, &[fixture::game(slug, "Twice")], &assets, &opts).await;
+        }
+
+        let path = |slug| existing_asset_path(&Lutris, AssetType::Grid, slug).unwrap();
+        assert_eq!(std::fs::read(path("fixture-wine")).unwrap(), std::fs::read(path("fixture-native")).unwrap());
+        // A copy, not a link: editing one game's art leaves the other's alone
+        std::fs::write(path("fixture-wine"), b"edited").unwrap();
+        assert_eq!(std::fs::read(path("fixture-native")).unwrap(), fixture::png(3, 2));
+    }
+
+    #[tokio::test]
+    async fn download_all_stops_when_cancelled() {
+        let _turn = fixture::pipeline_turn().await;
+        let providers = serving("fixture-cancelled", 9, AssetType::Grid, &fixture::png(1, 1));
+        let games = [fixture::game("fixture-cancelled", "Cancelled")];
+        let opts = opts(&Config::default(), false, false);
+
+        opts.cancel.cancel();
+        run_with(&providers, &games, &HashSet::from([AssetType::Grid]), &opts).await;
+
+        assert!(!asset_exists(&Lutris, AssetType::Grid, "fixture-cancelled"));
+    }
 }
@@ -1,15 +1,19 @@
 /// Async event system — decouples terminal input from the render/update loop.
 ///
 /// Spawns background tasks for crossterm event polling and a periodic tick,
-/// then exposes a unified `AppEvent` stream consumed by the main loop.
+/// then exposes a unified `AppEvent` stream consumed by the main loop. The launcher's
+/// library file can be watched too, for `AppEvent::GamesChanged`.
+use std::path::Path;
 use std::time::Duration;
 
-use color_eyre::eyre::{Result, eyre};
+use color_eyre::eyre::{Context, Result, eyre};
 use crossterm::event::{Event, EventStream};
 use futures::StreamExt;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
 use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
-use crate::api::models::DownloadProgress;
+use crate::api::models::PipelineEvent;
+use crate::db::Game;
 
 /// Unified event type consumed by the main application loop.
 #[derive(Debug)]
@@ -18,17 +22,23 @@ pub enum AppEvent {
     Key(crossterm::event::KeyEvent),
     /// Periodic tick for UI animations (spinners, etc.).
     Tick,
-    /// Progress update from a background download task.
-    Download(DownloadProgress),
+    /// Update from the background download pipeline.
+    Pipeline(PipelineEvent),
     /// Terminal was resized.
     #[allow(dead_code)]
     Resize(u16, u16),
+    /// The watched library file was written to, e.g. by Lutris installing a game.
+    GamesChanged,
+    /// The launcher's library, reloaded after it changed — or why it couldn't be.
+    GamesLoaded(Result<Vec<Game>, String>),
 }
 
 /// Manages event sources and exposes a single receiver.
 pub struct EventHandler {
     rx: UnboundedReceiver<AppEvent>,
     tx: UnboundedSender<AppEvent>,
+    /// Watcher on the library file, once [`EventHandler::watch`] is called.
+    watcher: Option<RecommendedWatcher>,
 }
 
 impl EventHandler {
@@ -42,10 +52,7 @@ impl EventHandler {
         let tx_input = tx.clone();
         tokio::spawn(async move {
             let mut reader = EventStream::new();
-            loop {
-                let Some(event_result) = reader.next().await else {
-                    break;
-                };
+            while let Some(event_result) = reader.next().await {
                 let Ok(event) = event_result else {
                     continue;
                 };
@@ -72,7 +79,42 @@ impl EventHandler {
             }
         });
 
-        Self { rx, tx }
+        Self { rx, tx, watcher: None }
+    }
+
+    /// Send `AppEvent::GamesChanged` whenever `path` (or its `SQLite` journal) changes.
+    /// A write usually comes in several events, so receivers should wait for them to
+    /// settle.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file's directory can't be watched.
+    pub fn watch(&mut self, path: &Path) -> Result<()> {
+        let dir = path.parent().ok_or_else(|| eyre!("{} has no parent directory", path.display()))?;
+        let name = path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let journal = format!("{name}-");
+        let tx = self.tx.clone();
+        // The directory is watched, as SQLite may write through a journal next to the file
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let Ok(event) = event else {
+                return;
+            };
+            let writes = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_));
+            let ours = event.paths.iter().any(|p| {
+                p.file_name()
+                    .and_then(|f| f.to_str())
+                    .is_some_and(|f| f == name || f.starts_with(&journal))
+            });
+            if writes && ours {
+                let _ = tx.send(AppEvent::GamesChanged);
+            }
+        })
+        .wrap_err("Failed to start file watcher")?;
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .wrap_err_with(|| format!("Failed to watch {}", dir.display()))?;
+        self.watcher = Some(watcher);
+        Ok(())
     }
 
     /// Get a clone of the sender — used by download tasks to send progress events.
@@ -0,0 +1,119 @@
+/// The assets the last run failed to fetch.
+///
+/// Written whenever a run ends, replacing the previous list's entries for what the run
+/// covered, so `--retry-failed` (or `r` on the summary screen) can go over just those
+/// instead of the whole library.
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::AssetType;
+use crate::config;
+
+/// Failed (game, asset) pairs.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct FailedAssets {
+    /// Asset types that failed, by game slug.
+    pub games: BTreeMap<String, BTreeSet<AssetType>>,
+}
+
+impl FailedAssets {
+    /// Location of the failure list.
+    fn path() -> Result<PathBuf> {
+        Ok(config::app_data_dir()?.join("failed.json"))
+    }
+
+    /// Load the last run's failures, or none if there is no list or it can't be read.
+    pub fn load() -> Self {
+        Self::path()
+            .ok()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the list, replacing the previous run's.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).wrap_err("Failed to create data directory")?;
+        }
+        let content = serde_json::to_string(self).wrap_err("Failed to serialize failed assets")?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, content)
+            .wrap_err_with(|| format!("Failed to write failed assets to {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path).wrap_err("Failed to move failed assets into place")?;
+        Ok(())
+    }
+
+    /// Save this run's failures in place of the saved ones it `covered` — the (game,
+    /// asset) pairs it fetched — keeping the rest, so a run narrowed with `--game`
+    /// doesn't forget the other games' failures.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save_merged(&self, covered: impl Fn(&str, AssetType) -> bool) -> Result<()> {
+        self.merged_into(Self::load(), covered).save()
+    }
+
+    /// These failures, plus those of `saved` that weren't `covered`.
+    fn merged_into(&self, saved: Self, covered: impl Fn(&str, AssetType) -> bool) -> Self {
+        let mut merged = self.clone();
+        for (slug, assets) in saved.games {
+            for asset in assets.into_iter().filter(|&asset| !covered(&slug, asset)) {
+                merged.insert(&slug, asset);
+            }
+        }
+        merged
+    }
+
+    /// Record a failed asset.
+    pub fn insert(&mut self, slug: &str, asset: AssetType) {
+        self.games.entry(slug.to_owned()).or_default().insert(asset);
+    }
+
+    /// Whether this asset of the game failed.
+    pub fn contains(&self, slug: &str, asset: AssetType) -> bool {
+        self.games.get(slug).is_some_and(|assets| assets.contains(&asset))
+    }
+
+    /// Number of failed (game, asset) pairs.
+    pub fn len(&self) -> usize {
+        self.games.values().map(BTreeSet::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn narrowed_runs_keep_the_other_failures() {
+        let mut saved = FailedAssets::default();
+        saved.insert("celeste", AssetType::Grid);
+        saved.insert("hollow-knight", AssetType::Grid);
+        saved.insert("hollow-knight", AssetType::Icon);
+        let mut run = FailedAssets::default();
+        run.insert("hollow-knight", AssetType::Hero);
+
+        // A run of hollow-knight's grids and heroes: its grid got through this time
+        let covered = |slug: &str, asset| slug == "hollow-knight" && asset != AssetType::Icon;
+        let merged = run.merged_into(saved, covered);
+        assert!(merged.contains("celeste", AssetType::Grid));
+        assert!(!merged.contains("hollow-knight", AssetType::Grid));
+        assert!(merged.contains("hollow-knight", AssetType::Icon));
+        assert!(merged.contains("hollow-knight", AssetType::Hero));
+        assert_eq!(merged.len(), 3);
+    }
+}
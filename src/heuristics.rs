@@ -0,0 +1,132 @@
+/// Heuristics for spotting Lutris entries that aren't games, and for naming games found
+/// as plain folders.
+///
+/// Lutris libraries often contain store launchers and runner tools ("Battle.net",
+/// "Wine Configuration", `DOSBox` configs). Searching `SteamGridDB` for these wastes API
+/// calls and usually grabs the wrong art, so they're skipped by default.
+///
+/// Names that are exactly a launcher or store client (compared case-insensitively).
+const LAUNCHER_NAMES: &[&str] = &[
+    "amazon games",
+    "battle.net",
+    "ea app",
+    "epic games store",
+    "gog galaxy",
+    "itch.io",
+    "origin",
+    "rockstar games launcher",
+    "steam",
+    "ubisoft connect",
+    "uplay",
+];
+
+/// Fragments that mark a configuration tool or launcher anywhere in the name.
+const NON_GAME_FRAGMENTS: &[&str] = &[
+    "battle.net",
+    "control panel",
+    "dosbox config",
+    "launcher",
+    "regedit",
+    "uninstall",
+    "wine configuration",
+    "winecfg",
+    "winetricks",
+];
+
+/// Whether a game entry looks like a launcher or tool rather than a game.
+pub fn is_probably_not_a_game(name: &str, slug: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    LAUNCHER_NAMES.contains(&name.as_str())
+        || NON_GAME_FRAGMENTS.iter().any(|f| name.contains(f))
+        || slug.contains("battlenet")
+}
+
+/// A game's name guessed from the folder it's installed in: bracketed notes ("[GOG]",
+/// "(2019)") and everything from a version number on are dropped, separators become
+/// spaces, and `CamelCase` words are split — `Hollow_Knight_v1.5.78 [GOG]` and
+/// `HollowKnight` both give "Hollow Knight".
+pub fn name_from_folder(folder: &str) -> String {
+    let mut plain = String::new();
+    let mut depth = 0_u32;
+    for c in folder.chars() {
+        match c {
+            '[' | '(' | '{' => depth += 1,
+            ']' | ')' | '}' => depth = depth.saturating_sub(1),
+            _ if depth == 0 => plain.push(c),
+            _ => {}
+        }
+    }
+
+    // Dots only separate words in names without spaces ("Hollow.Knight")
+    let dots_separate = !plain.contains(' ');
+    let is_version = |word: &str| match word.strip_prefix(['v', 'V']) {
+        Some(number) => number.starts_with(|c: char| c.is_ascii_digit()),
+        None => word.contains('.') && word.chars().all(|c| c.is_ascii_digit() || c == '.'),
+    };
+    let is_separator = |c: char| c.is_whitespace() || c == '_' || (dots_separate && c == '.');
+    // CamelCase only separates words in names without separators ("HollowKnight", but
+    // not "SteamWorld Dig")
+    let camel_case = !plain.trim().contains(is_separator);
+    let words = plain
+        .split(is_separator)
+        .filter(|w| !w.is_empty())
+        .take_while(|w| !is_version(w));
+
+    let mut name = String::new();
+    for word in words {
+        if !name.is_empty() {
+            name.push(' ');
+        }
+        let mut prev = ' ';
+        for c in word.chars() {
+            if camel_case && prev.is_lowercase() && c.is_uppercase() {
+                name.push(' ');
+            }
+            name.push(c);
+            prev = c;
+        }
+    }
+    if name.is_empty() { folder.trim().to_owned() } else { name }
+}
+
+/// A Lutris-style slug for a game name: lowercase words joined by hyphens, with
+/// apostrophes dropped ("Assassin's Creed" gives `assassins-creed`).
+pub fn slugify(name: &str) -> String {
+    name.to_lowercase()
+        .replace(['\'', '’'], "")
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn launchers_and_tools_are_flagged() {
+        assert!(is_probably_not_a_game("Battle.net", "battlenet"));
+        assert!(is_probably_not_a_game("Wine Configuration", "wine-configuration"));
+        assert!(is_probably_not_a_game("Epic Games Store", "epic-games-store"));
+        assert!(is_probably_not_a_game("Rockstar Games Launcher", "rockstar-games-launcher"));
+    }
+
+    #[test]
+    fn regular_games_are_not_flagged() {
+        assert!(!is_probably_not_a_game("Hollow Knight", "hollow-knight"));
+        assert!(!is_probably_not_a_game("Steamworld Dig", "steamworld-dig"));
+        assert!(!is_probably_not_a_game("Origin of the Species", "origin-of-the-species"));
+    }
+
+    #[test]
+    fn folder_names_become_game_names() {
+        assert_eq!(name_from_folder("Hollow_Knight_v1.5.78 [GOG]"), "Hollow Knight");
+        assert_eq!(name_from_folder("Hollow.Knight.v1.5"), "Hollow Knight");
+        assert_eq!(name_from_folder("HollowKnight"), "Hollow Knight");
+        assert_eq!(slugify(&name_from_folder("SteamWorld Dig")), "steamworld-dig");
+        assert_eq!(name_from_folder("Half-Life 2 1.0.2"), "Half-Life 2");
+        assert_eq!(name_from_folder("Doom 3 (2004)"), "Doom 3");
+        assert_eq!(slugify("Assassin's Creed: Origins"), "assassins-creed-origins");
+    }
+}
@@ -0,0 +1,145 @@
+/// Persisted run history — average time per search and per asset type.
+///
+/// Updated at the end of every run and used to estimate how long the next one will
+/// take, which is far more accurate than extrapolating from the first few items.
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use color_eyre::eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::AssetType;
+use crate::config;
+use crate::db::Game;
+use crate::download;
+use crate::launcher::LauncherBackend;
+
+/// Samples beyond this count are weighted as this many, so averages keep adapting to
+/// changes in network speed or API load.
+const MAX_WEIGHT: u64 = 500;
+
+/// Running mean of a duration.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Timing {
+    pub mean_secs: f64,
+    pub samples: u64,
+}
+
+impl Timing {
+    /// Fold a single measurement into the mean.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn add(&mut self, elapsed: Duration) {
+        let weight = self.samples.min(MAX_WEIGHT) as f64;
+        self.mean_secs = (self.mean_secs * weight + elapsed.as_secs_f64()) / (weight + 1.0);
+        self.samples += 1;
+    }
+
+    /// The mean, if at least one sample was recorded.
+    pub fn mean(self) -> Option<f64> {
+        (self.samples > 0).then_some(self.mean_secs)
+    }
+}
+
+/// Average timings across all previous runs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunHistory {
+    /// Time to resolve a game's `SteamGridDB` ID.
+    #[serde(default)]
+    pub search: Timing,
+    /// Time to fetch the asset list and download one image, per asset type.
+    #[serde(default)]
+    pub assets: HashMap<AssetType, Timing>,
+}
+
+impl RunHistory {
+    fn path() -> Result<PathBuf> {
+        Ok(config::app_data_dir()?.join("history.json"))
+    }
+
+    /// Load the history, or an empty one if none exists or it can't be read.
+    pub fn load() -> Self {
+        Self::path()
+            .ok()
+            .and_then(|p| std::fs::read_to_string(p).ok())
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default()
+    }
+
+    /// Persist the history.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).wrap_err("Failed to create data directory")?;
+        }
+        let content = serde_json::to_string_pretty(self).wrap_err("Failed to serialize history")?;
+        std::fs::write(&path, content)
+            .wrap_err_with(|| format!("Failed to write history to {}", path.display()))
+    }
+
+    /// Merge the measurements of a finished run.
+    pub fn merge(&mut self, run: &RunTimings) {
+        for &elapsed in &run.searches {
+            self.search.add(elapsed);
+        }
+        for &(asset, elapsed) in &run.assets {
+            self.assets.entry(asset).or_default().add(elapsed);
+        }
+    }
+
+    /// Estimated seconds for `searches` game lookups plus the given asset downloads.
+    ///
+    /// Returns `None` when any involved measurement has no history yet.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn estimate_secs(&self, searches: usize, assets: impl IntoIterator<Item = AssetType>) -> Option<f64> {
+        let mut total = if searches > 0 {
+            self.search.mean()? * searches as f64
+        } else {
+            0.0
+        };
+        for asset in assets {
+            total += self.assets.get(&asset).copied().unwrap_or_default().mean()?;
+        }
+        Some(total)
+    }
+
+    /// Pre-run estimate for processing `games`: one search each plus every asset that
+    /// isn't already on disk (or every asset, with `force`).
+    pub fn estimate_run(
+        &self,
+        launcher: &dyn LauncherBackend,
+        games: &[Game],
+        assets: &HashSet<AssetType>,
+        force: bool,
+    ) -> Option<f64> {
+        let pending = games.iter().flat_map(|g| {
+            assets
+                .iter()
+                .copied()
+                .filter(move |&a| force || !download::asset_exists(launcher, a, &g.slug))
+        });
+        self.estimate_secs(games.len(), pending)
+    }
+}
+
+/// Measurements collected during one run.
+#[derive(Debug, Default)]
+pub struct RunTimings {
+    pub searches: Vec<Duration>,
+    pub assets: Vec<(AssetType, Duration)>,
+}
+
+/// Format a duration in seconds as a short human-readable string, e.g. `"4m 05s"`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub fn format_eta(secs: f64) -> String {
+    let secs = secs.max(0.0).round() as u64;
+    match secs {
+        0..60 => format!("{secs}s"),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
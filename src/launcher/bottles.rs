@@ -0,0 +1,172 @@
+/// Bottles: the programs added to each bottle, with art saved in Lutris's layout (or
+/// `output_dir`'s).
+///
+/// Every bottle is a directory with a `bottle.yml`, whose `External_Programs` lists the
+/// programs added to it by name and executable. A bottle whose `bottle.yml` can't be
+/// parsed is skipped with a warning.
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use color_eyre::eyre::{Context, Result};
+use serde::Deserialize;
+
+use super::LauncherBackend;
+use super::lutris::Lutris;
+use crate::api::models::AssetType;
+use crate::config::Config;
+use crate::db::Game;
+use crate::heuristics;
+
+/// Where Bottles keeps its bottles: `bottles_dir` from the config, or the first of the
+/// native and Flatpak locations that exists.
+pub fn bottles_dir(configured: Option<&Path>) -> Option<PathBuf> {
+    if let Some(dir) = configured {
+        return Some(dir.to_owned());
+    }
+    let home = dirs::home_dir()?;
+    [
+        dirs::data_dir().map(|d| d.join("bottles/bottles")),
+        Some(home.join(".var/app/com.usebottles.bottles/data/bottles/bottles")),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|dir| dir.is_dir())
+}
+
+/// The parts of a `bottle.yml` that list games.
+#[derive(Deserialize)]
+struct BottleConfig {
+    #[serde(rename = "External_Programs", default)]
+    programs: BTreeMap<String, Program>,
+}
+
+/// A program added to a bottle.
+#[derive(Deserialize)]
+struct Program {
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    executable: String,
+}
+
+/// The Bottles backend, reading the bottles in a directory.
+pub struct Bottles {
+    dir: PathBuf,
+    /// The Lutris database that wasn't found, when Bottles is read in its place.
+    instead_of: Option<PathBuf>,
+    /// Bottles skipped by the last read, for [`LauncherBackend::take_warnings`].
+    warnings: Mutex<Vec<String>>,
+}
+
+impl Bottles {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, instead_of: None, warnings: Mutex::new(Vec::new()) }
+    }
+
+    /// Read Bottles because there's no Lutris database at `db_path`.
+    pub fn standing_in_for(self, db_path: PathBuf) -> Self {
+        Self { instead_of: Some(db_path), ..self }
+    }
+
+    fn warn(&self, warning: String) {
+        if let Ok(mut warnings) = self.warnings.lock() {
+            warnings.push(warning);
+        }
+    }
+}
+
+impl LauncherBackend for Bottles {
+    fn name(&self) -> &'static str {
+        "Bottles"
+    }
+
+    fn shown_in(&self) -> Option<&'static str> {
+        Some("Lutris")
+    }
+
+    /// Programs are always installed; a program in several bottles is listed once.
+    fn games(&self, _config: &Config, _include_uninstalled: bool) -> Result<Vec<Game>> {
+        let bottles = std::fs::read_dir(&self.dir)
+            .wrap_err_with(|| format!("Failed to read {}", self.dir.display()))?;
+        let mut games = Vec::new();
+        let mut slugs = HashSet::new();
+        for bottle in bottles.filter_map(std::result::Result::ok) {
+            let path = bottle.path().join("bottle.yml");
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let config: BottleConfig = match serde_yaml_ng::from_str(&content) {
+                Ok(config) => config,
+                Err(e) => {
+                    self.warn(format!("Skipping bottle {}: {e}", path.display()));
+                    continue;
+                }
+            };
+            for program in config.programs.into_values() {
+                let name = if program.name.is_empty() {
+                    let stem = Path::new(&program.executable).file_stem().and_then(|s| s.to_str());
+                    heuristics::name_from_folder(stem.unwrap_or_default())
+                } else {
+                    program.name
+                };
+                let slug = heuristics::slugify(&name);
+                if !slug.is_empty() && slugs.insert(slug.clone()) {
+                    games.push(Game { runner: Some("bottles".to_owned()), ..Game::named(slug, name) });
+                }
+            }
+        }
+        games.sort_by_cached_key(|g| g.name.to_lowercase());
+        Ok(games)
+    }
+
+    fn describe(&self, _config: &Config) -> Option<String> {
+        let db_path = self.instead_of.as_ref()?;
+        Some(format!("No Lutris database at {} — reading games from Bottles in {}", db_path.display(), self.dir.display()))
+    }
+
+    fn take_warnings(&self) -> Vec<String> {
+        self.warnings.lock().map(|mut w| std::mem::take(&mut *w)).unwrap_or_default()
+    }
+
+    fn art_path(&self, asset: AssetType, slug: &str, ext: &str) -> Result<PathBuf> {
+        Lutris.art_path(asset, slug, ext)
+    }
+
+    fn art_dirs(&self) -> Vec<PathBuf> {
+        Lutris.art_dirs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fixture;
+
+    #[test]
+    fn programs_are_read_from_every_bottle() {
+        let dir = fixture::sandbox().join("bottles");
+        for (bottle, yml) in [
+            (
+                "Gaming",
+                "Name: Gaming\nExternal_Programs:\n  a1:\n    name: Hollow Knight\n    executable: hollow_knight.exe\n  b2:\n    executable: /home/me/Games/Celeste/Celeste.exe\n",
+            ),
+            ("Other", "External_Programs:\n  c3:\n    name: Hollow Knight\n    executable: hk.exe\n"),
+            ("Broken", "External_Programs: [\n"),
+        ] {
+            std::fs::create_dir_all(dir.join(bottle)).unwrap();
+            std::fs::write(dir.join(bottle).join("bottle.yml"), yml).unwrap();
+        }
+        std::fs::create_dir_all(dir.join("Empty")).unwrap();
+
+        let bottles = Bottles::new(dir);
+        let games = bottles.games(&Config::default(), false).unwrap();
+        let names: Vec<_> = games.iter().map(|g| (g.name.as_str(), g.slug.as_str())).collect();
+        assert_eq!(names, [("Celeste", "celeste"), ("Hollow Knight", "hollow-knight")]);
+        assert!(games.iter().all(|g| g.runner.as_deref() == Some("bottles")));
+        // The broken bottle is skipped, and said so once
+        let warnings = bottles.take_warnings();
+        assert!(warnings.len() == 1 && warnings[0].contains("Broken"), "{warnings:?}");
+        assert!(bottles.take_warnings().is_empty());
+    }
+}
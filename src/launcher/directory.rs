@@ -0,0 +1,105 @@
+/// A plain directory of game folders, one per game, with art saved in Lutris's layout
+/// (or `output_dir`'s). Names are guessed from the folder names (see
+/// [`heuristics::name_from_folder`]).
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use color_eyre::eyre::{Context, Result};
+
+use super::LauncherBackend;
+use super::lutris::Lutris;
+use crate::api::models::AssetType;
+use crate::config::Config;
+use crate::db::Game;
+use crate::heuristics;
+
+/// The directory backend, reading the folders in `games_dir`.
+pub struct Directory {
+    dir: PathBuf,
+    /// The Lutris database that wasn't found, when the directory is read in its place.
+    instead_of: Option<PathBuf>,
+}
+
+impl Directory {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir, instead_of: None }
+    }
+
+    /// Read the directory because there's no Lutris database at `db_path`.
+    pub fn standing_in_for(self, db_path: PathBuf) -> Self {
+        Self { instead_of: Some(db_path), ..self }
+    }
+}
+
+impl LauncherBackend for Directory {
+    fn name(&self) -> &'static str {
+        "games directory"
+    }
+
+    fn shown_in(&self) -> Option<&'static str> {
+        Some("Lutris")
+    }
+
+    /// Every folder is an installed game, except hidden ones.
+    fn games(&self, _config: &Config, _include_uninstalled: bool) -> Result<Vec<Game>> {
+        let folders = std::fs::read_dir(&self.dir)
+            .wrap_err_with(|| format!("Failed to read {}", self.dir.display()))?;
+        let mut games: Vec<Game> = folders
+            .filter_map(std::result::Result::ok)
+            .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+            .filter_map(|entry| {
+                let folder = entry.file_name().into_string().ok()?;
+                if folder.starts_with('.') {
+                    return None;
+                }
+                let name = heuristics::name_from_folder(&folder);
+                let slug = heuristics::slugify(&name);
+                (!slug.is_empty()).then(|| Game::named(slug, name))
+            })
+            .collect();
+        games.sort_by_cached_key(|g| g.name.to_lowercase());
+        let mut slugs = HashSet::new();
+        games.retain(|g| slugs.insert(g.slug.clone()));
+        Ok(games)
+    }
+
+    fn describe(&self, _config: &Config) -> Option<String> {
+        let db_path = self.instead_of.as_ref()?;
+        Some(format!("No Lutris database at {} — reading games from {}", db_path.display(), self.dir.display()))
+    }
+
+    fn art_path(&self, asset: AssetType, slug: &str, ext: &str) -> Result<PathBuf> {
+        Lutris.art_path(asset, slug, ext)
+    }
+
+    fn art_dirs(&self) -> Vec<PathBuf> {
+        Lutris.art_dirs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fixture;
+
+    #[test]
+    fn game_folders_become_games() {
+        let dir = fixture::sandbox().join("games-dir");
+        for folder in ["Hollow_Knight_v1.5.78 [GOG]", "Hollow Knight", "SteamWorld Dig", ".cache"] {
+            std::fs::create_dir_all(dir.join(folder)).unwrap();
+        }
+        std::fs::write(dir.join("notes.txt"), "not a game").unwrap();
+
+        let games = Directory::new(dir).games(&Config::default(), false).unwrap();
+        let names: Vec<_> = games.iter().map(|g| (g.name.as_str(), g.slug.as_str())).collect();
+        assert_eq!(names, [("Hollow Knight", "hollow-knight"), ("SteamWorld Dig", "steamworld-dig")]);
+    }
+
+    #[test]
+    fn standing_in_for_lutris_is_described() {
+        let directory = Directory::new("/games".into());
+        assert_eq!(directory.describe(&Config::default()), None);
+        let line = directory.standing_in_for("/nowhere/pga.db".into()).describe(&Config::default()).unwrap();
+        assert!(line.contains("/nowhere/pga.db") && line.contains("/games"), "{line}");
+    }
+}
@@ -0,0 +1,246 @@
+/// Heroic Games Launcher: games from its store libraries and sideloaded apps, art in its
+/// image cache.
+///
+/// Heroic keeps each store's library in `store_cache/<store>_library.json` (`legendary`
+/// for Epic, `gog`, and `nile` for Amazon) and sideloaded apps in
+/// `sideload_apps/library.json`. Entries link their art by URL — `art_square` (portrait
+/// cover), `art_cover` (wide cover), `art_background`, and `art_logo` — and Heroic shows
+/// the file in `images-cache/` named after the SHA-256 of that URL, only downloading the
+/// URL when there's none. Saving art under those names replaces the store's.
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use color_eyre::eyre::{Result, eyre};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use super::LauncherBackend;
+use crate::api::models::AssetType;
+use crate::config::Config;
+use crate::db::Game;
+use crate::heuristics;
+
+/// Library files, with the runner Heroic uses for them and the Lutris service name of
+/// their store.
+const LIBRARIES: [(&str, &str, Option<&str>); 4] = [
+    ("store_cache/legendary_library.json", "legendary", Some("egs")),
+    ("store_cache/gog_library.json", "gog", Some("gog")),
+    ("store_cache/nile_library.json", "nile", Some("amazon")),
+    ("sideload_apps/library.json", "sideload", None),
+];
+
+/// Where Heroic keeps its data: `heroic_dir` from the config, or the first of the native
+/// and Flatpak locations that has a library.
+pub fn heroic_dir(configured: Option<&Path>) -> Option<PathBuf> {
+    if let Some(dir) = configured {
+        return Some(dir.to_owned());
+    }
+    let home = dirs::home_dir()?;
+    [
+        dirs::config_dir().map(|d| d.join("heroic")),
+        Some(home.join(".var/app/com.heroicgameslauncher.hgl/config/heroic")),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|dir| LIBRARIES.iter().any(|(file, ..)| dir.join(file).is_file()))
+}
+
+/// A library file.
+#[derive(Deserialize)]
+struct Library {
+    /// Store caches call the list `library`, except GOG's and the sideload list.
+    #[serde(alias = "games", default)]
+    library: Vec<Entry>,
+}
+
+/// A game in a library file.
+#[derive(Deserialize)]
+struct Entry {
+    app_name: String,
+    #[serde(default)]
+    title: String,
+    #[serde(default)]
+    is_installed: bool,
+    art_square: Option<String>,
+    art_cover: Option<String>,
+    art_background: Option<String>,
+    art_logo: Option<String>,
+}
+
+impl Entry {
+    /// The art URLs Heroic caches, by asset type.
+    fn art(&self) -> HashMap<AssetType, String> {
+        [
+            (AssetType::Grid, &self.art_square),
+            (AssetType::Banner, &self.art_cover),
+            (AssetType::Hero, &self.art_background),
+            (AssetType::Logo, &self.art_logo),
+        ]
+        .into_iter()
+        // Local files are shown as they are, without going through the cache
+        .filter_map(|(asset, url)| Some((asset, url.clone().filter(|u| u.starts_with("http"))?)))
+        .collect()
+    }
+}
+
+/// The Heroic backend, with its libraries read.
+pub struct Heroic {
+    dir: PathBuf,
+    games: Vec<Game>,
+    /// Art URLs by game slug (Heroic's app name) and asset type.
+    art: HashMap<String, HashMap<AssetType, String>>,
+    /// Libraries skipped when read, for [`LauncherBackend::take_warnings`].
+    warnings: Mutex<Vec<String>>,
+}
+
+impl Heroic {
+    /// Read the libraries in Heroic's data directory `dir`. Libraries that can't be
+    /// parsed — one store's cache mid-rewrite, say — are skipped with a warning.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no library that can be read.
+    pub fn load(dir: PathBuf) -> Result<Self> {
+        let mut games = Vec::new();
+        let mut art = HashMap::new();
+        let mut warnings = Vec::new();
+        let mut found = false;
+        for (file, runner, service) in LIBRARIES {
+            let path = dir.join(file);
+            let Ok(content) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let library: Library = match serde_json::from_str(&content) {
+                Ok(library) => library,
+                Err(e) => {
+                    warnings.push(format!("Skipped {}: {e}", path.display()));
+                    continue;
+                }
+            };
+            found = true;
+            for entry in library.library {
+                // Slugs end up in file names
+                if entry.app_name.is_empty() || entry.app_name.contains('/') || entry.app_name.starts_with('.') {
+                    continue;
+                }
+                art.insert(entry.app_name.clone(), entry.art());
+                games.push(game(entry, runner, service));
+            }
+        }
+        if !found {
+            return match warnings.first() {
+                Some(warning) => Err(eyre!("No Heroic library could be read: {warning}")),
+                None => Err(eyre!("No Heroic library found in {}", dir.display())),
+            };
+        }
+        games.sort_by_cached_key(|g| g.name.to_lowercase());
+        Ok(Self { dir, games, art, warnings: Mutex::new(warnings) })
+    }
+}
+
+/// A library entry as a game, keyed by its app name but searched for by its title.
+fn game(entry: Entry, runner: &str, service: Option<&str>) -> Game {
+    let name = if entry.title.is_empty() { entry.app_name.clone() } else { entry.title };
+    Game {
+        runner: Some(runner.to_owned()),
+        service: service.map(str::to_owned),
+        service_id: service.map(|_| entry.app_name.clone()),
+        installed: entry.is_installed,
+        config_slug: Some(heuristics::slugify(&name)),
+        ..Game::named(entry.app_name, name)
+    }
+}
+
+impl LauncherBackend for Heroic {
+    fn name(&self) -> &'static str {
+        "Heroic"
+    }
+
+    fn games(&self, _config: &Config, include_uninstalled: bool) -> Result<Vec<Game>> {
+        Ok(self.games.iter().filter(|g| g.installed || include_uninstalled).cloned().collect())
+    }
+
+    fn take_warnings(&self) -> Vec<String> {
+        self.warnings.lock().map(|mut w| std::mem::take(&mut *w)).unwrap_or_default()
+    }
+
+    /// The cache file for the URL of the game's art, whatever the extension.
+    fn art_path(&self, asset: AssetType, slug: &str, _ext: &str) -> Result<PathBuf> {
+        let url = self
+            .art
+            .get(slug)
+            .and_then(|art| art.get(&asset))
+            .ok_or_else(|| eyre!("Heroic shows no {} for {slug}", asset.display_name().to_lowercase()))?;
+        Ok(self.dir.join("images-cache").join(format!("{:x}", Sha256::digest(url.as_bytes()))))
+    }
+
+    fn art_dirs(&self) -> Vec<PathBuf> {
+        vec![self.dir.join("images-cache")]
+    }
+
+    /// Heroic shows no icons of ours.
+    fn icon_theme_dir(&self) -> Option<PathBuf> {
+        None
+    }
+
+    fn shows(&self, game: &Game, asset: AssetType) -> bool {
+        self.art.get(&game.slug).is_some_and(|art| art.contains_key(&asset))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn libraries_are_read_and_art_goes_to_the_image_cache() {
+        let dir = std::env::temp_dir().join(format!("lutrisartfetcher-heroic-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("store_cache")).unwrap();
+        std::fs::write(
+            dir.join("store_cache/gog_library.json"),
+            r#"{"games": [
+                {"app_name": "1207658924", "title": "Celeste", "is_installed": false,
+                 "art_square": "https://images.gog.com/celeste.jpg", "art_cover": "/home/me/celeste.png"},
+                {"app_name": "../evil", "title": "Evil"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let heroic = Heroic::load(dir.clone()).unwrap();
+        let config = Config::default();
+        assert!(heroic.games(&config, false).unwrap().is_empty());
+        let games = heroic.games(&config, true).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].service.as_deref(), Some("gog"));
+        assert_eq!(games[0].service_id.as_deref(), Some("1207658924"));
+        assert_eq!((games[0].slug.as_str(), games[0].search_slug()), ("1207658924", "celeste"));
+
+        assert!(heroic.shows(&games[0], AssetType::Grid));
+        assert!(!heroic.shows(&games[0], AssetType::Banner));
+        let path = heroic.art_path(AssetType::Grid, "1207658924", "png").unwrap();
+        let digest = format!("{:x}", Sha256::digest(b"https://images.gog.com/celeste.jpg"));
+        assert_eq!(path, dir.join("images-cache").join(digest));
+        let _ = std::fs::remove_dir_all(dir);
+    }
+
+    #[test]
+    fn unparsable_libraries_are_skipped_with_a_warning() {
+        let dir = std::env::temp_dir().join(format!("lutrisartfetcher-heroic-broken-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("store_cache")).unwrap();
+        std::fs::write(dir.join("store_cache/legendary_library.json"), "{\"library\": [").unwrap();
+        assert!(Heroic::load(dir.clone()).is_err());
+
+        std::fs::write(
+            dir.join("store_cache/gog_library.json"),
+            r#"{"games": [{"app_name": "1207658924", "title": "Celeste", "is_installed": true}]}"#,
+        )
+        .unwrap();
+        let heroic = Heroic::load(dir.clone()).unwrap();
+        assert_eq!(heroic.games(&Config::default(), false).unwrap().len(), 1);
+        let warnings = heroic.take_warnings();
+        assert!(warnings.len() == 1 && warnings[0].contains("legendary_library.json"), "{warnings:?}");
+        assert!(heroic.take_warnings().is_empty());
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,122 @@
+/// Lutris: games from its `pga.db`, art in its data directory (or `output_dir`) and
+/// icon directory.
+///
+/// Each game's YAML config (named by the `configpath` column) can add the title and
+/// slug the game was installed as, which are often cleaner than the database's and are
+/// used to search for art.
+use std::path::PathBuf;
+
+use color_eyre::eyre::Result;
+use serde::Deserialize;
+
+use super::LauncherBackend;
+use crate::api::models::AssetType;
+use crate::config::{self, Config};
+use crate::db::{self, Game};
+
+/// The Lutris backend.
+pub struct Lutris;
+
+impl LauncherBackend for Lutris {
+    fn name(&self) -> &'static str {
+        "Lutris"
+    }
+
+    fn games(&self, config: &Config, include_uninstalled: bool) -> Result<Vec<Game>> {
+        let path = config.db_path()?;
+        db::validate_db(&path)?;
+        let mut games = db::get_games(&path, include_uninstalled, &config.exclusions())?;
+        read_game_configs(config, &mut games);
+        Ok(games)
+    }
+
+    /// `<subdir>/<slug>.<ext>` under [`config::art_dir`], or for icons,
+    /// `lutris_<slug>.<ext>` in the icon directory.
+    fn art_path(&self, asset: AssetType, slug: &str, ext: &str) -> Result<PathBuf> {
+        if asset == AssetType::Icon {
+            let dir = config::lutris_icon_dir()?;
+            Ok(dir.join(format!("lutris_{slug}.{ext}")))
+        } else {
+            let dir = config::lutris_asset_dir(asset.lutris_subdir())?;
+            Ok(dir.join(format!("{slug}.{ext}")))
+        }
+    }
+
+    fn describe(&self, config: &Config) -> Option<String> {
+        db::read_schema(&config.db_path().ok()?).ok().map(|schema| schema.describe())
+    }
+
+    fn reads_lutris_db(&self) -> bool {
+        true
+    }
+
+    fn library_file(&self, config: &Config) -> Option<PathBuf> {
+        config.db_path().ok()
+    }
+
+    fn art_dirs(&self) -> Vec<PathBuf> {
+        [AssetType::Grid, AssetType::Banner, AssetType::Hero, AssetType::Logo]
+            .iter()
+            .filter_map(|asset| config::lutris_asset_dir(asset.lutris_subdir()).ok())
+            .chain(config::lutris_icon_dir().ok())
+            .collect()
+    }
+}
+
+/// The parts of a game's YAML config that name it. Configs written by an installer
+/// carry the title and slug it installed the game as.
+#[derive(Deserialize)]
+struct GameConfig {
+    name: Option<String>,
+    game_slug: Option<String>,
+}
+
+/// Fill in the titles and slugs from the games' YAML configs. Missing configs, and ones
+/// that can't be parsed, are skipped.
+fn read_game_configs(config: &Config, games: &mut [Game]) {
+    let Ok(dirs) = config.lutris_game_config_dirs() else {
+        return;
+    };
+    for game in games {
+        let Some(configpath) = game.configpath.as_deref().filter(|p| !p.contains('/')) else {
+            continue;
+        };
+        let file = format!("{configpath}.yml");
+        let Some(content) = dirs.iter().find_map(|dir| std::fs::read_to_string(dir.join(&file)).ok()) else {
+            continue;
+        };
+        let Ok(parsed) = serde_yaml_ng::from_str::<GameConfig>(&content) else {
+            continue;
+        };
+        game.config_name = parsed.name.filter(|n| !n.trim().is_empty() && *n != game.name);
+        game.config_slug = parsed.game_slug.filter(|s| !s.is_empty() && *s != game.slug);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::LutrisInstall;
+    use crate::provider::fixture;
+
+    #[test]
+    fn game_configs_name_games_for_search() {
+        let dir = fixture::sandbox().join("data/lutris/games");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("celeste-gog-1612345678.yml"),
+            "game:\n  exe: /games/celeste/Celeste\nname: Celeste\ngame_slug: celeste\nslug: celeste-gog\n",
+        )
+        .unwrap();
+        let mut games = [
+            Game { configpath: Some("celeste-gog-1612345678".into()), ..fixture::game("celeste-gog", "Celeste (GOG)") },
+            Game { configpath: Some("missing-1".into()), ..fixture::game("missing", "Missing") },
+        ];
+
+        let config = Config { lutris_install: LutrisInstall::Native, ..Config::default() };
+        read_game_configs(&config, &mut games);
+        assert_eq!((games[0].search_name(), games[0].search_slug()), ("Celeste", "celeste"));
+        assert_eq!(games[0].slug, "celeste-gog");
+        assert_eq!((games[1].search_name(), games[1].search_slug()), ("Missing", "missing"));
+    }
+}
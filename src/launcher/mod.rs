@@ -0,0 +1,150 @@
+/// Launchers whose libraries art can be fetched for.
+///
+/// The rest of the app reads games and places art through [`LauncherBackend`], so
+/// another launcher can stand in for Lutris without touching `download.rs`. The backend
+/// is picked once at startup (`launcher` in the config, or `--launcher`) and handed to
+/// the app and the pipeline (see `DownloadOpts::launcher`).
+pub mod bottles;
+pub mod directory;
+pub mod heroic;
+pub mod lutris;
+pub mod steam;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use color_eyre::eyre::{Result, eyre};
+use serde::{Deserialize, Serialize};
+
+use crate::api::models::AssetType;
+use crate::config::{self, Config};
+use crate::db::Game;
+use crate::steam_grid;
+
+/// A launcher, for `--launcher`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Launcher {
+    #[default]
+    Lutris,
+    /// Heroic Games Launcher (Epic, GOG, and Amazon games, and sideloaded apps).
+    Heroic,
+    /// Programs added to Bottles, with art in Lutris's layout.
+    Bottles,
+    /// The folders in `games_dir`, with art in Lutris's layout.
+    Directory,
+    /// Games installed in Steam's libraries, with art in Lutris's layout under
+    /// `output_dir`.
+    Steam,
+}
+
+/// A launcher's game library and art layout.
+pub trait LauncherBackend: Send + Sync {
+    /// Name shown in messages, e.g. `"Lutris"`.
+    fn name(&self) -> &'static str;
+
+    /// The app that shows the saved art, for "restart it" hints; `None` when that's up
+    /// to the user.
+    fn shown_in(&self) -> Option<&'static str> {
+        Some(self.name())
+    }
+
+    /// Read the launcher's games, sorted by name: only the installed ones unless
+    /// `include_uninstalled`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the library can't be read.
+    fn games(&self, config: &Config, include_uninstalled: bool) -> Result<Vec<Game>>;
+
+    /// Where the launcher looks for an asset of a game, saved with extension `ext`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the location can't be determined, or the launcher doesn't
+    /// show this asset for the game.
+    fn art_path(&self, asset: AssetType, slug: &str, ext: &str) -> Result<PathBuf>;
+
+    /// Directories art is saved in.
+    fn art_dirs(&self) -> Vec<PathBuf>;
+
+    /// One line about the library for logs, e.g. the Lutris version that wrote it.
+    fn describe(&self, _config: &Config) -> Option<String> {
+        None
+    }
+
+    /// Problems met reading the library that didn't stop it being read, e.g. a config
+    /// file that was skipped. Each is returned once.
+    fn take_warnings(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Whether the library is the Lutris database.
+    fn reads_lutris_db(&self) -> bool {
+        false
+    }
+
+    /// The file the library is read from, watched while the TUI is open so the game
+    /// list follows games being installed.
+    fn library_file(&self, _config: &Config) -> Option<PathBuf> {
+        None
+    }
+
+    /// The hicolor icon theme saved icons go into, whose other icon sizes and icon cache
+    /// are then kept up to date; `None` when icons go elsewhere.
+    fn icon_theme_dir(&self) -> Option<PathBuf> {
+        config::icon_theme_dir()
+    }
+
+    /// Whether the launcher shows this asset for `game` at all. Assets it doesn't are
+    /// skipped without a lookup.
+    fn shows(&self, _game: &Game, _asset: AssetType) -> bool {
+        true
+    }
+}
+
+/// The backend for `config.launcher`. Without a Lutris database where Lutris keeps it,
+/// Lutris falls back to `games_dir` if set, or else Bottles if it's installed; one set
+/// with `lutris_db_path` (or `--db-path`) is always read.
+///
+/// # Errors
+///
+/// Returns an error if the launcher's data can't be found or read.
+pub fn from_config(config: &Config) -> Result<Arc<dyn LauncherBackend>> {
+    Ok(match config.launcher {
+        Launcher::Lutris if config.lutris_db_path.is_none() && !config.db_path()?.exists() => {
+            let db_path = config.db_path()?;
+            if let Some(dir) = &config.games_dir {
+                Arc::new(directory::Directory::new(dir.clone()).standing_in_for(db_path))
+            } else if let Some(dir) = bottles::bottles_dir(config.bottles_dir.as_deref()) {
+                Arc::new(bottles::Bottles::new(dir).standing_in_for(db_path))
+            } else {
+                Arc::new(lutris::Lutris)
+            }
+        }
+        Launcher::Lutris => Arc::new(lutris::Lutris),
+        Launcher::Heroic => {
+            let dir = heroic::heroic_dir(config.heroic_dir.as_deref())
+                .ok_or_else(|| eyre!("Heroic Games Launcher data not found\nSet heroic_dir in the config if it's somewhere unusual."))?;
+            Arc::new(heroic::Heroic::load(dir)?)
+        }
+        Launcher::Bottles => {
+            let dir = bottles::bottles_dir(config.bottles_dir.as_deref())
+                .ok_or_else(|| eyre!("Bottles not found\nSet bottles_dir in the config if it's somewhere unusual."))?;
+            Arc::new(bottles::Bottles::new(dir))
+        }
+        Launcher::Steam => {
+            if config.output_dir.is_none() {
+                return Err(eyre!("Set output_dir (or --output-dir) to choose where art for Steam games goes"));
+            }
+            let dir = steam_grid::steam_dir(config.steam_dir.as_deref())
+                .ok_or_else(|| eyre!("Steam installation not found\nSet steam_dir in the config if it's somewhere unusual."))?;
+            Arc::new(steam::Steam::new(dir))
+        }
+        Launcher::Directory => {
+            let dir = config.games_dir.clone().ok_or_else(|| eyre!("Set games_dir (or --games-dir) to read games from a directory"))?;
+            Arc::new(directory::Directory::new(dir))
+        }
+    })
+}
+
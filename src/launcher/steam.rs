@@ -0,0 +1,306 @@
+/// Steam: the games installed in Steam's libraries, with art saved in Lutris's layout
+/// under `output_dir`.
+///
+/// Steam lists its library folders in `steamapps/libraryfolders.vdf`, and each library
+/// has an `appmanifest_<appid>.acf` per installed app in its own `steamapps/`. Both are
+/// text `KeyValues` files. Games keep their app ID as a Steam store ID, so they're looked
+/// up by it rather than by name.
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Result, eyre};
+
+use super::LauncherBackend;
+use super::lutris::Lutris;
+use crate::api::models::AssetType;
+use crate::config::{self, Config};
+use crate::db::Game;
+use crate::heuristics;
+
+/// The Steam backend, reading the libraries of a Steam installation.
+pub struct Steam {
+    dir: PathBuf,
+}
+
+impl Steam {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// The library list.
+    fn library_folders_file(&self) -> PathBuf {
+        self.dir.join("steamapps/libraryfolders.vdf")
+    }
+
+    /// Steam's library folders: its own directory, plus the ones in the library list.
+    fn libraries(&self) -> Result<Vec<PathBuf>> {
+        let mut libraries = vec![self.dir.clone()];
+        let path = self.library_folders_file();
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            return Ok(libraries);
+        };
+        let root = parse(&content).ok_or_else(|| eyre!("Failed to parse {}", path.display()))?;
+        for (key, folder) in root.into_iter().flat_map(|(_, value)| value.into_map()) {
+            let folder = match folder {
+                Value::Map(fields) => fields
+                    .into_iter()
+                    .find(|(k, _)| k.eq_ignore_ascii_case("path"))
+                    .and_then(|(_, v)| v.into_str()),
+                // Before 2021, folders were listed by number, as plain paths
+                Value::Str(path) if key.parse::<u32>().is_ok() => Some(path),
+                Value::Str(_) => None,
+            };
+            if let Some(folder) = folder.map(PathBuf::from) {
+                if !libraries.contains(&folder) {
+                    libraries.push(folder);
+                }
+            }
+        }
+        Ok(libraries)
+    }
+}
+
+impl LauncherBackend for Steam {
+    fn name(&self) -> &'static str {
+        "Steam"
+    }
+
+    fn shown_in(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Apps Steam is still downloading count as not installed. Steam's runtimes and
+    /// Proton versions are left out.
+    fn games(&self, _config: &Config, include_uninstalled: bool) -> Result<Vec<Game>> {
+        let mut games = Vec::new();
+        let mut slugs = HashSet::new();
+        for library in self.libraries()? {
+            let Ok(entries) = std::fs::read_dir(library.join("steamapps")) else {
+                continue;
+            };
+            for entry in entries.filter_map(std::result::Result::ok) {
+                let path = entry.path();
+                let is_manifest = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("acf"))
+                    && entry.file_name().to_string_lossy().starts_with("appmanifest_");
+                if !is_manifest {
+                    continue;
+                }
+                let Some(game) = read_manifest(&path) else {
+                    continue;
+                };
+                if (game.installed || include_uninstalled) && slugs.insert(game.slug.clone()) {
+                    games.push(game);
+                }
+            }
+        }
+        games.sort_by_cached_key(|g| g.name.to_lowercase());
+        Ok(games)
+    }
+
+    /// Lutris's layout under [`config::art_dir`], with icons in its `icons/`.
+    fn art_path(&self, asset: AssetType, slug: &str, ext: &str) -> Result<PathBuf> {
+        if asset == AssetType::Icon {
+            Ok(config::art_dir()?.join("icons").join(format!("{slug}.{ext}")))
+        } else {
+            Lutris.art_path(asset, slug, ext)
+        }
+    }
+
+    fn art_dirs(&self) -> Vec<PathBuf> {
+        [AssetType::Grid, AssetType::Banner, AssetType::Hero, AssetType::Logo]
+            .iter()
+            .filter_map(|asset| config::lutris_asset_dir(asset.lutris_subdir()).ok())
+            .chain(config::art_dir().ok().map(|dir| dir.join("icons")))
+            .collect()
+    }
+
+    /// Icons stay in `output_dir`, out of the user's icon theme.
+    fn icon_theme_dir(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// Steam updates the library list as games are installed.
+    fn library_file(&self, _config: &Config) -> Option<PathBuf> {
+        Some(self.library_folders_file())
+    }
+}
+
+/// The game an app manifest describes, unless it's one of Steam's tools.
+fn read_manifest(path: &Path) -> Option<Game> {
+    let content = std::fs::read_to_string(path).ok()?;
+    let (_, state) = parse(&content)?.into_iter().next()?;
+    let mut appid = None;
+    let mut name = None;
+    let mut flags = 0_u32;
+    for (key, value) in state.into_map() {
+        match (key.to_ascii_lowercase().as_str(), value) {
+            ("appid", Value::Str(s)) => appid = Some(s),
+            ("name", Value::Str(s)) => name = Some(s),
+            ("stateflags", Value::Str(s)) => flags = s.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+    let (appid, name) = (appid?, name?);
+    if is_steam_tool(&name) {
+        return None;
+    }
+    let slug = heuristics::slugify(&name);
+    if slug.is_empty() {
+        return None;
+    }
+    Some(Game {
+        runner: Some("steam".to_owned()),
+        service: Some("steam".to_owned()),
+        service_id: Some(appid),
+        // StateFlags 4 is "fully installed"
+        installed: flags & 4 != 0,
+        ..Game::named(slug, name)
+    })
+}
+
+/// Whether an app is one of Steam's runtimes or compatibility tools rather than a game.
+fn is_steam_tool(name: &str) -> bool {
+    name.starts_with("Proton ")
+        || name.starts_with("Steam Linux Runtime")
+        || name == "Steamworks Common Redistributables"
+}
+
+// ---------------------------------------------------------------------------
+// KeyValues
+// ---------------------------------------------------------------------------
+
+/// A value in a text `KeyValues` file.
+enum Value {
+    Str(String),
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn into_map(self) -> Vec<(String, Value)> {
+        match self {
+            Self::Map(fields) => fields,
+            Self::Str(_) => Vec::new(),
+        }
+    }
+
+    fn into_str(self) -> Option<String> {
+        match self {
+            Self::Str(s) => Some(s),
+            Self::Map(_) => None,
+        }
+    }
+}
+
+/// A token of a text `KeyValues` file.
+#[derive(Debug, PartialEq, Eq)]
+enum Token {
+    Str(String),
+    Open,
+    Close,
+}
+
+/// Parse a text `KeyValues` file into its top-level fields; a malformed file gives
+/// `None`.
+fn parse(text: &str) -> Option<Vec<(String, Value)>> {
+    let mut tokens = tokenize(text).into_iter();
+    parse_map(&mut tokens, false)
+}
+
+/// Parse fields up to the closing brace of a map, or the end of the file at the top
+/// level.
+fn parse_map(tokens: &mut impl Iterator<Item = Token>, nested: bool) -> Option<Vec<(String, Value)>> {
+    let mut fields = Vec::new();
+    loop {
+        let key = match tokens.next() {
+            Some(Token::Str(key)) => key,
+            Some(Token::Close) if nested => return Some(fields),
+            None if !nested => return Some(fields),
+            _ => return None,
+        };
+        let value = match tokens.next()? {
+            Token::Str(value) => Value::Str(value),
+            Token::Open => Value::Map(parse_map(tokens, true)?),
+            Token::Close => return None,
+        };
+        fields.push((key, value));
+    }
+}
+
+/// Split a text `KeyValues` file into tokens, dropping `//` comments.
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let mut s = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => s.push('\n'),
+                            Some('t') => s.push('\t'),
+                            Some(other) => s.push(other),
+                            None => {}
+                        },
+                        _ => s.push(c),
+                    }
+                }
+                tokens.push(Token::Str(s));
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                chars.by_ref().find(|&c| c == '\n');
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut s = String::from(c);
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '"' | '{' | '}') {
+                        break;
+                    }
+                    s.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Str(s));
+            }
+        }
+    }
+    tokens
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn libraries_and_app_manifests_are_read() {
+        let dir = std::env::temp_dir().join(format!("lutrisartfetcher-steam-{}", std::process::id()));
+        let other = dir.join("other");
+        std::fs::create_dir_all(dir.join("steam/steamapps")).unwrap();
+        std::fs::create_dir_all(other.join("steamapps")).unwrap();
+        std::fs::write(
+            dir.join("steam/steamapps/libraryfolders.vdf"),
+            format!(
+                "\"libraryfolders\"\n{{\n\t\"0\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t\t\"apps\" {{ \"620\" \"1\" }}\n\t}}\n}}\n",
+                other.display()
+            ),
+        )
+        .unwrap();
+        let manifest = |appid: &str, name: &str, flags: u32| {
+            format!("// Written by Steam\n\"AppState\"\n{{\n\t\"appid\"\t\t\"{appid}\"\n\t\"name\"\t\t\"{name}\"\n\t\"StateFlags\"\t\t\"{flags}\"\n}}\n")
+        };
+        std::fs::write(other.join("steamapps/appmanifest_620.acf"), manifest("620", "Portal 2", 4)).unwrap();
+        std::fs::write(other.join("steamapps/appmanifest_1493710.acf"), manifest("1493710", "Proton Experimental", 4)).unwrap();
+        std::fs::write(dir.join("steam/steamapps/appmanifest_70.acf"), manifest("70", "Half-Life", 1026)).unwrap();
+
+        let steam = Steam::new(dir.join("steam"));
+        let config = Config::default();
+        let games = steam.games(&config, false).unwrap();
+        assert_eq!(games.len(), 1);
+        assert_eq!((games[0].slug.as_str(), games[0].service_id.as_deref()), ("portal-2", Some("620")));
+        assert_eq!(steam.games(&config, true).unwrap().len(), 2);
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
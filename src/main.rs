@@ -4,30 +4,49 @@
 /// Lutris `SQLite` database and downloads grids, heroes, logos, and icons.
 mod api;
 mod app;
+mod checkpoint;
 mod config;
 mod db;
 mod download;
 mod event;
+mod failures;
+mod heuristics;
+mod history;
+mod launcher;
+mod manifest;
+mod matching;
+mod pack;
+mod provider;
+mod report;
+mod steam_grid;
 mod tui;
 mod ui;
 
 use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use color_eyre::eyre::{Context, Result, eyre};
 
-use crate::api::models::AssetType;
+use crate::api::models::{AssetCounts, AssetType, PipelineEvent, RunCounts};
 use crate::api::SteamGridDbClient;
-use crate::app::App;
-use crate::config::Config;
-use crate::download::{asset_exists, asset_path};
+use crate::app::{App, LogLevel, SortOrder};
+use crate::config::{Config, IconTarget, LutrisInstall};
+use crate::download::{Coverage, art_size_on_disk, asset_exists, asset_path, format_size};
 use crate::event::{AppEvent, EventHandler};
+use crate::failures::FailedAssets;
+use crate::history::RunHistory;
+use crate::launcher::LauncherBackend;
+use crate::manifest::Manifest;
 
 // ---------------------------------------------------------------------------
 // CLI
 // ---------------------------------------------------------------------------
 
 #[derive(Parser, Debug)]
+#[allow(clippy::struct_excessive_bools)]
 #[command(
     name = "lutrisartfetcher",
     about = "Download cover art for Lutris games from SteamGridDB",
@@ -42,34 +61,225 @@ struct Cli {
     #[arg(long)]
     force: bool,
 
+    /// Replace saved art only where a higher-ranked or newer asset is available now.
+    #[arg(long, conflicts_with = "force")]
+    update: bool,
+
     /// Show what would be downloaded without actually downloading.
     #[arg(long)]
     dry_run: bool,
 
-    /// Asset types to download (comma-separated: grids,heroes,logos,icons).
+    /// Asset types to download (comma-separated: grids,banners,heroes,logos,icons).
     #[arg(long, value_delimiter = ',', default_value = "grids,heroes,logos,icons")]
     assets: Vec<String>,
 
+    /// Allow art flagged as a photosensitive-epilepsy risk (filtered out by default).
+    #[arg(long)]
+    no_epilepsy_filter: bool,
+
+    /// Replace grids and banners set in Lutris too (kept by default, even with --force).
+    #[arg(long)]
+    overwrite_custom_art: bool,
+
+    /// Clear Lutris's custom-art flags of the grids and banners replaced (backs up pga.db).
+    #[arg(long)]
+    clear_custom_art_flags: bool,
+
     /// Max parallel downloads.
     #[arg(long, default_value = "3")]
     concurrency: u8,
+
+    /// Timeout for each API request in seconds (image downloads get twice this).
+    #[arg(long, value_name = "SECS")]
+    http_timeout: Option<u64>,
+
+    /// Timeout for establishing a connection, in seconds.
+    #[arg(long, value_name = "SECS")]
+    connect_timeout: Option<u64>,
+
+    /// Game list order for the TUI and dry-run report.
+    #[arg(long, value_enum, default_value = "name")]
+    sort: SortOrder,
+
+    /// Launcher whose games to fetch art for (overrides `launcher` in the config).
+    #[arg(long, value_enum)]
+    launcher: Option<launcher::Launcher>,
+
+    /// Directory of game folders to read games from (overrides `games_dir` in the config).
+    #[arg(long, value_name = "DIR")]
+    games_dir: Option<PathBuf>,
+
+    /// Fetch from this source only, instead of `SteamGridDB` with fallbacks.
+    #[arg(long, value_enum)]
+    source: Option<provider::Source>,
+
+    /// Only process the game with this slug (repeatable).
+    #[arg(long = "game", value_name = "SLUG")]
+    games: Vec<String>,
+
+    /// Only process games in this Lutris category (repeatable).
+    #[arg(long = "category", value_name = "NAME")]
+    categories: Vec<String>,
+
+    /// Skip games in this Lutris category, e.g. `.hidden` (repeatable).
+    #[arg(long = "exclude-category", value_name = "NAME")]
+    excluded_categories: Vec<String>,
+
+    /// Also process games in the Lutris library that aren't installed.
+    #[arg(long)]
+    include_uninstalled: bool,
+
+    /// Record sanitized HTTP traffic into DIR for attaching to bug reports.
+    #[arg(long, value_name = "DIR", conflicts_with = "replay")]
+    capture: Option<PathBuf>,
+
+    /// Drive the pipeline from a capture in DIR instead of the network.
+    #[arg(long, value_name = "DIR")]
+    replay: Option<PathBuf>,
+
+    /// Skip the games and assets finished by the previous, interrupted run.
+    #[arg(long)]
+    resume: bool,
+
+    /// Only fetch the assets that failed in the last run.
+    #[arg(long, conflicts_with_all = ["resume", "dry_run"])]
+    retry_failed: bool,
+
+    /// Only process a random subset of N games — handy for trying out filter settings.
+    #[arg(long, value_name = "N")]
+    sample: Option<usize>,
+
+    /// Read games from this Lutris database instead of the default `pga.db`.
+    #[arg(long, value_name = "FILE")]
+    db_path: Option<PathBuf>,
+
+    /// Save art under DIR instead of Lutris's data directory, in the same layout.
+    #[arg(long, value_name = "DIR")]
+    output_dir: Option<PathBuf>,
+
+    /// Log every HTTP request — URL, status, size, retries — to FILE.
+    #[arg(long, value_name = "FILE")]
+    trace: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Inspect or change the saved configuration.
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Check paths and settings, and show where Lutris keeps its icons.
+    Doctor,
+    /// Write a report on the library's installed art.
+    Report {
+        /// Write a self-contained HTML gallery to FILE.
+        #[arg(long, value_name = "FILE")]
+        html: PathBuf,
+    },
+    /// List saved art that would be picked differently today, e.g. because better-rated
+    /// art has been uploaded since. Nothing is downloaded.
+    Check,
+    /// Report each game's art coverage: what's saved, and whether the missing assets are
+    /// available from any source. Nothing is downloaded.
+    Audit,
+    /// Put back the art the last run replaced — or, with --game, the art most recently
+    /// replaced for those games.
+    Restore,
+    /// Fetch the art another machine has that this one lacks, using its manifest.
+    Sync {
+        /// Manifest copied from the other machine
+        /// (`~/.local/share/lutrisartfetcher/manifest.json` there).
+        #[arg(long, value_name = "MANIFEST")]
+        from: PathBuf,
+    },
+    /// Bundle all saved art and its manifest entries into a zip, to take to another
+    /// machine.
+    ExportPack {
+        /// Zip file to write.
+        path: PathBuf,
+    },
+    /// Save the art in a pack made by export-pack for the installed games that lack it —
+    /// or, with --force, for all of them, replacing their art.
+    ImportPack {
+        /// Zip file to read.
+        path: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ConfigCommand {
+    /// Validate and save a new API key, keeping all other settings.
+    SetKey {
+        /// The new key. Read from stdin when omitted.
+        key: Option<String>,
+    },
 }
 
 // ---------------------------------------------------------------------------
 // Main
 // ---------------------------------------------------------------------------
 
+impl Cli {
+    /// Apply the options that override config settings for this run.
+    fn apply_overrides(&self, config: &mut Config) {
+        config.max_concurrent_downloads = self.concurrency;
+        config.epilepsy_filter &= !self.no_epilepsy_filter;
+        config.respect_custom_art &= !self.overwrite_custom_art;
+        config.clear_custom_art_flags |= self.clear_custom_art_flags;
+        config.include_uninstalled |= self.include_uninstalled;
+        if !self.categories.is_empty() {
+            config.categories = self.categories.iter().cloned().collect();
+        }
+        config.excluded_categories.extend(self.excluded_categories.iter().cloned());
+        if let Some(secs) = self.http_timeout {
+            config.http_timeout_secs = secs;
+        }
+        if let Some(secs) = self.connect_timeout {
+            config.connect_timeout_secs = secs;
+        }
+        if let Some(launcher) = self.launcher {
+            config.launcher = launcher;
+        }
+        if let Some(dir) = &self.games_dir {
+            config.games_dir = Some(dir.clone());
+        }
+        if let Some(path) = &self.db_path {
+            config.lutris_db_path = Some(path.clone());
+        }
+        if let Some(dir) = &self.output_dir {
+            config.output_dir = Some(dir.clone());
+        }
+        config.source = self.source;
+        config.update = self.update;
+        config.capture_dir.clone_from(&self.capture);
+        config.replay_dir.clone_from(&self.replay);
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
     color_eyre::install()?;
     let cli = Cli::parse();
+    cli.trace.as_deref().map(init_trace).transpose()?;
 
     // Load configuration
     let mut config = Config::load()?;
-    config.max_concurrent_downloads = cli.concurrency;
+    cli.apply_overrides(&mut config);
+    config.validate()?;
+    config::set_art_paths(config.art_paths()?);
+
+    match cli.command {
+        Some(Command::Config { action }) => return run_config_command(config, action).await,
+        Some(Command::Doctor) => return run_doctor(&config),
+        _ => {}
+    }
 
     // Parse asset types
-    let assets: HashSet<AssetType> = cli
+    let mut assets: HashSet<AssetType> = cli
         .assets
         .iter()
         .map(|s| s.parse::<AssetType>())
@@ -80,41 +290,265 @@ async fn main() -> Result<()> {
         return Err(eyre!("No asset types selected"));
     }
 
-    // Validate Lutris database
-    let db_path = config::lutris_db_path()?;
-    db::validate_db(&db_path)?;
-
-    // Read installed games (synchronous — must finish before async work)
-    let games = db::get_installed_games(&db_path)?;
+    // Read games (synchronous — must finish before async work)
+    let launcher = launcher::from_config(&config)?;
+    let mut games = load_games(&cli, &config, &*launcher, &mut assets)?;
     if games.is_empty() {
-        println!("No installed games found in the Lutris database.");
+        println!("No games found in the {} library.", launcher.name());
+        return Ok(());
+    }
+
+    match cli.command {
+        Some(Command::Report { html }) => {
+            cli.sort.sort(&mut games, &*launcher, |g| g);
+            report::write_html_gallery(&*launcher, &games, &assets, &config, &html)?;
+            println!("Wrote gallery of {} games to {}", games.len(), html.display());
+            return Ok(());
+        }
+        Some(Command::Sync { from }) => {
+            games.retain(|g| !config.excludes(g));
+            return run_sync(&config, &launcher, &games, &assets, &from).await;
+        }
+        Some(Command::Check) => return run_check(&config, &launcher, &games, &assets).await,
+        Some(Command::Audit) => return run_audit(&config, &launcher, &games, &assets).await,
+        Some(Command::Restore) => return run_restore(&*launcher, &games, !cli.games.is_empty()),
+        Some(Command::ExportPack { path }) => return run_export_pack(&*launcher, &games, &path),
+        Some(Command::ImportPack { path }) => {
+            return run_import_pack(&config, &launcher, &games, &path, cli.force).await;
+        }
+        _ => {}
+    }
+
+    let retry_only = cli.retry_failed.then(|| narrow_to_failures(&mut games, &mut assets));
+    if retry_only.as_ref().is_some_and(FailedAssets::is_empty) {
+        println!("No failed assets from the last run to retry.");
         return Ok(());
     }
+    // A failed replacement leaves the old file in place; retries replace it
+    let force = cli.force || cli.retry_failed;
+
+    // The TUI shows excluded games (dimmed) so they can be re-included there
+    if cli.dry_run || cli.no_tui {
+        let before = games.len();
+        games.retain(|g| !config.excludes(g));
+        if games.len() < before {
+            println!("Skipping {} excluded games", before - games.len());
+        }
+    }
+
+    if let Some(n) = cli.sample {
+        sample(&mut games, &*launcher, n);
+    }
+
+    let notices = if cli.dry_run {
+        Vec::new()
+    } else {
+        let shown: Vec<db::Game> =
+            games.iter().filter(|g| g.installed || config.include_uninstalled).cloned().collect();
+        preflight(&config, &*launcher, &shown, &mut assets, force)?
+    };
+
+    if cli.dry_run || cli.no_tui {
+        print_library_notes(&config, &*launcher);
+    }
 
     if cli.dry_run {
-        run_dry_run(&games, &assets)?;
+        run_dry_run(&*launcher, games, &assets, cli.sort)?;
     } else if cli.no_tui {
-        run_headless(config, games, assets, cli.force).await?;
+        for notice in &notices {
+            println!("Warning: {notice}");
+        }
+        run_headless(config, launcher, games, assets, force, cli.resume, retry_only).await?;
     } else {
-        run_tui(config, games, assets, cli.force).await?;
+        // A sampled or retried list stays that way when the library is reloaded
+        let game_filter = if cli.sample.is_some() || retry_only.is_some() {
+            games.iter().map(|g| g.slug.clone()).collect()
+        } else {
+            cli.games
+        };
+        let mut app = App::new(config, launcher, games, assets, force);
+        app.resume = cli.resume;
+        app.retry_only = retry_only;
+        app.game_filter = game_filter;
+        run_tui(app, cli.sort, notices).await?;
     }
 
     Ok(())
 }
 
+/// Read the launcher's games, narrowed to `--game`, and drop the asset types it shows
+/// for none of them. The TUI gets the uninstalled games either way, to show them on
+/// request.
+fn load_games(
+    cli: &Cli,
+    config: &Config,
+    launcher: &dyn LauncherBackend,
+    assets: &mut HashSet<AssetType>,
+) -> Result<Vec<db::Game>> {
+    let tui = cli.command.is_none() && !cli.dry_run && !cli.no_tui;
+    let mut games = launcher.games(config, config.include_uninstalled || tui)?;
+    if !cli.games.is_empty() {
+        games.retain(|g| cli.games.contains(&g.slug));
+    }
+    assets.retain(|&asset| games.iter().any(|g| launcher.shows(g, asset)));
+    if assets.is_empty() && !games.is_empty() {
+        return Err(eyre!("{} shows none of the selected asset types", launcher.name()));
+    }
+    Ok(games)
+}
+
+/// Print what the launcher says about its library, and any problems reading it.
+fn print_library_notes(config: &Config, launcher: &dyn LauncherBackend) {
+    if let Some(line) = launcher.describe(config) {
+        println!("{line}");
+    }
+    for warning in launcher.take_warnings() {
+        println!("Warning: {warning}");
+    }
+}
+
+/// "N installed games", plus the uninstalled ones if there are any.
+fn count_games(games: &[db::Game]) -> String {
+    let installed = games.iter().filter(|g| g.installed).count();
+    match games.len() - installed {
+        0 => format!("{installed} installed games"),
+        uninstalled => format!("{installed} installed and {uninstalled} uninstalled games"),
+    }
+}
+
+/// Check the art directories before a run: move icons to a writable directory (or skip
+/// them), clear out stale temporary files, and make sure there's room for the downloads.
+/// Returns warnings to show.
+fn preflight(
+    config: &Config,
+    launcher: &dyn LauncherBackend,
+    games: &[db::Game],
+    assets: &mut HashSet<AssetType>,
+    force: bool,
+) -> Result<Vec<String>> {
+    let mut notices = Vec::new();
+    if assets.contains(&AssetType::Icon) {
+        match download::preflight_icon_dir() {
+            Ok(preflight) => {
+                if let Some(default) = preflight.fell_back_from {
+                    notices.push(format!(
+                        "{} is not writable — saving icons to {} instead",
+                        default.display(),
+                        preflight.dir.display()
+                    ));
+                }
+            }
+            Err(e) => {
+                notices.push(format!("Skipping icons: {e}"));
+                assets.remove(&AssetType::Icon);
+            }
+        }
+    }
+    download::remove_stale_temp_files(launcher);
+    notices.extend(download::preflight_disk_space(launcher, games, assets, force, config)?);
+    Ok(notices)
+}
+
+/// Narrow the run to `n` games picked at random (`--sample`), in name order.
+fn sample(games: &mut Vec<db::Game>, launcher: &dyn LauncherBackend, n: usize) {
+    if n < games.len() {
+        println!("Sampling {n} of {} games", games.len());
+        fastrand::shuffle(games);
+        games.truncate(n);
+        SortOrder::Name.sort(games, launcher, |g| g);
+    }
+}
+
+/// Narrow the run to the games and assets that failed in the last run, returning those
+/// failures — or none, if nothing selected failed.
+fn narrow_to_failures(games: &mut Vec<db::Game>, assets: &mut HashSet<AssetType>) -> FailedAssets {
+    let failed = FailedAssets::load();
+    games.retain(|g| failed.games.contains_key(&g.slug));
+    assets.retain(|a| failed.games.values().any(|failed| failed.contains(a)));
+    if games.is_empty() || assets.is_empty() {
+        return FailedAssets::default();
+    }
+    println!("Retrying {} assets that failed in the last run", failed.len());
+    failed
+}
+
+/// Send this crate's request traces to `path`. Other crates' (reqwest, hyper) are left
+/// out — they would drown it.
+fn init_trace(path: &Path) -> Result<()> {
+    use tracing_subscriber::filter::{LevelFilter, Targets};
+    use tracing_subscriber::prelude::*;
+
+    let file = std::fs::File::create(path)
+        .wrap_err_with(|| format!("Failed to create trace file {}", path.display()))?;
+    let layer = tracing_subscriber::fmt::layer()
+        .with_writer(std::sync::Mutex::new(file))
+        .with_ansi(false);
+    let targets = Targets::new().with_target(env!("CARGO_CRATE_NAME"), LevelFilter::DEBUG);
+    tracing_subscriber::registry().with(layer.with_filter(targets)).init();
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Config subcommands
+// ---------------------------------------------------------------------------
+
+async fn run_config_command(config: Config, action: ConfigCommand) -> Result<()> {
+    match action {
+        ConfigCommand::SetKey { key } => {
+            let key = if let Some(key) = key {
+                key
+            } else {
+                print!("New SteamGridDB API key: ");
+                std::io::stdout().flush()?;
+                let mut line = String::new();
+                std::io::stdin()
+                    .read_line(&mut line)
+                    .wrap_err("Failed to read API key")?;
+                line
+            };
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(eyre!("API key cannot be empty"));
+            }
+
+            let client = SteamGridDbClient::new(key, &config)?;
+            if !client.validate_key().await? {
+                return Err(eyre!("API key rejected by SteamGridDB"));
+            }
+            // Save onto the on-disk config so command-line overrides aren't persisted
+            let mut on_disk = Config::load()?;
+            on_disk.api_key = Some(key.to_owned());
+            on_disk.save()?;
+            println!("API key validated and saved to {}", config::config_path().display());
+        }
+    }
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // TUI mode
 // ---------------------------------------------------------------------------
 
-async fn run_tui(
-    config: Config,
-    games: Vec<db::Game>,
-    assets: HashSet<AssetType>,
-    force: bool,
-) -> Result<()> {
+async fn run_tui(mut app: App, sort: SortOrder, notices: Vec<String>) -> Result<()> {
     let mut terminal = tui::init()?;
     let mut events = EventHandler::new(250);
-    let mut app = App::new(config, games, assets, force);
+    for notice in notices {
+        app.log(LogLevel::Warn, notice);
+    }
+    if sort != SortOrder::Name {
+        app.set_sort_order(sort);
+    }
+    if let Some(line) = app.launcher.describe(&app.config) {
+        app.log(LogLevel::Info, line);
+    }
+    for warning in app.launcher.take_warnings() {
+        app.log(LogLevel::Warn, warning);
+    }
+    if let Some(path) = app.launcher.library_file(&app.config) {
+        if let Err(e) = events.watch(&path) {
+            app.log(LogLevel::Warn, format!("Not watching for new games: {e}"));
+        }
+    }
 
     loop {
         terminal
@@ -128,9 +562,12 @@ async fn run_tui(
             }
             AppEvent::Tick => {
                 app.tick_count += 1;
+                app.reload_games_if_due(&events.sender());
             }
-            AppEvent::Download(ref progress) => {
-                app.handle_download_progress(progress);
+            AppEvent::GamesChanged => app.games_changed(),
+            AppEvent::GamesLoaded(games) => app.games_loaded(games),
+            AppEvent::Pipeline(ref event) => {
+                app.handle_pipeline_event(event);
             }
             AppEvent::Resize(_, _) => {
                 // ratatui handles resize automatically on next draw
@@ -142,28 +579,387 @@ async fn run_tui(
         }
     }
 
+    // Don't leave downloads writing files behind us
+    app.cancel_downloads();
     tui::restore()?;
     Ok(())
 }
 
+// ---------------------------------------------------------------------------
+// Doctor
+// ---------------------------------------------------------------------------
+
+fn run_doctor(config: &Config) -> Result<()> {
+    let config_path = config::config_path();
+    println!(
+        "Config:    {}{}",
+        config_path.display(),
+        if config_path.exists() { "" } else { " (not created yet)" }
+    );
+    println!(
+        "API key:   {}",
+        if config.api_key.is_some() { "set" } else { "not set" }
+    );
+    println!("API:       {}", config.api_base_url);
+    if let Some(ref cdn) = config.cdn_base_url {
+        println!("CDN:       {cdn}");
+    }
+    if let Some(ref proxy) = config.proxy_url {
+        println!("Proxy:     {proxy}");
+    }
+    if config.igdb_client_id.is_some() && config.igdb_client_secret.is_some() {
+        println!("IGDB:      credentials set (used as a fallback)");
+    }
+
+    let install = match config.lutris_install {
+        LutrisInstall::Auto => "auto",
+        LutrisInstall::Native => "native",
+        LutrisInstall::Flatpak => "flatpak",
+    };
+    println!("Lutris:    {} (lutris_install = {install})", config.lutris_data_dir()?.display());
+    let db_path = config.db_path()?;
+    let note = if config.lutris_db_path.is_some() { ", lutris_db_path" } else { "" };
+    let read = db::read_schema(&db_path).and_then(|schema| Ok((schema, db::get_installed_games(&db_path)?)));
+    match read {
+        Ok((schema, games)) => {
+            println!("Database:  {} ({} installed games{note})", db_path.display(), games.len());
+            println!("Schema:    {}", schema.describe());
+        }
+        Err(e) => println!("Database:  {} — {e}", db_path.display()),
+    }
+
+    if config.launcher == launcher::Launcher::Heroic {
+        match launcher::heroic::heroic_dir(config.heroic_dir.as_deref()) {
+            Some(dir) => match launcher::from_config(config).and_then(|heroic| heroic.games(config, true)) {
+                Ok(games) => println!("Heroic:    {} ({} games)", dir.display(), games.len()),
+                Err(e) => println!("Heroic:    {} — {e}", dir.display()),
+            },
+            None => println!("Heroic:    not found"),
+        }
+    } else {
+        match launcher::from_config(config) {
+            Ok(backend) if backend.reads_lutris_db() => {}
+            Ok(backend) => match backend.games(config, true) {
+                Ok(games) => println!("Games:     {} ({} games)", backend.name(), games.len()),
+                Err(e) => println!("Games:     {} — {e}", backend.name()),
+            },
+            Err(e) => println!("Games:     {e}"),
+        }
+    }
+
+    if let Some(ref dir) = config.output_dir {
+        println!("Art:       {} (output_dir)", dir.display());
+    }
+
+    let icon_dir = config::lutris_icon_dir()?;
+    let target = match config.icon_target {
+        IconTarget::Hicolor => "hicolor",
+        IconTarget::Lutris => "lutris",
+        IconTarget::Custom(_) => "custom",
+    };
+    println!("Icons:     {} (icon_target = {target})", icon_dir.display());
+    // Whichever location already holds Lutris icons is the one this Lutris reads
+    let paths = config.art_paths()?;
+    for target in [IconTarget::Hicolor, IconTarget::Lutris] {
+        let dir = paths.icon_dir(&target);
+        let count = std::fs::read_dir(&dir).map_or(0, |entries| {
+            entries
+                .filter_map(Result::ok)
+                .filter(|e| e.file_name().to_string_lossy().starts_with("lutris_"))
+                .count()
+        });
+        if count > 0 {
+            let note = if dir == icon_dir { "" } else { " — consider setting icon_target to match" };
+            println!("           Lutris has {count} icons in {}{note}", dir.display());
+        }
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Restore
+// ---------------------------------------------------------------------------
+
+/// Restore backed-up art: everything the last run replaced, or for `per_game`, the
+/// latest backups of each game regardless of run.
+fn run_restore(launcher: &dyn LauncherBackend, games: &[db::Game], per_game: bool) -> Result<()> {
+    let run = if per_game {
+        None
+    } else {
+        Some(download::last_backup_run().ok_or_else(|| eyre!("No backed-up art to restore"))?)
+    };
+    let mut manifest = Manifest::load()?;
+
+    let (mut restored, mut failed) = (0u32, 0u32);
+    for game in games {
+        for (asset, result) in download::restore_backups(launcher, &mut manifest, &game.slug, run.as_deref()) {
+            match result {
+                Ok(done) => {
+                    restored += 1;
+                    println!("  ✓ {} [{}] — {} restored", game.name, asset.display_name(), done.path.display());
+                    if let Some(warning) = done.warning {
+                        println!("    ! {warning}");
+                    }
+                }
+                Err(e) => {
+                    failed += 1;
+                    println!("  ✗ {} [{}]: {e}", game.name, asset.display_name());
+                }
+            }
+        }
+    }
+    manifest.save()?;
+
+    println!("\n{restored} assets restored, {failed} failed");
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Manifest sync
+// ---------------------------------------------------------------------------
+
+/// Download every asset recorded in another machine's manifest that is missing here.
+async fn run_sync(
+    config: &Config,
+    launcher: &Arc<dyn LauncherBackend>,
+    games: &[db::Game],
+    assets: &HashSet<AssetType>,
+    from: &Path,
+) -> Result<()> {
+    let remote = Manifest::load_from(from)?;
+
+    // Image downloads go to the CDN, which needs no API key
+    let client = SteamGridDbClient::new(config.api_key.as_deref().unwrap_or_default(), config)?;
+    let opts = download::DownloadOpts::new(config, Arc::clone(launcher), false, false);
+
+    let missing: Vec<_> = games
+        .iter()
+        .filter_map(|g| remote.games.get(&g.slug).map(|entries| (g, entries)))
+        .flat_map(|(g, entries)| entries.iter().map(move |(&asset, entry)| (g, asset, entry)))
+        .filter(|&(g, asset, _)| assets.contains(&asset) && !asset_exists(&**launcher, asset, &g.slug))
+        .collect();
+
+    println!("{} assets to fetch from {}\n", missing.len(), from.display());
+
+    let (mut fetched, mut failed) = (0u32, 0u32);
+    for (game, asset, entry) in missing {
+        match download::fetch_pinned(&client, &game.slug, asset, entry, &opts).await {
+            Ok(path) => {
+                fetched += 1;
+                println!("  ✓ {} [{}] → {}", game.name, asset.display_name(), path.display());
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {} [{}]: {e}", game.name, asset.display_name());
+            }
+        }
+        for warning in opts.take_warnings().into_iter().chain(client.take_warnings()) {
+            println!("    ! {warning}");
+        }
+    }
+
+    opts.save_manifest()?;
+    println!("\nSynced: {fetched} fetched, {failed} failed");
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Art packs
+// ---------------------------------------------------------------------------
+
+fn run_export_pack(launcher: &dyn LauncherBackend, games: &[db::Game], path: &Path) -> Result<()> {
+    let count = pack::export(launcher, games, &Manifest::load()?, path)?;
+    println!("Packed {count} images into {}", path.display());
+    Ok(())
+}
+
+/// Save the art in a pack for the installed games, keeping the art already here unless
+/// `force` is set — and, even then, art changed by hand or set in Lutris.
+async fn run_import_pack(
+    config: &Config,
+    launcher: &Arc<dyn LauncherBackend>,
+    games: &[db::Game],
+    path: &Path,
+    force: bool,
+) -> Result<()> {
+    let mut pack = pack::Pack::open(path)?;
+    let opts = download::DownloadOpts::new(config, Arc::clone(launcher), force, false);
+
+    let art: Vec<_> = pack
+        .art()
+        .iter()
+        .filter_map(|a| Some((games.iter().find(|g| g.slug == a.slug)?, a.clone())))
+        .collect();
+
+    let (mut imported, mut kept, mut failed) = (0u32, 0u32, 0u32);
+    for (game, art) in art {
+        let exists = asset_exists(&**launcher, art.asset, &game.slug);
+        if let Some(reason) = download::reason_to_keep(game, art.asset, &opts, exists, false).await {
+            if exists && force {
+                println!("  - {} [{}]: {reason}", game.name, art.asset.display_name());
+            }
+            kept += 1;
+            continue;
+        }
+        let result = match pack.read(&art) {
+            Ok(bytes) => download::save_packed(&game.slug, art.asset, &bytes, pack.entry(&art), &opts).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(path) => {
+                imported += 1;
+                println!("  ✓ {} [{}] → {}", game.name, art.asset.display_name(), path.display());
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {} [{}]: {e}", game.name, art.asset.display_name());
+            }
+        }
+        for warning in opts.take_warnings() {
+            println!("    ! {warning}");
+        }
+    }
+
+    opts.save_manifest()?;
+    println!("\nImported: {imported} saved, {kept} kept, {failed} failed");
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Update check
+// ---------------------------------------------------------------------------
+
+/// The art providers for a command that queries them without the TUI.
+fn query_providers(config: &Config) -> Result<Vec<Box<dyn provider::ArtProvider>>> {
+    let api_key = match config.api_key.as_deref() {
+        Some(key) => key,
+        None if !provider::needs_api_key(config) => "",
+        None => return Err(eyre!("No API key configured. Run the TUI once to set one.")),
+    };
+    provider::from_config(api_key, config)
+}
+
+async fn run_check(
+    config: &Config,
+    launcher: &Arc<dyn LauncherBackend>,
+    games: &[db::Game],
+    assets: &HashSet<AssetType>,
+) -> Result<()> {
+    let providers = query_providers(config)?;
+    let opts = download::DownloadOpts::new(config, Arc::clone(launcher), false, false);
+
+    let (mut outdated, mut failed) = (0u32, 0u32);
+    for game in games.iter().filter(|g| !config.excludes(g)) {
+        match download::check_for_updates(&providers, game, assets, &opts).await {
+            Ok(updates) => {
+                for (asset, saved, best) in updates {
+                    outdated += 1;
+                    println!(
+                        "  ↑ {} [{}]: #{} → #{} {}",
+                        game.name,
+                        asset.display_name(),
+                        saved.asset_id,
+                        best.id,
+                        best.url
+                    );
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                println!("  ✗ {}: {e}", game.name);
+            }
+        }
+    }
+
+    println!("\n{outdated} saved assets have better art available; {failed} games couldn't be checked");
+    if outdated > 0 {
+        println!("Fetch them with --update.");
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Coverage audit
+// ---------------------------------------------------------------------------
+
+async fn run_audit(
+    config: &Config,
+    launcher: &Arc<dyn LauncherBackend>,
+    games: &[db::Game],
+    assets: &HashSet<AssetType>,
+) -> Result<()> {
+    let providers = query_providers(config)?;
+    let opts = download::DownloadOpts::new(config, Arc::clone(launcher), false, false);
+
+    let (mut saved, mut available, mut unavailable, mut failed) = (0u32, 0u32, 0u32, 0u32);
+    for game in games.iter().filter(|g| !config.excludes(g)) {
+        println!("  {} ({})", game.name, game.slug);
+        match download::audit_game(&providers, game, assets, &opts).await {
+            Ok(coverage) => {
+                for (asset, coverage) in coverage {
+                    let status = match coverage {
+                        Coverage::Local => {
+                            saved += 1;
+                            "✓ saved".to_owned()
+                        }
+                        Coverage::Available(provider, best) => {
+                            available += 1;
+                            format!("↓ available on {provider} (#{})", best.id)
+                        }
+                        Coverage::Unavailable(reason) => {
+                            unavailable += 1;
+                            format!("✗ {reason}")
+                        }
+                    };
+                    println!("    {:<7}{status}", asset.display_name());
+                }
+            }
+            Err(e) => {
+                failed += 1;
+                println!("    ✗ couldn't be checked: {e}");
+            }
+        }
+    }
+
+    let total = saved + available + unavailable;
+    let percent = (saved * 100).checked_div(total).unwrap_or(100);
+    println!("\nCoverage: {saved} of {total} assets saved ({percent}%), {available} available, {unavailable} not available anywhere");
+    if failed > 0 {
+        println!("{failed} games couldn't be checked");
+    }
+    if available > 0 {
+        println!("Run without `audit` to fetch the available ones.");
+    }
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // Headless mode
 // ---------------------------------------------------------------------------
 
 async fn run_headless(
     config: Config,
+    launcher: Arc<dyn LauncherBackend>,
     games: Vec<db::Game>,
     assets: HashSet<AssetType>,
     force: bool,
+    resume: bool,
+    retry_only: Option<FailedAssets>,
 ) -> Result<()> {
-    let api_key = config
-        .api_key
-        .as_deref()
-        .ok_or_else(|| eyre!("No API key configured. Run without --no-tui to set one interactively."))?;
+    // Replays never reach the network, and other sources don't use the key
+    let api_key = match config.api_key.as_deref() {
+        Some(key) => key,
+        None if config.replay_dir.is_some() || !provider::needs_api_key(&config) => "",
+        None => {
+            return Err(eyre!(
+                "No API key configured. Run without --no-tui to set one interactively."
+            ));
+        }
+    };
 
-    let client = SteamGridDbClient::new(api_key, config.request_delay_ms)?;
+    let providers = provider::from_config(api_key, &config)?;
 
-    println!("Found {} installed games", games.len());
+    println!("Found {}", count_games(&games));
     println!(
         "Downloading: {}",
         assets
@@ -172,106 +968,195 @@ async fn run_headless(
             .collect::<Vec<_>>()
             .join(", ")
     );
+    if let Some(secs) = RunHistory::load().estimate_run(&*launcher, &games, &assets, force) {
+        println!("Estimated time: ~{} (from past runs)", history::format_eta(secs));
+    }
     println!();
 
     let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
 
     let games_clone = games.clone();
     let assets_clone = assets.clone();
-    let grid_dim = config.preferred_grid_dimension.clone();
-    let nsfw = config.nsfw_filter;
-    let humor = config.humor_filter;
     let max_conc = config.max_concurrent_downloads as usize;
 
     // Spawn download pipeline
-    let opts = download::DownloadOpts {
-        grid_dim: grid_dim.clone(),
-        nsfw_filter: nsfw,
-        humor_filter: humor,
-        force,
-    };
+    let mut opts = download::DownloadOpts::new(&config, Arc::clone(&launcher), force, resume);
+    opts.retry_only = retry_only;
     tokio::spawn(async move {
         download::download_all(
-            &client,
+            &providers,
             &games_clone,
             &assets_clone,
             &opts,
             max_conc,
             tx,
+            None,
         )
         .await;
     });
 
     // Consume progress messages
-    let mut downloaded = 0u32;
-    let mut skipped = 0u32;
-    let mut failed = 0u32;
+    let mut report = HeadlessReport::default();
+    while let Some(event) = rx.recv().await {
+        report.handle(event, &games);
+    }
+
+    println!();
+    let covered = |slug: &str, asset| assets.contains(&asset) && games.iter().any(|g| g.slug == slug);
+    if let Err(e) = report.failures.save_merged(covered) {
+        println!("Warning: could not save the list of failed assets: {e}");
+    }
+    let HeadlessReport {
+        counts,
+        auth_expired,
+        aborted,
+        ..
+    } = report;
+    let AssetCounts { downloaded, skipped, failed } = counts.total();
+    if let Some(reason) = aborted {
+        println!("Stopped! Downloaded: {downloaded}, Skipped: {skipped}, Failed: {failed}");
+        return Err(eyre!("{reason}. Re-run with --resume once it's back."));
+    }
+    if auth_expired {
+        println!("Stopped! Downloaded: {downloaded}, Skipped: {skipped}, Failed: {failed}");
+        return Err(eyre!(
+            "SteamGridDB rejected the API key mid-run. Set a new one with \
+             `lutrisartfetcher config set-key`, then re-run with --resume."
+        ));
+    }
+    println!("Done! Downloaded: {downloaded}, Skipped: {skipped}, Failed: {failed}");
+    if counts.by_asset.len() > 1 {
+        for (asset, c) in &counts.by_asset {
+            println!("  {:<7}{} downloaded, {} skipped, {} failed", asset.display_name(), c.downloaded, c.skipped, c.failed);
+        }
+    }
+    if failed > 0 {
+        println!("Fetch the failed assets again with --retry-failed.");
+    }
+    let saved = api::client::bytes_saved();
+    if saved > 0 {
+        println!("Compression saved {} of API traffic", format_size(saved));
+    }
+    println!("{}", api::stats::snapshot().summary());
+    if let Some(app) = launcher.shown_in() {
+        println!("Restart {app} to see the changes.");
+    }
+
+    Ok(())
+}
+
+/// Running tally for headless output.
+#[derive(Debug, Default)]
+struct HeadlessReport {
+    counts: RunCounts,
+    auth_expired: bool,
+    /// Why the pipeline gave up early, if it did.
+    aborted: Option<String>,
+    /// Slug of the game whose search was printed last.
+    searching: String,
+    /// What failed, for `--retry-failed`.
+    failures: FailedAssets,
+}
+
+impl HeadlessReport {
+    /// Print one pipeline event and update the counters.
+    fn handle(&mut self, event: PipelineEvent, games: &[db::Game]) {
+        let progress = match event {
+            PipelineEvent::Progress(progress) => progress,
+            PipelineEvent::ChunkFinished { chunk, chunks } => {
+                let AssetCounts { downloaded, skipped, failed } = self.counts.total();
+                println!(
+                    "── Chunk {chunk}/{chunks} checkpointed — downloaded: {downloaded}, \
+                     skipped: {skipped}, failed: {failed} so far"
+                );
+                return;
+            }
+            PipelineEvent::Warning(msg) => {
+                println!("  ! {msg}");
+                return;
+            }
+            PipelineEvent::AuthExpired => {
+                self.auth_expired = true;
+                return;
+            }
+            PipelineEvent::Aborted(reason) | PipelineEvent::NotStarted(reason) => {
+                self.aborted = Some(reason);
+                return;
+            }
+        };
 
-    while let Some(progress) = rx.recv().await {
         let display = games
             .iter()
             .find(|g| g.slug == progress.game_slug)
             .map_or_else(|| progress.game_slug.clone(), |g| g.name.clone());
 
+        self.counts.record(progress.asset_type, &progress.status);
         match &progress.status {
             api::models::DownloadStatus::Done(path) => {
-                downloaded += 1;
                 println!("  ✓ {display} — {} saved", path.display());
             }
             api::models::DownloadStatus::Skipped(reason) => {
-                skipped += 1;
                 println!("  ─ {display} — {} skipped: {reason}", progress.asset_type);
             }
             api::models::DownloadStatus::Failed(msg) => {
-                failed += 1;
+                self.failures.insert(&progress.game_slug, progress.asset_type);
                 println!("  ✗ {display} — {} failed: {msg}", progress.asset_type);
             }
-            api::models::DownloadStatus::Searching => {
-                print!("  ⟳ Searching for {display}...");
+            // Every asset of a game reports the search; print it once
+            api::models::DownloadStatus::Searching if self.searching != progress.game_slug => {
+                println!("  ⟳ Searching for {display}...");
+                self.searching = progress.game_slug;
             }
-            api::models::DownloadStatus::Downloading => {
-                println!(" downloading {}", progress.asset_type);
+            api::models::DownloadStatus::Downloading { received: 0, .. } => {
+                println!("  ⇣ {display} — downloading {}", progress.asset_type);
             }
-            api::models::DownloadStatus::Pending => {}
+            api::models::DownloadStatus::Searching
+            | api::models::DownloadStatus::Downloading { .. }
+            | api::models::DownloadStatus::Pending => {}
         }
     }
-
-    println!();
-    println!("Done! Downloaded: {downloaded}, Skipped: {skipped}, Failed: {failed}");
-    println!("Restart Lutris to see the changes.");
-
-    Ok(())
 }
 
 // ---------------------------------------------------------------------------
 // Dry-run mode
 // ---------------------------------------------------------------------------
 
-fn run_dry_run(games: &[db::Game], assets: &HashSet<AssetType>) -> Result<()> {
+fn run_dry_run(
+    launcher: &dyn LauncherBackend,
+    mut games: Vec<db::Game>,
+    assets: &HashSet<AssetType>,
+    sort: SortOrder,
+) -> Result<()> {
     println!("DRY RUN — no files will be downloaded\n");
-    println!("Found {} installed games\n", games.len());
+    println!("Found {}\n", count_games(&games));
+
+    sort.sort(&mut games, launcher, |g| g);
 
     let mut would_download = 0u32;
     let mut already_exist = 0u32;
+    let mut total_size = 0u64;
 
-    for game in games {
+    for game in &games {
         let mut statuses = Vec::new();
         for asset in assets {
-            if asset_exists(*asset, &game.slug) {
+            if asset_exists(launcher, *asset, &game.slug) {
                 already_exist += 1;
                 statuses.push(format!("{}: exists", asset.display_name()));
             } else {
                 would_download += 1;
-                let path = asset_path(*asset, &game.slug)?;
+                let path = asset_path(launcher, *asset, &game.slug)?;
                 statuses.push(format!("{}: would download → {}", asset.display_name(), path.display()));
             }
         }
-        println!("  {} ({})", game.name, game.slug);
+        let size = art_size_on_disk(launcher, &game.slug);
+        total_size += size;
+        println!("  {} ({}) — {} on disk", game.name, game.slug, format_size(size));
         for s in &statuses {
             println!("    {s}");
         }
     }
 
     println!("\nSummary: {would_download} assets to download, {already_exist} already exist");
+    println!("Art on disk: {}", format_size(total_size));
     Ok(())
 }
@@ -0,0 +1,284 @@
+/// Download manifest — which `SteamGridDB` asset each saved file came from.
+///
+/// Updated whenever art is saved. Copying it to another machine and running
+/// `sync --from <manifest>` there fetches exactly the same assets for installed games
+/// that are missing them, so both libraries end up with identical art.
+///
+/// Each entry also keeps the saved file's SHA-256, so a file the user has since replaced
+/// or edited by hand can be told apart from the one downloaded.
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use color_eyre::eyre::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::api::models::AssetType;
+use crate::config;
+
+/// The asset saved for one (game, asset type) pair.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// `SteamGridDB` asset ID.
+    pub asset_id: u64,
+    /// CDN URL the image was downloaded from.
+    pub url: String,
+    /// `ETag` the CDN sent with the image, for conditional re-downloads.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// `Last-Modified` the CDN sent with the image.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_modified: Option<String>,
+    /// When the file was saved, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub saved_at: Option<u64>,
+    /// SHA-256 of the saved file, hex-encoded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Size of the saved file in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Modification time of the saved file, in nanoseconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modified_ns: Option<u64>,
+    /// Where the art this file replaced was moved to, in the run's backup directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup: Option<PathBuf>,
+    /// The entry of the art this file replaced, if it had one; it takes this entry's
+    /// place again when the backup is restored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replaced: Option<Box<ManifestEntry>>,
+}
+
+impl ManifestEntry {
+    /// This entry for the file just saved at `path`: timestamped now, with its hash,
+    /// size and modification time.
+    #[must_use]
+    pub fn stamped(mut self, path: &Path) -> Self {
+        self.saved_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs());
+        self.sha256 = file_sha256(path);
+        let metadata = std::fs::metadata(path).ok();
+        self.size = metadata.as_ref().map(std::fs::Metadata::len);
+        self.modified_ns = metadata.as_ref().and_then(modified_ns);
+        self
+    }
+
+    /// Whether the file at `path` is no longer the one saved — the user replaced or
+    /// edited it. Entries from before hashes were recorded never count as changed.
+    ///
+    /// A file of another size has changed, and one with the recorded size and
+    /// modification time hasn't; anything else is hashed, off the async runtime.
+    pub async fn changed_on_disk(&self, path: &Path) -> bool {
+        let Some(saved) = self.sha256.clone() else {
+            return false;
+        };
+        let Ok(metadata) = tokio::fs::metadata(path).await else {
+            return true;
+        };
+        if self.size.is_some_and(|size| size != metadata.len()) {
+            return true;
+        }
+        if self.size.is_some() && self.modified_ns.is_some() && modified_ns(&metadata) == self.modified_ns {
+            return false;
+        }
+        let path = path.to_owned();
+        let current = tokio::task::spawn_blocking(move || file_sha256(&path))
+            .await
+            .ok()
+            .flatten();
+        current != Some(saved)
+    }
+}
+
+/// A file's modification time in nanoseconds since the Unix epoch.
+fn modified_ns(metadata: &std::fs::Metadata) -> Option<u64> {
+    let since_epoch = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?;
+    u64::try_from(since_epoch.as_nanos()).ok()
+}
+
+/// Hex SHA-256 of a file's contents, or `None` if it can't be read.
+pub fn file_sha256(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    Some(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Saved assets, keyed by game slug and asset type.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub games: BTreeMap<String, BTreeMap<AssetType, ManifestEntry>>,
+}
+
+impl Manifest {
+    /// Location of this machine's manifest.
+    pub fn path() -> Result<PathBuf> {
+        Ok(config::app_data_dir()?.join("manifest.json"))
+    }
+
+    /// Load this machine's manifest, or an empty one if none exists yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest exists but cannot be read or parsed.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        Self::load_from(&path)
+    }
+
+    /// Load this machine's manifest like [`Manifest::load`]; one that can't be read is
+    /// moved aside to `manifest.json.corrupt`, so it isn't overwritten, and a new one
+    /// started. Returns a warning saying so if it was.
+    pub fn load_or_set_aside() -> (Self, Option<String>) {
+        let error = match Self::load() {
+            Ok(manifest) => return (manifest, None),
+            Err(e) => e,
+        };
+        let warning = match Self::path() {
+            Ok(path) => {
+                let aside = path.with_extension("json.corrupt");
+                match std::fs::rename(&path, &aside) {
+                    Ok(()) => format!("{error:#} — moved it to {} and started a new one", aside.display()),
+                    Err(e) => format!("{error:#} — and it could not be moved aside: {e}"),
+                }
+            }
+            Err(_) => format!("{error:#}"),
+        };
+        (Self::default(), Some(warning))
+    }
+
+    /// Load a manifest from an arbitrary file, e.g. one exported from another machine.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid manifest.
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("Failed to read manifest {}", path.display()))?;
+        serde_json::from_str(&content)
+            .wrap_err_with(|| format!("Failed to parse manifest {}", path.display()))
+    }
+
+    /// Persist the manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).wrap_err("Failed to create data directory")?;
+        }
+        let content = serde_json::to_string_pretty(self).wrap_err("Failed to serialize manifest")?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, content)
+            .wrap_err_with(|| format!("Failed to write manifest to {}", tmp.display()))?;
+        std::fs::rename(&tmp, &path).wrap_err("Failed to move manifest into place")?;
+        Ok(())
+    }
+
+    /// The asset saved for a game, if any.
+    pub fn get(&self, slug: &str, asset: AssetType) -> Option<&ManifestEntry> {
+        self.games.get(slug)?.get(&asset)
+    }
+
+    /// Record the asset saved for a game.
+    pub fn record(&mut self, slug: &str, asset: AssetType, entry: ManifestEntry) {
+        self.games.entry(slug.to_owned()).or_default().insert(asset, entry);
+    }
+
+    /// Forget the asset saved for a game.
+    pub fn remove(&mut self, slug: &str, asset: AssetType) -> Option<ManifestEntry> {
+        let assets = self.games.get_mut(slug)?;
+        let entry = assets.remove(&asset);
+        if assets.is_empty() {
+            self.games.remove(slug);
+        }
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fixture;
+
+    fn entry() -> ManifestEntry {
+        ManifestEntry {
+            asset_id: 1,
+            url: "https://cdn.example/1.png".into(),
+            etag: None,
+            last_modified: None,
+            saved_at: None,
+            sha256: None,
+            size: None,
+            modified_ns: None,
+            backup: None,
+            replaced: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn saved_files_changed_by_hand_are_noticed() {
+        let path = fixture::sandbox().join("manifest-stamped.png");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"downloaded").unwrap();
+
+        let stamped = entry().stamped(&path);
+        assert_eq!(stamped.sha256, file_sha256(&path));
+        assert_eq!(stamped.size, Some(10));
+        assert!(stamped.saved_at.is_some() && stamped.modified_ns.is_some());
+        assert!(!stamped.changed_on_disk(&path).await);
+
+        // Same size and a new modification time: told apart by the hash
+        std::fs::write(&path, b"edited-it!").unwrap();
+        let touched = ManifestEntry { modified_ns: Some(0), ..stamped.clone() };
+        assert!(touched.changed_on_disk(&path).await);
+        // Another size: changed without hashing
+        std::fs::write(&path, b"replaced by hand").unwrap();
+        assert!(stamped.changed_on_disk(&path).await);
+        // Missing files have changed too; entries without a hash never have
+        std::fs::remove_file(&path).unwrap();
+        assert!(stamped.changed_on_disk(&path).await);
+        assert!(!entry().changed_on_disk(&path).await);
+    }
+
+    #[tokio::test]
+    async fn unreadable_manifests_are_reported_and_set_aside() {
+        // The pipeline tests share this machine's manifest
+        let _turn = fixture::pipeline_turn().await;
+        fixture::sandbox();
+        let path = Manifest::path().unwrap();
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let saved = std::fs::read(&path).ok();
+        std::fs::write(&path, "{ not json").unwrap();
+
+        assert!(Manifest::load().is_err());
+        let (manifest, warning) = Manifest::load_or_set_aside();
+        assert!(manifest.games.is_empty());
+        assert!(warning.is_some_and(|w| w.contains("manifest.json.corrupt")));
+        let aside = path.with_extension("json.corrupt");
+        assert_eq!(std::fs::read_to_string(&aside).unwrap(), "{ not json");
+        assert!(Manifest::load().unwrap().games.is_empty());
+
+        let _ = std::fs::remove_file(aside);
+        if let Some(saved) = saved {
+            std::fs::write(&path, saved).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn rewriting_the_same_bytes_is_not_a_change() {
+        let path = fixture::sandbox().join("manifest-rewritten.png");
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, b"downloaded").unwrap();
+        let stamped = ManifestEntry { modified_ns: Some(0), ..entry().stamped(&path) };
+        assert!(!stamped.changed_on_disk(&path).await);
+    }
+}
@@ -0,0 +1,120 @@
+/// Matching Lutris games to `SteamGridDB` search results.
+///
+/// Search results are scored by name similarity so that "Doom" doesn't silently pick up
+/// "Doom (2016)", and the chosen game's details are sanity-checked before any art is
+/// fetched for it.
+use std::collections::HashSet;
+
+use crate::api::models::{GameInfo, SearchResult};
+use crate::db::Game;
+
+/// The search result whose name is most similar to `name`, with its confidence (0–1).
+///
+/// Similarity is the Sørensen–Dice coefficient over character bigrams, which tolerates
+/// edition suffixes ("… Game of the Year Edition") better than edit distance. Ties keep
+/// the API's order.
+pub fn best_match<'a>(name: &str, results: &'a [SearchResult]) -> Option<(&'a SearchResult, f64)> {
+    let target = normalize(name);
+    results
+        .iter()
+        .map(|r| (r, strsim::sorensen_dice(&target, &normalize(&r.name))))
+        .fold(None, |best, (r, score)| match best {
+            Some((_, best_score)) if best_score >= score => best,
+            _ => Some((r, score)),
+        })
+}
+
+/// Lowercase, with punctuation dropped and whitespace collapsed, so "DOOM®" and "Doom"
+/// compare equal.
+fn normalize(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Why a `SteamGridDB` game can't be the Lutris game, if it clearly can't.
+pub fn mismatch_reason(game: &Game, info: &GameInfo) -> Option<String> {
+    let words = |name: &str| -> HashSet<String> {
+        name.to_lowercase()
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.len() > 1 && !matches!(*w, "the" | "of" | "and"))
+            .map(str::to_owned)
+            .collect()
+    };
+    if words(game.search_name()).is_disjoint(&words(&info.name)) {
+        return Some(format!("search matched unrelated game \"{}\"", info.name));
+    }
+
+    let released = info.release_date.map(unix_year);
+    if let (Some(year), Some(released)) = (game.year, released) {
+        if (year - released).abs() > 1 {
+            return Some(format!(
+                "search matched \"{}\" from {released}, not {year}",
+                info.name
+            ));
+        }
+    }
+    None
+}
+
+/// Calendar year (UTC) of a Unix timestamp.
+fn unix_year(timestamp: i64) -> i64 {
+    // Civil-from-days, after Howard Hinnant's date algorithms
+    let days = timestamp.div_euclid(86_400) + 719_468;
+    let era = days.div_euclid(146_097);
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let year = yoe + era * 400;
+    if mp >= 10 {
+        year + 1
+    } else {
+        year
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(id: u64, name: &str) -> SearchResult {
+        SearchResult {
+            id,
+            name: name.to_owned(),
+            types: Vec::new(),
+            verified: true,
+        }
+    }
+
+    #[test]
+    fn best_match_prefers_the_closest_name() {
+        let results = [
+            result(1, "Doom Eternal"),
+            result(2, "DOOM"),
+            result(3, "Doom II"),
+        ];
+        let (best, confidence) = best_match("Doom", &results).unwrap();
+        assert_eq!(best.id, 2);
+        assert!((confidence - 1.0).abs() < f64::EPSILON);
+
+        let (best, confidence) = best_match("Doom", &results[..1]).unwrap();
+        assert_eq!(best.id, 1);
+        assert!(confidence < 0.6);
+
+        let goty = [result(4, "The Witcher 3: Wild Hunt")];
+        let (_, confidence) =
+            best_match("The Witcher 3: Wild Hunt - Game of the Year Edition", &goty).unwrap();
+        assert!(confidence >= 0.6);
+    }
+
+    #[test]
+    fn unix_year_handles_year_boundaries() {
+        assert_eq!(unix_year(0), 1970);
+        assert_eq!(unix_year(1_488_326_399), 2017); // 2017-02-28T23:59:59Z
+        assert_eq!(unix_year(1_704_067_199), 2023); // 2023-12-31T23:59:59Z
+        assert_eq!(unix_year(1_704_067_200), 2024);
+    }
+}
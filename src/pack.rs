@@ -0,0 +1,228 @@
+/// Art packs — zip archives of saved art and its manifest entries, for moving curated art
+/// between machines without downloading it again.
+///
+/// Each image is stored as `art/<slug>/<asset>.<ext>` (e.g. `art/hollow-knight/grid.png`),
+/// next to a `manifest.json` holding the manifest entries of the packed art.
+use std::fs::File;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use color_eyre::eyre::{Context, Result, eyre};
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::api::models::AssetType;
+use crate::db::Game;
+use crate::download;
+use crate::launcher::LauncherBackend;
+use crate::manifest::{Manifest, ManifestEntry};
+
+/// Name of the manifest inside a pack.
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Largest file read out of a pack, so a crafted one can't exhaust memory.
+const MAX_ENTRY_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Write all saved art of `games` and its manifest entries to a pack at `path`. Returns
+/// how many images were packed. Nothing is left behind if it fails.
+///
+/// # Errors
+///
+/// Returns an error if the pack or a saved image can't be written or read.
+pub fn export(launcher: &dyn LauncherBackend, games: &[Game], manifest: &Manifest, path: &Path) -> Result<usize> {
+    let tmp = path.with_extension("tmp");
+    let count = write_pack(launcher, games, manifest, &tmp)
+        .and_then(|count| std::fs::rename(&tmp, path).wrap_err("Failed to move pack into place").map(|()| count));
+    if count.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    count
+}
+
+/// Write the pack for [`export`] to `tmp`.
+fn write_pack(launcher: &dyn LauncherBackend, games: &[Game], manifest: &Manifest, tmp: &Path) -> Result<usize> {
+    let file = File::create(tmp).wrap_err_with(|| format!("Failed to create {}", tmp.display()))?;
+    let mut zip = ZipWriter::new(file);
+    // Images are compressed already
+    let stored = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+
+    let mut packed = Manifest::default();
+    let mut count = 0;
+    for game in games {
+        for &asset in AssetType::all() {
+            let Some(source) = download::existing_asset_path(launcher, asset, &game.slug) else {
+                continue;
+            };
+            let ext = source.extension().and_then(|e| e.to_str()).unwrap_or("png");
+            let name = format!("art/{}/{}.{ext}", game.slug, asset.display_name().to_lowercase());
+            let bytes = std::fs::read(&source)
+                .wrap_err_with(|| format!("Failed to read {}", source.display()))?;
+            zip.start_file(name, stored).wrap_err("Failed to write pack")?;
+            zip.write_all(&bytes).wrap_err("Failed to write pack")?;
+            count += 1;
+
+            // Backups only exist on this machine
+            if let Some(entry) = manifest.get(&game.slug, asset) {
+                let entry = ManifestEntry { backup: None, replaced: None, ..entry.clone() };
+                packed.record(&game.slug, asset, entry);
+            }
+        }
+    }
+
+    let content = serde_json::to_string_pretty(&packed).wrap_err("Failed to serialize manifest")?;
+    zip.start_file(MANIFEST_NAME, SimpleFileOptions::default())
+        .wrap_err("Failed to write pack")?;
+    zip.write_all(content.as_bytes()).wrap_err("Failed to write pack")?;
+    zip.finish().wrap_err("Failed to write pack")?;
+    Ok(count)
+}
+
+/// Read a file out of a pack, refusing one bigger than [`MAX_ENTRY_BYTES`].
+fn read_entry(mut file: impl Read, name: &str) -> Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    file.by_ref()
+        .take(MAX_ENTRY_BYTES + 1)
+        .read_to_end(&mut bytes)
+        .wrap_err("Failed to read pack")?;
+    if bytes.len() as u64 > MAX_ENTRY_BYTES {
+        return Err(eyre!("{name} in the pack is over {} MB", MAX_ENTRY_BYTES / 1024 / 1024));
+    }
+    Ok(bytes)
+}
+
+/// An image in a pack.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PackedArt {
+    pub slug: String,
+    pub asset: AssetType,
+    /// Position of the image in the archive.
+    index: usize,
+}
+
+/// A pack opened for reading.
+pub struct Pack {
+    archive: ZipArchive<File>,
+    manifest: Manifest,
+    art: Vec<PackedArt>,
+}
+
+impl Pack {
+    /// Open the pack at `path` and list its images.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be read or is not an art pack.
+    pub fn open(path: &Path) -> Result<Self> {
+        let file = File::open(path).wrap_err_with(|| format!("Failed to open {}", path.display()))?;
+        let mut archive = ZipArchive::new(file)
+            .wrap_err_with(|| format!("{} is not a zip archive", path.display()))?;
+
+        let manifest = {
+            let entry = archive
+                .by_name(MANIFEST_NAME)
+                .map_err(|_| eyre!("{} is not an art pack: it has no {MANIFEST_NAME}", path.display()))?;
+            let content = read_entry(entry, MANIFEST_NAME)?;
+            serde_json::from_slice(&content).wrap_err("Failed to parse pack manifest")?
+        };
+
+        let art = (0..archive.len())
+            .filter_map(|index| {
+                let name = archive.name_for_index(index)?;
+                let (slug, file) = name.strip_prefix("art/")?.split_once('/')?;
+                let (asset, _ext) = file.split_once('.')?;
+                // Slugs end up in file names
+                if slug.is_empty() || slug.starts_with('.') || file.contains('/') {
+                    return None;
+                }
+                Some(PackedArt { slug: slug.to_owned(), asset: asset.parse().ok()?, index })
+            })
+            .collect();
+        Ok(Self { archive, manifest, art })
+    }
+
+    /// The images in the pack.
+    pub fn art(&self) -> &[PackedArt] {
+        &self.art
+    }
+
+    /// The packing machine's manifest entry for an image, if it had one.
+    pub fn entry(&self, art: &PackedArt) -> Option<&ManifestEntry> {
+        self.manifest.get(&art.slug, art.asset)
+    }
+
+    /// Read an image out of the pack.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the image can't be decompressed or is implausibly big.
+    pub fn read(&mut self, art: &PackedArt) -> Result<Vec<u8>> {
+        let file = self.archive.by_index(art.index).wrap_err("Failed to read pack")?;
+        let name = file.name().to_owned();
+        read_entry(file, &name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::lutris::Lutris;
+    use crate::provider::fixture;
+
+    fn entry(asset_id: u64) -> ManifestEntry {
+        ManifestEntry {
+            asset_id,
+            url: format!("https://cdn.example/{asset_id}.png"),
+            etag: None,
+            last_modified: None,
+            saved_at: Some(1),
+            sha256: None,
+            size: None,
+            modified_ns: None,
+            backup: Some("/backups/1/grid.png".into()),
+            replaced: None,
+        }
+    }
+
+    #[test]
+    fn exported_art_is_imported_as_packed() {
+        let dir = fixture::sandbox().join("packs");
+        std::fs::create_dir_all(&dir).unwrap();
+        let grid = fixture::png(3, 2);
+        let target = download::asset_path_with_extension(&Lutris, AssetType::Grid, "pack-roundtrip", "png").unwrap();
+        std::fs::create_dir_all(target.parent().unwrap()).unwrap();
+        std::fs::write(&target, &grid).unwrap();
+        let mut manifest = Manifest::default();
+        manifest.record("pack-roundtrip", AssetType::Grid, entry(7));
+
+        let path = dir.join("roundtrip.zip");
+        let games = [fixture::game("pack-roundtrip", "Round Trip"), fixture::game("pack-artless", "Artless")];
+        assert_eq!(export(&Lutris, &games, &manifest, &path).unwrap(), 1);
+
+        let mut pack = Pack::open(&path).unwrap();
+        let art = pack.art().to_vec();
+        assert_eq!(art.iter().map(|a| (a.slug.as_str(), a.asset)).collect::<Vec<_>>(), [("pack-roundtrip", AssetType::Grid)]);
+        // Backups stay on the packing machine
+        assert_eq!(pack.entry(&art[0]), Some(&ManifestEntry { backup: None, ..entry(7) }));
+        assert_eq!(pack.read(&art[0]).unwrap(), grid);
+    }
+
+    #[test]
+    fn failed_exports_leave_nothing_behind() {
+        let dir = fixture::sandbox().join("packs");
+        std::fs::create_dir_all(&dir).unwrap();
+        // A directory where the art should be can't be read
+        let target = download::asset_path_with_extension(&Lutris, AssetType::Banner, "pack-unreadable", "png").unwrap();
+        std::fs::create_dir_all(&target).unwrap();
+
+        let path = dir.join("failed.zip");
+        assert!(export(&Lutris, &[fixture::game("pack-unreadable", "Unreadable")], &Manifest::default(), &path).is_err());
+        assert!(!path.exists() && !path.with_extension("tmp").exists());
+    }
+
+    #[test]
+    fn oversized_entries_are_refused() {
+        let bytes = vec![0_u8; usize::try_from(MAX_ENTRY_BYTES).unwrap() + 1];
+        assert!(read_entry(bytes.as_slice(), "art/huge/grid.png").is_err());
+        assert_eq!(read_entry(&b"small"[..], "art/small/grid.png").unwrap(), b"small");
+    }
+}
@@ -0,0 +1,152 @@
+/// In-memory [`ArtProvider`] for tests.
+///
+/// Serves games, asset lists, and image bytes registered up front, so the download
+/// pipeline — and the TUI state it drives — can be exercised end to end without the
+/// network. Paths are redirected into a scratch directory by [`sandbox`].
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+use color_eyre::eyre::{Result, eyre};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+use super::{ArtProvider, Resolution};
+use crate::api::client::{AssetQuery, FileSink, ImageDownload, StatusError, Validators};
+use crate::api::models::{AssetType, ImageAsset};
+use crate::db::Game;
+
+/// Canned responses, keyed by game slug, provider ID and asset type, and image URL.
+#[derive(Default)]
+pub struct FixtureProvider {
+    games: HashMap<String, u64>,
+    assets: HashMap<(u64, AssetType), Vec<ImageAsset>>,
+    images: HashMap<String, Vec<u8>>,
+    /// Fail every request, as if the source were down.
+    unreachable: bool,
+    /// Fail lookups of these slugs, as if the source went down just then.
+    outages: HashSet<String>,
+    /// Fail the first lookup of these slugs with a 503, as if the source hiccuped.
+    hiccups: Mutex<HashSet<String>>,
+}
+
+impl FixtureProvider {
+    /// A provider whose every request fails.
+    pub fn unreachable() -> Self {
+        Self {
+            unreachable: true,
+            ..Self::default()
+        }
+    }
+
+    /// Make `slug` resolve to `id`.
+    pub fn with_game(mut self, slug: &str, id: u64) -> Self {
+        self.games.insert(slug.to_owned(), id);
+        self
+    }
+
+    /// Fail lookups of `slug` as if the source were down.
+    pub fn with_outage(mut self, slug: &str) -> Self {
+        self.outages.insert(slug.to_owned());
+        self
+    }
+
+    /// Fail the first lookup of `slug` with a 503, then answer as usual.
+    pub fn with_hiccup(self, slug: &str) -> Self {
+        self.hiccups.lock().unwrap().insert(slug.to_owned());
+        self
+    }
+
+    /// Offer one image of type `asset` for game `id`.
+    pub fn with_art(mut self, id: u64, asset: AssetType, image: &[u8]) -> Self {
+        let url = format!("fixture://{id}/{}", asset.lutris_subdir());
+        self.assets.entry((id, asset)).or_default().push(ImageAsset {
+            id,
+            url: url.clone(),
+            ..ImageAsset::default()
+        });
+        self.images.insert(url, image.to_vec());
+        self
+    }
+}
+
+impl ArtProvider for FixtureProvider {
+    fn name(&self) -> &'static str {
+        "Fixture"
+    }
+
+    fn find_game<'a>(&'a self, game: &'a Game, _min_confidence: f64) -> BoxFuture<'a, Result<Resolution>> {
+        let resolution = if self.unreachable || self.outages.contains(&game.slug) {
+            Err(eyre!("connection refused"))
+        } else if self.hiccups.lock().unwrap().remove(&game.slug) {
+            Err(StatusError::new(reqwest::StatusCode::SERVICE_UNAVAILABLE, "try again later".into()).into())
+        } else {
+            Ok(self.games.get(&game.slug).map_or(Resolution::NotFound, |&id| Resolution::Found(id)))
+        };
+        async move { resolution }.boxed()
+    }
+
+    fn list_assets<'a>(
+        &'a self,
+        _game: &'a Game,
+        id: u64,
+        asset: AssetType,
+        _query: AssetQuery<'a>,
+        _max_pages: u32,
+    ) -> BoxFuture<'a, Result<Vec<ImageAsset>>> {
+        let assets = self.assets.get(&(id, asset)).cloned().unwrap_or_default();
+        async move { Ok(assets) }.boxed()
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        _known: &'a Validators,
+        sink: &'a FileSink<'a>,
+    ) -> BoxFuture<'a, Result<ImageDownload>> {
+        async move {
+            let bytes = self.images.get(url).ok_or_else(|| eyre!("no fixture image at {url}"))?;
+            let size = sink.write_bytes(bytes).await?;
+            Ok(ImageDownload::Modified {
+                size,
+                validators: Validators::default(),
+            })
+        }
+        .boxed()
+    }
+}
+
+/// A Lutris game with just a name and slug.
+pub fn game(slug: &str, name: &str) -> Game {
+    Game::named(slug.to_owned(), name.to_owned())
+}
+
+/// A valid, blank PNG of the given size; different sizes give different bytes.
+pub fn png(width: u32, height: u32) -> Vec<u8> {
+    let mut bytes = std::io::Cursor::new(Vec::new());
+    image::RgbImage::new(width, height)
+        .write_to(&mut bytes, image::ImageFormat::Png)
+        .expect("encoding a blank PNG");
+    bytes.into_inner()
+}
+
+/// Wait for other tests running the pipeline to finish. Runs share the checkpoint and
+/// journal in the [`sandbox`], which each starts afresh and clears once done.
+pub async fn pipeline_turn() -> tokio::sync::MutexGuard<'static, ()> {
+    static TURN: tokio::sync::Mutex<()> = tokio::sync::Mutex::const_new(());
+    TURN.lock().await
+}
+
+/// Point the XDG data and cache directories at a scratch directory for this test run,
+/// so saved art, manifests, and checkpoints never touch the real ones. Tests sharing
+/// it should use distinct slugs.
+pub fn sandbox() -> &'static Path {
+    static DIR: OnceLock<PathBuf> = OnceLock::new();
+    DIR.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("lutrisartfetcher-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::env::set_var("XDG_DATA_HOME", dir.join("data"));
+        std::env::set_var("XDG_CACHE_HOME", dir.join("cache"));
+        dir
+    })
+}
@@ -0,0 +1,197 @@
+/// [`ArtProvider`] for GOG's own store art.
+///
+/// GOG games carry their product ID as the Lutris `service_id`, and GOG's public
+/// product API lists the box art, background, logo, and icon for it — exact matches,
+/// used for GOG games `SteamGridDB` has no art for.
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use color_eyre::eyre::{Context, Result};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+
+use super::{ArtProvider, Resolution};
+use crate::api::client::{AssetQuery, FileSink, ImageDownload, StatusError, Validators, http_client};
+use crate::api::models::{AssetType, ImageAsset};
+use crate::config::Config;
+use crate::db::Game;
+
+const PRODUCT_URL: &str = "https://api.gog.com/v2/games";
+
+/// Fetches store art from GOG.
+pub struct Gog {
+    client: Client,
+    /// Image links per product, so the asset types of one game share a request.
+    links: Mutex<HashMap<u64, ProductLinks>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Product {
+    #[serde(rename = "_links")]
+    links: ProductLinks,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProductLinks {
+    box_art_image: Option<Link>,
+    galaxy_background_image: Option<Link>,
+    background_image: Option<Link>,
+    logo: Option<Link>,
+    icon_square: Option<Link>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Link {
+    href: String,
+}
+
+impl ProductLinks {
+    /// The image for an asset type, if GOG has one.
+    fn image(&self, asset: AssetType) -> Option<&str> {
+        let link = match asset {
+            AssetType::Grid => self.box_art_image.as_ref(),
+            AssetType::Hero => self.galaxy_background_image.as_ref().or(self.background_image.as_ref()),
+            AssetType::Logo => self.logo.as_ref(),
+            AssetType::Icon => self.icon_square.as_ref(),
+            AssetType::Banner => None,
+        };
+        link.map(|l| l.href.as_str())
+    }
+}
+
+impl Gog {
+    /// Create the provider, using the proxy and timeouts from `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: &Config) -> Result<Self> {
+        let client = http_client(config, config.http_timeout_secs)
+            .wrap_err("Failed to build GOG HTTP client")?;
+        Ok(Self {
+            client,
+            links: Mutex::new(HashMap::new()),
+        })
+    }
+
+    async fn product_links(&self, id: u64) -> Result<ProductLinks> {
+        if let Some(links) = self.links.lock().unwrap_or_else(PoisonError::into_inner).get(&id) {
+            return Ok(links.clone());
+        }
+        let resp = self
+            .client
+            .get(format!("{PRODUCT_URL}/{id}"))
+            .send()
+            .await
+            .wrap_err_with(|| format!("GOG product request failed for {id}"))?;
+        let links = if resp.status().is_success() {
+            resp.json::<Product>()
+                .await
+                .wrap_err("Failed to parse GOG product response")?
+                .links
+        } else if resp.status() == reqwest::StatusCode::NOT_FOUND {
+            ProductLinks::default()
+        } else {
+            let message = format!("GOG product request failed with status {} for {id}", resp.status());
+            return Err(StatusError::new(resp.status(), message).into());
+        };
+        self.links
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(id, links.clone());
+        Ok(links)
+    }
+}
+
+impl ArtProvider for Gog {
+    fn name(&self) -> &'static str {
+        "GOG"
+    }
+
+    fn serves(&self, game: &Game) -> bool {
+        game.service.as_deref() == Some("gog")
+    }
+
+    fn find_game<'a>(&'a self, game: &'a Game, _min_confidence: f64) -> BoxFuture<'a, Result<Resolution>> {
+        let id = game.service_id.as_deref().and_then(|id| id.parse().ok());
+        let resolution = id.map_or(Resolution::NotFound, Resolution::Found);
+        async move { Ok(resolution) }.boxed()
+    }
+
+    fn list_assets<'a>(
+        &'a self,
+        _game: &'a Game,
+        id: u64,
+        asset: AssetType,
+        _query: AssetQuery<'a>,
+        _max_pages: u32,
+    ) -> BoxFuture<'a, Result<Vec<ImageAsset>>> {
+        async move {
+            // Store art comes in one size; preferred sizes can't be matched exactly
+            let links = self.product_links(id).await?;
+            let Some(url) = links.image(asset) else {
+                return Ok(Vec::new());
+            };
+            // Some GOG links are protocol-relative
+            let url = match url.strip_prefix("//") {
+                Some(rest) => format!("https://{rest}"),
+                None => url.to_owned(),
+            };
+            // The MIME type is left empty; the saved file's extension is sniffed from its bytes
+            Ok(vec![ImageAsset {
+                id,
+                url,
+                ..ImageAsset::default()
+            }])
+        }
+        .boxed()
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        known: &'a Validators,
+        sink: &'a FileSink<'a>,
+    ) -> BoxFuture<'a, Result<ImageDownload>> {
+        super::fetch_image(&self.client, url, known, sink).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fixture;
+
+    #[tokio::test]
+    async fn gog_games_get_their_product_art() {
+        let gog = Gog::new(&Config::default()).unwrap();
+        let game = Game {
+            service: Some("gog".into()),
+            service_id: Some("1207658924".into()),
+            ..fixture::game("celeste", "Celeste")
+        };
+        assert!(gog.serves(&game));
+        assert!(!gog.serves(&fixture::game("celeste", "Celeste")));
+        assert_eq!(gog.find_game(&game, 1.0).await.unwrap(), Resolution::Found(1_207_658_924));
+
+        let product: Product = serde_json::from_str(
+            r#"{"_links": {
+                "boxArtImage": {"href": "//images.gog.com/box.jpg"},
+                "backgroundImage": {"href": "https://images.gog.com/background.jpg"}
+            }}"#,
+        )
+        .unwrap();
+        gog.links.lock().unwrap().insert(1_207_658_924, product.links);
+        let (gog, game) = (&gog, &game);
+        let urls = |asset| async move {
+            let assets = gog.list_assets(game, 1_207_658_924, asset, AssetQuery::default(), 1).await.unwrap();
+            assets.into_iter().map(|a| a.url).collect::<Vec<_>>()
+        };
+        assert_eq!(urls(AssetType::Grid).await, ["https://images.gog.com/box.jpg"]);
+        assert_eq!(urls(AssetType::Hero).await, ["https://images.gog.com/background.jpg"]);
+        assert!(urls(AssetType::Banner).await.is_empty());
+    }
+}
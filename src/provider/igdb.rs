@@ -0,0 +1,227 @@
+/// [`ArtProvider`] for IGDB cover art.
+///
+/// IGDB knows many obscure and emulated titles `SteamGridDB` lacks. It only has covers,
+/// which stand in for grids. Requests authenticate with a Twitch app's client ID and
+/// secret (`igdb_client_id` / `igdb_client_secret`), exchanged for an access token that
+/// is renewed as it expires.
+use std::time::{Duration, Instant};
+
+use color_eyre::eyre::{Context, Result, eyre};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use reqwest::Client;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use tokio::sync::Mutex;
+
+use super::{ArtProvider, Resolution};
+use crate::api::client::{AssetQuery, FileSink, ImageDownload, Validators, http_client};
+use crate::api::models::{AssetType, GameInfo, ImageAsset, SearchResult};
+use crate::api::throttle::Throttle;
+use crate::config::Config;
+use crate::db::Game;
+use crate::matching;
+
+const TOKEN_URL: &str = "https://id.twitch.tv/oauth2/token";
+const API_URL: &str = "https://api.igdb.com/v4";
+const IMAGE_URL: &str = "https://images.igdb.com/igdb/image/upload";
+
+/// IGDB allows four requests per second.
+const REQUEST_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Cover rendition to download, and its size.
+const COVER_SIZE: (&str, u32, u32) = ("t_cover_big_2x", 528, 748);
+
+/// Fetches covers from IGDB.
+pub struct Igdb {
+    client: Client,
+    client_id: String,
+    client_secret: String,
+    /// Current access token and when it stops being valid.
+    token: Mutex<Option<(String, Instant)>>,
+    throttle: Throttle,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    expires_in: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgdbGame {
+    id: u64,
+    #[serde(default)]
+    name: String,
+    first_release_date: Option<i64>,
+    cover: Option<IgdbImage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct IgdbImage {
+    image_id: String,
+}
+
+impl Igdb {
+    /// Create the provider from the IGDB credentials in `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the credentials are missing or the HTTP client cannot be built.
+    pub fn new(config: &Config) -> Result<Self> {
+        let (Some(id), Some(secret)) = (&config.igdb_client_id, &config.igdb_client_secret) else {
+            return Err(eyre!("IGDB needs igdb_client_id and igdb_client_secret in config"));
+        };
+        let client = http_client(config, config.http_timeout_secs)
+            .wrap_err("Failed to build IGDB HTTP client")?;
+        Ok(Self {
+            client,
+            client_id: id.clone(),
+            client_secret: secret.clone(),
+            token: Mutex::new(None),
+            throttle: Throttle::new(REQUEST_INTERVAL, 1),
+        })
+    }
+
+    /// A valid access token, fetching a new one when the current one is about to expire.
+    async fn access_token(&self) -> Result<String> {
+        let mut token = self.token.lock().await;
+        if let Some((ref value, expires)) = *token {
+            if Instant::now() + Duration::from_secs(60) < expires {
+                return Ok(value.clone());
+            }
+        }
+        let resp: TokenResponse = self
+            .client
+            .post(TOKEN_URL)
+            .query(&[
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", self.client_secret.as_str()),
+                ("grant_type", "client_credentials"),
+            ])
+            .send()
+            .await
+            .wrap_err("IGDB token request failed")?
+            .error_for_status()
+            .wrap_err("IGDB rejected the client ID or secret")?
+            .json()
+            .await
+            .wrap_err("Failed to parse IGDB token response")?;
+        let expires = Instant::now() + Duration::from_secs(resp.expires_in);
+        *token = Some((resp.access_token.clone(), expires));
+        Ok(resp.access_token)
+    }
+
+    /// Run an Apicalypse query against an IGDB endpoint.
+    async fn query<T: DeserializeOwned>(&self, endpoint: &str, body: String) -> Result<Vec<T>> {
+        let token = self.access_token().await?;
+        self.throttle.wait().await;
+        self.client
+            .post(format!("{API_URL}/{endpoint}"))
+            .header("Client-ID", &self.client_id)
+            .bearer_auth(token)
+            .body(body)
+            .send()
+            .await
+            .wrap_err("IGDB request failed")?
+            .error_for_status()
+            .wrap_err("IGDB request failed")?
+            .json()
+            .await
+            .wrap_err("Failed to parse IGDB response")
+    }
+
+    /// Search by name, scoring results like `SteamGridDB` searches.
+    async fn resolve(&self, game: &Game, min_confidence: f64) -> Result<Resolution> {
+        let name = game.search_name().replace('"', "");
+        let found: Vec<IgdbGame> = self
+            .query("games", format!("search \"{name}\"; fields name,first_release_date; limit 10;"))
+            .await?;
+        let results: Vec<SearchResult> = found
+            .iter()
+            .map(|g| SearchResult {
+                id: g.id,
+                name: g.name.clone(),
+                types: Vec::new(),
+                verified: false,
+            })
+            .collect();
+        let Some((best, confidence)) = matching::best_match(game.search_name(), &results) else {
+            return Ok(Resolution::NotFound);
+        };
+        if confidence < min_confidence {
+            return Ok(Resolution::Rejected(format!(
+                "ambiguous IGDB match: \"{}\" ({:.0}% confident)",
+                best.name,
+                confidence * 100.0
+            )));
+        }
+        let info = GameInfo {
+            id: best.id,
+            name: best.name.clone(),
+            release_date: found.iter().find(|g| g.id == best.id).and_then(|g| g.first_release_date),
+            types: Vec::new(),
+            verified: false,
+        };
+        Ok(match matching::mismatch_reason(game, &info) {
+            Some(reason) => Resolution::Rejected(reason),
+            None => Resolution::Found(info.id),
+        })
+    }
+
+    async fn covers(&self, id: u64) -> Result<Vec<ImageAsset>> {
+        let found: Vec<IgdbGame> = self
+            .query("games", format!("fields cover.image_id; where id = {id};"))
+            .await?;
+        let (size, width, height) = COVER_SIZE;
+        Ok(found
+            .into_iter()
+            .filter_map(|g| g.cover)
+            .map(|cover| ImageAsset {
+                id,
+                width,
+                height,
+                mime: "image/jpeg".to_owned(),
+                url: format!("{IMAGE_URL}/{size}/{}.jpg", cover.image_id),
+                ..ImageAsset::default()
+            })
+            .collect())
+    }
+}
+
+impl ArtProvider for Igdb {
+    fn name(&self) -> &'static str {
+        "IGDB"
+    }
+
+    fn find_game<'a>(&'a self, game: &'a Game, min_confidence: f64) -> BoxFuture<'a, Result<Resolution>> {
+        self.resolve(game, min_confidence).boxed()
+    }
+
+    fn list_assets<'a>(
+        &'a self,
+        _game: &'a Game,
+        id: u64,
+        asset: AssetType,
+        _query: AssetQuery<'a>,
+        _max_pages: u32,
+    ) -> BoxFuture<'a, Result<Vec<ImageAsset>>> {
+        async move {
+            // Covers come in one aspect ratio; preferred grid sizes can't be matched exactly
+            if asset != AssetType::Grid {
+                return Ok(Vec::new());
+            }
+            self.covers(id).await
+        }
+        .boxed()
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        known: &'a Validators,
+        sink: &'a FileSink<'a>,
+    ) -> BoxFuture<'a, Result<ImageDownload>> {
+        super::fetch_image(&self.client, url, known, sink).boxed()
+    }
+}
@@ -0,0 +1,208 @@
+/// Art sources the download pipeline can fetch from.
+///
+/// The pipeline only talks to [`ArtProvider`], so another source — or a test double —
+/// can stand in for `SteamGridDB` without touching `download.rs`. Methods return boxed
+/// futures so providers can be used as trait objects.
+///
+/// Providers are tried in order: a fallback is only asked for an asset the ones before
+/// it had no acceptable art for.
+#[cfg(test)]
+pub mod fixture;
+pub mod gog;
+pub mod igdb;
+pub mod steam;
+pub mod steamgriddb;
+
+use color_eyre::eyre::{Context, Result, eyre};
+use futures::future::BoxFuture;
+use reqwest::header::{self, HeaderMap, HeaderValue};
+use reqwest::{Client, StatusCode};
+
+use crate::api::SteamGridDbClient;
+use crate::api::client::{AssetQuery, FileSink, ImageDownload, StatusError, Validators, is_error_page_type};
+use crate::api::models::{AssetType, ImageAsset};
+use crate::config::Config;
+use crate::db::Game;
+
+/// An art source, for `--source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Source {
+    #[value(name = "steamgriddb")]
+    SteamGridDb,
+    /// Steam's own store art (Steam games only).
+    Steam,
+    /// GOG's own store art (GOG games only).
+    Gog,
+    /// IGDB covers (needs IGDB credentials in config).
+    Igdb,
+}
+
+/// Outcome of looking a Lutris game up with a provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// The provider's ID for the game.
+    Found(u64),
+    NotFound,
+    /// A candidate was found but doesn't look like this game.
+    Rejected(String),
+}
+
+/// A source of game art.
+pub trait ArtProvider: Send + Sync {
+    /// Name shown in status messages, e.g. `"SteamGridDB"`.
+    fn name(&self) -> &'static str;
+
+    /// Whether this provider can have art for `game` at all. Store-specific providers
+    /// only serve their own store's games; the rest are skipped without a lookup.
+    fn serves(&self, _game: &Game) -> bool {
+        true
+    }
+
+    /// Find the provider's entry for a game. Name-based matches scoring below
+    /// `min_confidence` (0–1) are rejected.
+    fn find_game<'a>(&'a self, game: &'a Game, min_confidence: f64) -> BoxFuture<'a, Result<Resolution>>;
+
+    /// List the candidate images of one type for a game resolved by [`Self::find_game`].
+    fn list_assets<'a>(
+        &'a self,
+        game: &'a Game,
+        id: u64,
+        asset: AssetType,
+        query: AssetQuery<'a>,
+        max_pages: u32,
+    ) -> BoxFuture<'a, Result<Vec<ImageAsset>>>;
+
+    /// Download an image into `sink` unless it still matches `known`.
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        known: &'a Validators,
+        sink: &'a FileSink<'a>,
+    ) -> BoxFuture<'a, Result<ImageDownload>>;
+
+    /// Swap in a new API key after the old one was rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is unusable or the provider doesn't take one.
+    fn set_api_key(&self, _key: &str) -> Result<()> {
+        Err(eyre!("{} doesn't use an API key", self.name()))
+    }
+
+    /// Problems met that didn't fail a lookup or download. Each is returned once.
+    fn take_warnings(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// The providers enabled in `config`, in the order they are tried: `SteamGridDB`, then
+/// GOG's store art for GOG games, Steam's CDN, and IGDB. `config.source` narrows this to
+/// one provider.
+///
+/// # Errors
+///
+/// Returns an error if a provider's HTTP client cannot be set up, or the selected
+/// source lacks credentials.
+pub fn from_config(api_key: &str, config: &Config) -> Result<Vec<Box<dyn ArtProvider>>> {
+    let providers: Vec<Box<dyn ArtProvider>> = match config.source {
+        Some(Source::SteamGridDb) => vec![Box::new(SteamGridDbClient::new(api_key, config)?)],
+        Some(Source::Steam) => vec![Box::new(steam::SteamCdn::new(config)?)],
+        Some(Source::Gog) => vec![Box::new(gog::Gog::new(config)?)],
+        Some(Source::Igdb) => vec![Box::new(igdb::Igdb::new(config)?)],
+        None => {
+            let mut chain: Vec<Box<dyn ArtProvider>> = vec![Box::new(SteamGridDbClient::new(api_key, config)?)];
+            if config.gog_store_art {
+                chain.push(Box::new(gog::Gog::new(config)?));
+            }
+            if config.steam_cdn_fallback {
+                chain.push(Box::new(steam::SteamCdn::new(config)?));
+            }
+            if config.igdb_client_id.is_some() && config.igdb_client_secret.is_some() {
+                chain.push(Box::new(igdb::Igdb::new(config)?));
+            }
+            chain
+        }
+    };
+    Ok(providers)
+}
+
+/// Whether the configured providers include `SteamGridDB`, which needs an API key.
+pub fn needs_api_key(config: &Config) -> bool {
+    matches!(config.source, None | Some(Source::SteamGridDb))
+}
+
+/// Plain conditional image download for providers without a client of their own: a
+/// single GET with `If-None-Match` / `If-Modified-Since` from `known`, streamed into
+/// `sink`.
+///
+/// An earlier attempt's partial file is resumed with a `Range` request, guarded by
+/// `If-Range` so a changed image is sent whole; if the server won't resume it, it is
+/// dropped and the image fetched whole.
+async fn fetch_image(client: &Client, url: &str, known: &Validators, sink: &FileSink<'_>) -> Result<ImageDownload> {
+    let mut headers = HeaderMap::new();
+    let conditions = [
+        (header::IF_NONE_MATCH, &known.etag),
+        (header::IF_MODIFIED_SINCE, &known.last_modified),
+    ];
+    for (name, value) in conditions {
+        if let Some(value) = value.as_deref().and_then(|v| HeaderValue::from_str(v).ok()) {
+            headers.insert(name, value);
+        }
+    }
+    let send = |headers: HeaderMap| async move {
+        client
+            .get(url)
+            .headers(headers)
+            .send()
+            .await
+            .wrap_err_with(|| format!("Image download failed for {url}"))
+    };
+    let resume = sink.resume_headers();
+    let mut resp = if resume.is_empty() {
+        send(headers.clone()).await?
+    } else {
+        let mut ranged = headers.clone();
+        ranged.extend(resume);
+        send(ranged).await?
+    };
+    if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        sink.discard_partial().await;
+        resp = send(headers).await?;
+    }
+
+    if resp.status() == StatusCode::NOT_MODIFIED {
+        return Ok(ImageDownload::NotModified);
+    }
+    if !resp.status().is_success() {
+        let message = format!("Image download returned status {}", resp.status());
+        return Err(StatusError::new(resp.status(), message).into());
+    }
+    let header_string = |name: header::HeaderName| {
+        resp.headers()
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .map(str::to_owned)
+    };
+    if let Some(kind) = header_string(header::CONTENT_TYPE).filter(|t| is_error_page_type(t)) {
+        return Err(eyre!("Image download returned {kind} instead of an image"));
+    }
+    let validators = Validators {
+        etag: header_string(header::ETAG),
+        last_modified: header_string(header::LAST_MODIFIED),
+    };
+    let size = sink.write_response(resp).await?;
+    Ok(ImageDownload::Modified { size, validators })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steamgriddb_is_tried_first() {
+        let config = Config { cache_ttl_hours: 0, ..Config::default() };
+        let providers = from_config("key", &config).unwrap();
+        let names: Vec<&str> = providers.iter().map(|p| p.name()).collect();
+        assert_eq!(names, ["SteamGridDB", "GOG", "Steam"]);
+    }
+}
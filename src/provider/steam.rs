@@ -0,0 +1,136 @@
+/// [`ArtProvider`] for Steam's own store art, served straight from its CDN.
+///
+/// Only Steam games can be resolved — the app ID is the Lutris `service_id`. Steam has
+/// exactly one library capsule (600×900), hero, and logo per app and no icons, so
+/// there is nothing to rank. Used as a fallback when `SteamGridDB` has no art.
+use color_eyre::eyre::{Context, Result};
+use futures::FutureExt;
+use futures::future::BoxFuture;
+use reqwest::Client;
+
+use super::{ArtProvider, Resolution};
+use crate::api::client::{AssetQuery, FileSink, ImageDownload, Validators, http_client};
+use crate::api::models::{AssetType, ImageAsset};
+use crate::config::Config;
+use crate::db::Game;
+
+/// Root of Steam's per-app art.
+const STEAM_CDN_URL: &str = "https://cdn.cloudflare.steamstatic.com/steam/apps";
+
+/// Fetches library art from Steam's CDN.
+pub struct SteamCdn {
+    client: Client,
+}
+
+impl SteamCdn {
+    /// Create the provider, using the proxy and timeouts from `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(config: &Config) -> Result<Self> {
+        let client = http_client(config, config.http_timeout_secs.saturating_mul(2))
+            .wrap_err("Failed to build Steam CDN HTTP client")?;
+        Ok(Self { client })
+    }
+
+    /// Whether the CDN has `url`, checked with a `HEAD` request.
+    async fn exists(&self, url: &str) -> Result<bool> {
+        let resp = self.client.head(url).send().await?;
+        Ok(resp.status().is_success())
+    }
+}
+
+/// Steam's file name, MIME type, and size (where fixed) for an asset type.
+fn steam_file(asset: AssetType) -> Option<(&'static str, &'static str, u32, u32)> {
+    match asset {
+        AssetType::Grid => Some(("library_600x900.jpg", "image/jpeg", 600, 900)),
+        AssetType::Banner => Some(("header.jpg", "image/jpeg", 460, 215)),
+        AssetType::Hero => Some(("library_hero.jpg", "image/jpeg", 0, 0)),
+        AssetType::Logo => Some(("logo.png", "image/png", 0, 0)),
+        AssetType::Icon => None,
+    }
+}
+
+impl ArtProvider for SteamCdn {
+    fn name(&self) -> &'static str {
+        "Steam"
+    }
+
+    fn serves(&self, game: &Game) -> bool {
+        game.service.as_deref() == Some("steam")
+    }
+
+    fn find_game<'a>(&'a self, game: &'a Game, _min_confidence: f64) -> BoxFuture<'a, Result<Resolution>> {
+        let appid = game.service_id.as_deref().and_then(|id| id.parse().ok());
+        let resolution = appid.map_or(Resolution::NotFound, Resolution::Found);
+        async move { Ok(resolution) }.boxed()
+    }
+
+    fn list_assets<'a>(
+        &'a self,
+        _game: &'a Game,
+        id: u64,
+        asset: AssetType,
+        query: AssetQuery<'a>,
+        _max_pages: u32,
+    ) -> BoxFuture<'a, Result<Vec<ImageAsset>>> {
+        async move {
+            let Some((file, mime, width, height)) = steam_file(asset) else {
+                return Ok(Vec::new());
+            };
+            // The capsule only comes in one size; other art's size isn't known up front
+            if width > 0 && query.dimensions.is_some_and(|d| d != format!("{width}x{height}")) {
+                return Ok(Vec::new());
+            }
+            let url = format!("{STEAM_CDN_URL}/{id}/{file}");
+            if !self.exists(&url).await? {
+                return Ok(Vec::new());
+            }
+            Ok(vec![ImageAsset {
+                id,
+                width,
+                height,
+                mime: mime.to_owned(),
+                url,
+                ..ImageAsset::default()
+            }])
+        }
+        .boxed()
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        known: &'a Validators,
+        sink: &'a FileSink<'a>,
+    ) -> BoxFuture<'a, Result<ImageDownload>> {
+        super::fetch_image(&self.client, url, known, sink).boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fixture;
+
+    #[tokio::test]
+    async fn steam_games_resolve_by_app_id() {
+        let steam = SteamCdn::new(&Config::default()).unwrap();
+        let game = Game {
+            service: Some("steam".into()),
+            service_id: Some("504230".into()),
+            ..fixture::game("celeste", "Celeste")
+        };
+        assert!(steam.serves(&game));
+        assert!(!steam.serves(&fixture::game("celeste", "Celeste")));
+        assert_eq!(steam.find_game(&game, 1.0).await.unwrap(), Resolution::Found(504_230));
+        let unparsable = Game { service_id: Some("celeste".into()), ..game.clone() };
+        assert_eq!(steam.find_game(&unparsable, 1.0).await.unwrap(), Resolution::NotFound);
+
+        // Nothing Steam can't have is asked for: no icons, and only the one capsule size
+        let other_size = AssetQuery { dimensions: Some("342x482"), ..AssetQuery::default() };
+        assert!(steam.list_assets(&game, 504_230, AssetType::Grid, other_size, 1).await.unwrap().is_empty());
+        assert!(steam.list_assets(&game, 504_230, AssetType::Icon, AssetQuery::default(), 1).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+/// [`ArtProvider`] backed by the `SteamGridDB` API.
+///
+/// Games from a store `SteamGridDB` indexes (Steam, GOG, Epic, EA, Ubisoft) are looked
+/// up by their store ID; everything else by a text search whose best result is scored
+/// and sanity-checked (see [`crate::matching`]).
+use color_eyre::eyre::Result;
+use futures::FutureExt;
+use futures::future::BoxFuture;
+
+use super::{ArtProvider, Resolution};
+use crate::api::SteamGridDbClient;
+use crate::api::client::{AssetLookup, AssetQuery, FileSink, ImageDownload, Validators};
+use crate::api::models::{AssetType, ImageAsset};
+use crate::db::Game;
+use crate::matching;
+
+impl ArtProvider for SteamGridDbClient {
+    fn name(&self) -> &'static str {
+        "SteamGridDB"
+    }
+
+    fn find_game<'a>(&'a self, game: &'a Game, min_confidence: f64) -> BoxFuture<'a, Result<Resolution>> {
+        async move {
+            Ok(match resolve_game_id(self, game, min_confidence).await? {
+                Resolution::Found(id) if self.excludes_game(id) => {
+                    Resolution::Rejected(format!("SteamGridDB game #{id} is excluded in config"))
+                }
+                resolution => resolution,
+            })
+        }
+        .boxed()
+    }
+
+    fn list_assets<'a>(
+        &'a self,
+        game: &'a Game,
+        id: u64,
+        asset: AssetType,
+        query: AssetQuery<'a>,
+        max_pages: u32,
+    ) -> BoxFuture<'a, Result<Vec<ImageAsset>>> {
+        // The platform endpoint is more accurate for store games, but doesn't know every
+        // store ID; fall back to the game ID when it has nothing. An overridden ID is
+        // used as is.
+        async move {
+            // Art from the configured collection wins whatever its size
+            let curated = self.without_blocked(self.collection_assets(id, asset, &query, max_pages).await?);
+            if !curated.is_empty() {
+                return Ok(curated);
+            }
+            let store = store_id(game).filter(|_| self.id_override(&game.slug).is_none());
+            if let Some((platform, sid)) = store {
+                let lookup = AssetLookup::Platform { platform, id: sid };
+                let assets = self.without_blocked(self.get_assets_paged(asset, lookup, &query, max_pages).await?);
+                if !assets.is_empty() {
+                    return Ok(assets);
+                }
+            }
+            let assets = self.get_assets_paged(asset, AssetLookup::Game(id), &query, max_pages).await?;
+            Ok(self.without_blocked(assets))
+        }
+        .boxed()
+    }
+
+    fn download<'a>(
+        &'a self,
+        url: &'a str,
+        known: &'a Validators,
+        sink: &'a FileSink<'a>,
+    ) -> BoxFuture<'a, Result<ImageDownload>> {
+        self.download_image_if_modified(url, known, sink).boxed()
+    }
+
+    fn set_api_key(&self, key: &str) -> Result<()> {
+        SteamGridDbClient::set_api_key(self, key)
+    }
+
+    fn take_warnings(&self) -> Vec<String> {
+        SteamGridDbClient::take_warnings(self)
+    }
+}
+
+/// `SteamGridDB`'s platform slug for a Lutris service, if it indexes that store.
+fn platform(service: &str) -> Option<&'static str> {
+    match service {
+        "steam" => Some("steam"),
+        "gog" => Some("gog"),
+        "egs" => Some("egs"),
+        "origin" | "ea_app" => Some("origin"),
+        "ubisoft" => Some("uplay"),
+        _ => None,
+    }
+}
+
+/// A game's `SteamGridDB` platform and store ID, when it came from an indexed store.
+fn store_id(game: &Game) -> Option<(&'static str, &str)> {
+    let platform = platform(game.service.as_deref()?)?;
+    Some((platform, game.service_id.as_deref()?))
+}
+
+/// Resolve a game's `SteamGridDB` ID — from `[overrides]` if the slug has one, then by
+/// platform lookup if available, otherwise text search.
+///
+/// Text-search results are scored against the game's name; the best one must reach
+/// `min_confidence` and pass a check against its details, so that a wildly different
+/// title or release year is rejected rather than given the wrong art.
+async fn resolve_game_id(
+    client: &SteamGridDbClient,
+    game: &Game,
+    min_confidence: f64,
+) -> Result<Resolution> {
+    if let Some(id) = client.id_override(&game.slug) {
+        return Ok(Resolution::Found(id));
+    }
+
+    // Try platform-specific lookup first (more accurate)
+    if let Some((platform, sid)) = store_id(game) {
+        if let Some(info) = client.get_game_by_platform(platform, sid).await? {
+            return Ok(Resolution::Found(info.id));
+        }
+    }
+
+    // Fallback: text search using the slug converted to a human-readable name
+    let search_term = game.search_slug().replace('-', " ");
+    let results = client.search(&search_term).await?;
+    let Some((best, confidence)) = matching::best_match(game.search_name(), &results) else {
+        return Ok(Resolution::NotFound);
+    };
+    if confidence < min_confidence {
+        return Ok(Resolution::Rejected(format!(
+            "ambiguous match: \"{}\" ({:.0}% confident)",
+            best.name,
+            confidence * 100.0
+        )));
+    }
+
+    let info = client.get_game(best.id).await?;
+    Ok(match matching::mismatch_reason(game, &info) {
+        Some(reason) => Resolution::Rejected(reason),
+        None => Resolution::Found(info.id),
+    })
+}
@@ -0,0 +1,132 @@
+/// Static HTML gallery of the library's installed art.
+///
+/// `report --html <file>` writes a single self-contained page — images are embedded as
+/// data URIs — with one card per game showing its grid, hero, logo, and icon, and a
+/// placeholder for every missing asset. Useful for spotting wrong matches at a glance.
+use std::collections::HashSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use color_eyre::eyre::{Context, Result};
+
+use crate::api::models::AssetType;
+use crate::config::Config;
+use crate::db::Game;
+use crate::download::existing_asset_path;
+use crate::launcher::LauncherBackend;
+
+const STYLE: &str = "
+body { font-family: sans-serif; background: #1e1e2e; color: #cdd6f4; margin: 2em; }
+h1 { margin-bottom: 0.2em; }
+.summary { color: #a6adc8; margin-bottom: 2em; }
+.game { background: #313244; border-radius: 8px; padding: 1em; margin-bottom: 1.5em; }
+.game.excluded { opacity: 0.5; }
+.game h2 { margin: 0 0 0.2em; font-size: 1.2em; }
+.slug { color: #a6adc8; font-size: 0.9em; margin-bottom: 0.8em; }
+.assets { display: flex; gap: 1em; align-items: flex-start; flex-wrap: wrap; }
+figure { margin: 0; text-align: center; }
+figcaption { font-size: 0.8em; color: #a6adc8; margin-top: 0.3em; }
+img { max-height: 180px; max-width: 360px; border-radius: 4px; background: #45475a; }
+.missing { display: flex; align-items: center; justify-content: center; width: 120px;
+  height: 180px; border: 2px dashed #f38ba8; border-radius: 4px; color: #f38ba8; }
+";
+
+/// Write the gallery for `games` to `path`, covering the selected asset types.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be written.
+pub fn write_html_gallery(
+    launcher: &dyn LauncherBackend,
+    games: &[Game],
+    assets: &HashSet<AssetType>,
+    config: &Config,
+    path: &Path,
+) -> Result<()> {
+    let assets: Vec<AssetType> = AssetType::all()
+        .iter()
+        .copied()
+        .filter(|a| assets.contains(a))
+        .collect();
+
+    let mut cards = String::new();
+    let mut present = vec![0usize; assets.len()];
+    for game in games {
+        let excluded = config.excludes(game);
+        let _ = writeln!(
+            cards,
+            "<section class=\"game{}\">\n<h2>{}</h2>\n<div class=\"slug\">{}{}</div>\n<div class=\"assets\">",
+            if excluded { " excluded" } else { "" },
+            escape(&game.name),
+            escape(&game.slug),
+            if excluded { " — excluded" } else { "" },
+        );
+        for (i, &asset) in assets.iter().enumerate() {
+            let bytes = existing_asset_path(launcher, asset, &game.slug).and_then(|p| std::fs::read(p).ok());
+            let figure = match bytes {
+                Some(bytes) => {
+                    present[i] += 1;
+                    format!(
+                        "<img src=\"data:{};base64,{}\" alt=\"{}\">",
+                        sniff_mime(&bytes),
+                        STANDARD.encode(&bytes),
+                        asset.display_name(),
+                    )
+                }
+                None => "<div class=\"missing\">missing</div>".to_owned(),
+            };
+            let _ = writeln!(
+                cards,
+                "<figure>{figure}<figcaption>{}</figcaption></figure>",
+                asset.display_name()
+            );
+        }
+        cards.push_str("</div>\n</section>\n");
+    }
+
+    let summary = assets
+        .iter()
+        .zip(&present)
+        .map(|(asset, count)| format!("{}: {count}/{}", asset.display_name(), games.len()))
+        .collect::<Vec<_>>()
+        .join(" · ");
+
+    let html = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Lutris art gallery</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>Lutris art gallery</h1>\n<div class=\"summary\">{} games — {summary}</div>\n\
+         {cards}</body>\n</html>\n",
+        games.len()
+    );
+
+    std::fs::write(path, html)
+        .wrap_err_with(|| format!("Failed to write gallery to {}", path.display()))
+}
+
+/// Image MIME type from the file's magic bytes; Lutris stores PNGs with `.jpg` names too.
+fn sniff_mime(bytes: &[u8]) -> &'static str {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => "image/png",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        [b'G', b'I', b'F', ..] => "image/gif",
+        _ => "image/jpeg",
+    }
+}
+
+/// Escape text for inclusion in HTML.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
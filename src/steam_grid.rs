@@ -0,0 +1,257 @@
+/// Copying art into Steam's grid folder, for Lutris games added to Steam as non-Steam
+/// shortcuts.
+///
+/// Steam keeps each user's shortcuts in a binary VDF file
+/// (`userdata/<user>/config/shortcuts.vdf`) and their custom art next to it in `grid/`,
+/// named after the shortcut's app ID: `<appid>p` for the portrait grid, `<appid>` for the
+/// wide one, and `<appid>_hero` / `<appid>_logo`.
+use std::path::{Path, PathBuf};
+
+use color_eyre::eyre::{Context, Result, eyre};
+
+use crate::api::models::AssetType;
+use crate::db::Game;
+use crate::download;
+use crate::launcher::LauncherBackend;
+
+/// Where Steam is installed: `steam_dir` from the config, or the first of the usual
+/// native and Flatpak locations that exists.
+pub fn steam_dir(configured: Option<&Path>) -> Option<PathBuf> {
+    if let Some(dir) = configured {
+        return Some(dir.to_owned());
+    }
+    let home = dirs::home_dir()?;
+    [
+        dirs::data_dir().map(|d| d.join("Steam")),
+        Some(home.join(".steam/steam")),
+        Some(home.join(".var/app/com.valvesoftware.Steam/.local/share/Steam")),
+    ]
+    .into_iter()
+    .flatten()
+    .find(|dir| dir.join("userdata").is_dir())
+}
+
+/// Copy the saved grids, banners, heroes, and logos of `games` into the grid folder of
+/// every Steam user with a shortcut for them. Files already there and up to date are
+/// left alone. Returns how many images were copied.
+///
+/// # Errors
+///
+/// Returns an error if Steam can't be found, or a file can't be copied.
+pub fn export(launcher: &dyn LauncherBackend, games: &[Game], configured: Option<&Path>) -> Result<usize> {
+    let steam = steam_dir(configured).ok_or_else(|| eyre!("Steam installation not found"))?;
+    let users = std::fs::read_dir(steam.join("userdata"))
+        .wrap_err_with(|| format!("Failed to read {}", steam.join("userdata").display()))?;
+
+    let mut copied = 0;
+    for user in users.filter_map(std::result::Result::ok) {
+        let config = user.path().join("config");
+        let Ok(vdf) = std::fs::read(config.join("shortcuts.vdf")) else {
+            continue;
+        };
+        let shortcuts = parse_shortcuts(&vdf);
+        for game in games {
+            for shortcut in shortcuts.iter().filter(|s| s.launches(game)) {
+                copied += export_game(launcher, game, shortcut.appid, &config.join("grid"))?;
+            }
+        }
+    }
+    Ok(copied)
+}
+
+/// Copy one game's art into a grid folder under a shortcut's app ID.
+fn export_game(launcher: &dyn LauncherBackend, game: &Game, appid: u32, grid: &Path) -> Result<usize> {
+    let mut copied = 0;
+    for asset in [AssetType::Grid, AssetType::Banner, AssetType::Hero, AssetType::Logo] {
+        let Some(source) = download::existing_asset_path(launcher, asset, &game.slug) else {
+            continue;
+        };
+        // Steam reads animated PNGs from plain .png files
+        let ext = match source.extension().and_then(|e| e.to_str()) {
+            Some("apng") | None => "png",
+            Some(ext) => ext,
+        };
+        let stem = grid_name(asset, appid);
+        let target = grid.join(format!("{stem}.{ext}"));
+        if is_up_to_date(&source, &target) {
+            continue;
+        }
+        std::fs::create_dir_all(grid).wrap_err("Failed to create Steam grid folder")?;
+        // Art saved in another format earlier would take precedence
+        for other in ["png", "jpg", "jpeg", "webp"].iter().filter(|&&e| e != ext) {
+            let _ = std::fs::remove_file(grid.join(format!("{stem}.{other}")));
+        }
+        std::fs::copy(&source, &target)
+            .wrap_err_with(|| format!("Failed to copy art to {}", target.display()))?;
+        copied += 1;
+    }
+    Ok(copied)
+}
+
+/// Whether `target` is a copy of `source` at least as new as it.
+fn is_up_to_date(source: &Path, target: &Path) -> bool {
+    let (Ok(source), Ok(target)) = (std::fs::metadata(source), std::fs::metadata(target)) else {
+        return false;
+    };
+    source.len() == target.len()
+        && matches!((source.modified(), target.modified()), (Ok(s), Ok(t)) if t >= s)
+}
+
+/// File name (without extension) Steam looks for an asset of a shortcut under.
+fn grid_name(asset: AssetType, appid: u32) -> String {
+    match asset {
+        AssetType::Grid => format!("{appid}p"),
+        AssetType::Hero => format!("{appid}_hero"),
+        AssetType::Logo => format!("{appid}_logo"),
+        AssetType::Icon => format!("{appid}_icon"),
+        AssetType::Banner => appid.to_string(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// shortcuts.vdf
+// ---------------------------------------------------------------------------
+
+/// A non-Steam shortcut.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct Shortcut {
+    appid: u32,
+    name: String,
+    exe: String,
+    launch_options: String,
+}
+
+impl Shortcut {
+    /// Whether the shortcut starts `game`: through Lutris, or failing that, by name.
+    fn launches(&self, game: &Game) -> bool {
+        let command = format!("{} {}", self.exe, self.launch_options);
+        let by_id = format!("lutris:rungameid/{}", game.id);
+        let by_slug = format!("lutris:rungame/{}", game.slug);
+        let runs = |uri: &str| {
+            command
+                .split(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+                .any(|word| word == uri)
+        };
+        if command.contains("lutris:") {
+            return runs(&by_id) || runs(&by_slug);
+        }
+        self.name.eq_ignore_ascii_case(&game.name)
+    }
+}
+
+/// A value in a binary VDF file.
+enum Value {
+    Map(Vec<(String, Value)>),
+    Str(String),
+    Int(u32),
+    Other,
+}
+
+/// Read the shortcuts from a `shortcuts.vdf` file; a malformed file has none.
+fn parse_shortcuts(bytes: &[u8]) -> Vec<Shortcut> {
+    let mut reader = bytes;
+    let Some(root) = parse_map(&mut reader) else {
+        return Vec::new();
+    };
+    let Some((_, Value::Map(entries))) = root.into_iter().find(|(k, _)| k.eq_ignore_ascii_case("shortcuts")) else {
+        return Vec::new();
+    };
+    entries
+        .into_iter()
+        .filter_map(|(_, entry)| match entry {
+            Value::Map(fields) => Some(shortcut(fields)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// A shortcut from its fields. Shortcuts made before Steam stored app IDs get the ID
+/// Steam derives from the executable and name.
+fn shortcut(fields: Vec<(String, Value)>) -> Shortcut {
+    let mut shortcut = Shortcut::default();
+    for (key, value) in fields {
+        match (key.to_ascii_lowercase().as_str(), value) {
+            ("appid", Value::Int(id)) => shortcut.appid = id,
+            ("appname", Value::Str(s)) => shortcut.name = s,
+            ("exe", Value::Str(s)) => shortcut.exe = s,
+            ("launchoptions", Value::Str(s)) => shortcut.launch_options = s,
+            _ => {}
+        }
+    }
+    if shortcut.appid == 0 {
+        let mut crc = flate2::Crc::new();
+        crc.update(shortcut.exe.as_bytes());
+        crc.update(shortcut.name.as_bytes());
+        shortcut.appid = crc.sum() | 0x8000_0000;
+    }
+    shortcut
+}
+
+/// Parse the fields of a map up to its end marker.
+fn parse_map(reader: &mut &[u8]) -> Option<Vec<(String, Value)>> {
+    let mut fields = Vec::new();
+    loop {
+        let (&kind, rest) = reader.split_first()?;
+        *reader = rest;
+        if kind == 0x08 {
+            return Some(fields);
+        }
+        let key = parse_str(reader)?;
+        let value = match kind {
+            0x00 => Value::Map(parse_map(reader)?),
+            0x01 => Value::Str(parse_str(reader)?),
+            0x02 => Value::Int(u32::from_le_bytes(take(reader, 4)?.try_into().ok()?)),
+            0x07 => {
+                take(reader, 8)?;
+                Value::Other
+            }
+            _ => return None,
+        };
+        fields.push((key, value));
+    }
+}
+
+/// Parse a NUL-terminated string.
+fn parse_str(reader: &mut &[u8]) -> Option<String> {
+    let end = reader.iter().position(|&b| b == 0)?;
+    let s = String::from_utf8_lossy(&reader[..end]).into_owned();
+    *reader = &reader[end + 1..];
+    Some(s)
+}
+
+/// Take the next `n` bytes.
+fn take<'a>(reader: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if reader.len() < n {
+        return None;
+    }
+    let (bytes, rest) = reader.split_at(n);
+    *reader = rest;
+    Some(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::fixture;
+
+    #[test]
+    fn shortcuts_are_parsed_and_matched() {
+        let mut vdf = b"\x00shortcuts\x00\x000\x00".to_vec();
+        vdf.extend(b"\x02appid\x00\x39\x30\x00\x80");
+        vdf.extend(b"\x01AppName\x00Hollow Knight\x00");
+        vdf.extend(b"\x01Exe\x00\"/usr/bin/lutris\"\x00");
+        vdf.extend(b"\x01LaunchOptions\x00lutris:rungameid/42\x00");
+        vdf.extend(b"\x00tags\x00\x08");
+        vdf.extend(b"\x08\x08\x08");
+
+        let shortcuts = parse_shortcuts(&vdf);
+        assert_eq!(shortcuts.len(), 1);
+        assert_eq!(shortcuts[0].appid, 0x8000_3039);
+        assert_eq!(grid_name(AssetType::Grid, shortcuts[0].appid), "2147495993p");
+
+        let game = |id, slug| Game { id, ..fixture::game(slug, "Hollow Knight") };
+        assert!(shortcuts[0].launches(&game(42, "hollow-knight")));
+        // Same name, but the shortcut launches another Lutris game
+        assert!(!shortcuts[0].launches(&game(420, "hollow-knight-2")));
+    }
+}
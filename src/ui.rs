@@ -10,9 +10,10 @@ use ratatui::{
     widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Wrap},
 };
 
-use crate::api::models::AssetType;
-use crate::app::{App, AppScreen, LogLevel};
+use crate::api::models::{AssetType, RunCounts};
+use crate::app::{App, AppScreen, LogLevel, SortOrder, Transfer};
 use crate::download;
+use crate::history;
 
 // ---------------------------------------------------------------------------
 // Colors
@@ -142,7 +143,7 @@ fn render_asset_selection(frame: &mut Frame, app: &App) {
     let chunks = Layout::vertical([
         Constraint::Length(2),  // Instructions
         Constraint::Length(1),  // Spacer
-        Constraint::Min(6),    // List
+        Constraint::Min(7),    // List
         Constraint::Length(2),  // Footer
     ])
     .split(inner);
@@ -226,7 +227,20 @@ fn render_main_view(frame: &mut Frame, app: &App) {
 }
 
 fn render_game_list(frame: &mut Frame, app: &App, area: Rect) {
-    let title = format!(" Games ({} installed) ", app.games.len());
+    let excluded = app.games.len() - app.active_games().count();
+    let installed = app.games.iter().filter(|e| e.game.installed).count();
+    let mut counts = vec![format!("{installed} installed")];
+    if app.games.len() > installed {
+        counts.push(format!("{} not installed", app.games.len() - installed));
+    }
+    if excluded > 0 {
+        counts.push(format!("{excluded} excluded"));
+    }
+    if !app.config.categories.is_empty() {
+        let names: Vec<&str> = app.config.categories.iter().map(String::as_str).collect();
+        counts.push(format!("in {}", names.join(" / ")));
+    }
+    let title = format!(" Games ({}, by {}) ", counts.join(", "), app.sort_order.label());
     let block = Block::default()
         .title(title)
         .borders(Borders::ALL)
@@ -236,6 +250,13 @@ fn render_game_list(frame: &mut Frame, app: &App, area: Rect) {
         .games
         .iter()
         .map(|entry| {
+            if app.is_excluded(entry) {
+                let line = Line::from(vec![
+                    Span::raw(" ⊘ "),
+                    Span::raw(&entry.game.name),
+                ]);
+                return ListItem::new(line).style(Style::default().fg(MUTED_COLOR));
+            }
             let icon = entry.overall_icon(&app.selected_assets);
             let icon_color = match icon {
                 "✓" => SUCCESS_COLOR,
@@ -244,11 +265,17 @@ fn render_game_list(frame: &mut Frame, app: &App, area: Rect) {
                 "─" => MUTED_COLOR,
                 _ => INFO_COLOR,
             };
-            let line = Line::from(vec![
+            let mut spans = vec![
                 Span::styled(format!(" {icon} "), Style::default().fg(icon_color)),
                 Span::raw(&entry.game.name),
-            ]);
-            ListItem::new(line)
+            ];
+            if !entry.game.installed {
+                spans.push(Span::styled(" (not installed)", Style::default().fg(MUTED_COLOR)));
+            }
+            if let (SortOrder::MostPlayed, Some(hours)) = (app.sort_order, entry.game.playtime) {
+                spans.push(Span::styled(format!(" {hours:.1} h"), Style::default().fg(MUTED_COLOR)));
+            }
+            ListItem::new(Line::from(spans))
         })
         .collect();
 
@@ -261,7 +288,29 @@ fn render_game_list(frame: &mut Frame, app: &App, area: Rect) {
         )
         .highlight_symbol("▸ ");
 
-    frame.render_stateful_widget(list, area, &mut app.list_state.clone());
+    let mut state = app.list_state.clone();
+    frame.render_stateful_widget(list, area, &mut state);
+    render_more_indicators(frame, area, state.offset(), app.games.len());
+}
+
+/// Overlay "▲ more" / "▼ more" on a bordered list's frame when entries are scrolled out
+/// of view above or below.
+fn render_more_indicators(frame: &mut Frame, area: Rect, offset: usize, len: usize) {
+    if area.height < 3 || area.width < 12 {
+        return;
+    }
+    let visible = usize::from(area.height - 2);
+    let style = Style::default().fg(MUTED_COLOR);
+    let row = |y| Rect::new(area.x + 1, y, area.width - 2, 1);
+
+    if offset > 0 {
+        let more = Line::styled("▲ more ", style).right_aligned();
+        frame.render_widget(Paragraph::new(more), row(area.y));
+    }
+    if offset + visible < len {
+        let more = Line::styled("▼ more ", style).right_aligned();
+        frame.render_widget(Paragraph::new(more), row(area.y + area.height - 1));
+    }
 }
 
 fn render_status_panel(frame: &mut Frame, app: &App, area: Rect) {
@@ -273,10 +322,12 @@ fn render_status_panel(frame: &mut Frame, app: &App, area: Rect) {
     let inner = block.inner(area);
     frame.render_widget(block, area);
 
+    let file_height = if app.transfer.is_some() { 3 } else { 0 };
     let chunks = Layout::vertical([
         Constraint::Length(1), // Mode
         Constraint::Length(1), // Spacer
         Constraint::Length(3), // Progress gauge
+        Constraint::Length(file_height), // Current file gauge
         Constraint::Length(1), // Spacer
         Constraint::Min(2),   // Current info
     ])
@@ -299,28 +350,40 @@ fn render_status_panel(frame: &mut Frame, app: &App, area: Rect) {
             } else {
                 *current as f64 / *total as f64
             };
-            let label = format!("{current} / {total}");
+            let label = match app.eta_secs() {
+                _ if app.paused_since.is_some() => format!("{current} / {total} — paused"),
+                Some(eta) => format!("{current} / {total} — ~{} left", history::format_eta(eta)),
+                None => format!("{current} / {total}"),
+            };
             let gauge = Gauge::default()
                 .block(Block::default().title(" Progress ").borders(Borders::ALL).border_style(Style::default().fg(BORDER_COLOR)))
                 .gauge_style(Style::default().fg(SUCCESS_COLOR).bg(Color::DarkGray))
                 .ratio(progress.min(1.0))
                 .label(label);
             frame.render_widget(gauge, chunks[2]);
+            if let Some(ref transfer) = app.transfer {
+                render_transfer_gauge(frame, transfer, chunks[3]);
+            }
         }
         AppScreen::GameList => {
             let existing: usize = app
-                .games
-                .iter()
+                .active_games()
                 .filter(|e| {
                     app.selected_assets
                         .iter()
-                        .all(|a| download::asset_exists(*a, &e.game.slug))
+                        .all(|a| download::asset_exists(&*app.launcher, *a, &e.game.slug))
                 })
                 .count();
-            let info = Paragraph::new(format!(
+            let mut lines = vec![Line::from(format!(
                 " {existing} games already have all selected art"
-            ))
-            .style(Style::default().fg(MUTED_COLOR));
+            ))];
+            if let Some(secs) = app.estimate_run_secs() {
+                lines.push(Line::from(format!(
+                    " Estimated time: ~{} (from past runs)",
+                    history::format_eta(secs)
+                )));
+            }
+            let info = Paragraph::new(lines).style(Style::default().fg(MUTED_COLOR));
             frame.render_widget(info, chunks[2]);
         }
         _ => {}
@@ -347,12 +410,40 @@ fn render_status_panel(frame: &mut Frame, app: &App, area: Rect) {
                     Style::default().fg(MUTED_COLOR),
                 )));
             }
+            lines.push(Line::from(Span::styled(
+                format!(
+                    " Art on disk: {}",
+                    download::format_size(download::art_size_on_disk(&*app.launcher, &entry.game.slug))
+                ),
+                Style::default().fg(MUTED_COLOR),
+            )));
             let info = Paragraph::new(lines);
-            frame.render_widget(info, chunks[4]);
+            frame.render_widget(info, chunks[5]);
         }
     }
 }
 
+/// Byte progress of the file being downloaded. Without a known size the bar stays
+/// empty and only the byte count moves.
+fn render_transfer_gauge(frame: &mut Frame, transfer: &Transfer, area: Rect) {
+    let received = download::format_size(transfer.received);
+    let (ratio, label) = match transfer.total {
+        Some(total) if total > 0 => {
+            #[allow(clippy::cast_precision_loss)]
+            let ratio = transfer.received as f64 / total as f64;
+            (ratio.min(1.0), format!("{received} / {}", download::format_size(total)))
+        }
+        _ => (0.0, received),
+    };
+    let title = format!(" {} — {} ", transfer.game, transfer.asset);
+    let gauge = Gauge::default()
+        .block(Block::default().title(title).borders(Borders::ALL).border_style(Style::default().fg(BORDER_COLOR)))
+        .gauge_style(Style::default().fg(INFO_COLOR).bg(Color::DarkGray))
+        .ratio(ratio)
+        .label(label);
+    frame.render_widget(gauge, area);
+}
+
 fn render_log_panel(frame: &mut Frame, app: &App, area: Rect) {
     let block = Block::default()
         .title(" Log ")
@@ -360,9 +451,15 @@ fn render_log_panel(frame: &mut Frame, app: &App, area: Rect) {
         .border_style(Style::default().fg(BORDER_COLOR));
 
     // Show last N messages that fit
-    let inner_height = area.height.saturating_sub(2) as usize;
-    let start = app.log.len().saturating_sub(inner_height);
-    let lines: Vec<Line> = app.log[start..]
+    let lines = log_tail(app, area.height.saturating_sub(2) as usize);
+    let log = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+    frame.render_widget(log, area);
+}
+
+/// The last `count` log messages, styled by level.
+fn log_tail(app: &App, count: usize) -> Vec<Line<'_>> {
+    let start = app.log.len().saturating_sub(count);
+    app.log[start..]
         .iter()
         .map(|(level, msg)| {
             let (prefix, color) = match level {
@@ -376,16 +473,14 @@ fn render_log_panel(frame: &mut Frame, app: &App, area: Rect) {
                 Span::raw(msg),
             ])
         })
-        .collect();
-
-    let log = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
-    frame.render_widget(log, area);
+        .collect()
 }
 
 fn render_footer(frame: &mut Frame, app: &App, area: Rect) {
     let text = match &app.screen {
-        AppScreen::GameList => " q:Quit  Enter:Start All  ↑↓:Navigate  ?:Help",
-        AppScreen::Downloading { .. } => " q:Quit  ?:Help  (downloading...)",
+        AppScreen::GameList => " q:Quit  Enter:Start All  ↑↓:Navigate  s:Sort  x:Exclude  u:Restore  ?:Help",
+        AppScreen::Downloading { .. } if app.paused_since.is_some() => " q:Quit  p:Resume  c:Cancel  ?:Help  (paused)",
+        AppScreen::Downloading { .. } => " q:Quit  p:Pause  c:Cancel  ?:Help  (downloading...)",
         _ => " q:Quit  ?:Help",
     };
     let footer = Paragraph::new(text)
@@ -403,18 +498,28 @@ fn render_done_screen(frame: &mut Frame, app: &App) {
         downloaded,
         skipped,
         failed,
+        ref by_asset,
         elapsed_secs,
+        bytes_saved,
+        api,
+        stopped,
     } = app.screen
     else {
         return;
     };
+    let breakdown = asset_breakdown(by_asset);
 
     let area = frame.area();
+    let (title, heading, color) = if stopped {
+        (" Lutris Art Fetcher — Stopped ", "Downloads stopped — re-run with --resume", ERROR_COLOR)
+    } else {
+        (" Lutris Art Fetcher — Complete! ", "All downloads complete!", SUCCESS_COLOR)
+    };
     let block = Block::default()
-        .title(" Lutris Art Fetcher — Complete! ")
+        .title(title)
         .title_alignment(Alignment::Center)
         .borders(Borders::ALL)
-        .border_style(Style::default().fg(SUCCESS_COLOR));
+        .border_style(Style::default().fg(color));
     frame.render_widget(block, area);
 
     let inner = centered_rect(50, 50, area);
@@ -422,23 +527,19 @@ fn render_done_screen(frame: &mut Frame, app: &App) {
     let chunks = Layout::vertical([
         Constraint::Length(2),  // Header
         Constraint::Length(1),  // Spacer
-        Constraint::Length(6),  // Stats
+        Constraint::Length(10 + u16::try_from(breakdown.len()).unwrap_or(0)), // Stats
         Constraint::Length(1),  // Spacer
         Constraint::Min(6),     // Log tail
         Constraint::Length(1),  // Footer
     ])
     .split(inner);
 
-    let header = Paragraph::new("All downloads complete!")
+    let header = Paragraph::new(heading)
         .alignment(Alignment::Center)
-        .style(
-            Style::default()
-                .fg(SUCCESS_COLOR)
-                .add_modifier(Modifier::BOLD),
-        );
+        .style(Style::default().fg(color).add_modifier(Modifier::BOLD));
     frame.render_widget(header, chunks[0]);
 
-    let stats = Paragraph::new(vec![
+    let mut lines = vec![
         Line::from(Span::styled(
             format!("  ✓ Downloaded: {downloaded}"),
             Style::default().fg(SUCCESS_COLOR),
@@ -451,38 +552,32 @@ fn render_done_screen(frame: &mut Frame, app: &App) {
             format!("  ✗ Failed:     {failed}"),
             Style::default().fg(if failed > 0 { ERROR_COLOR } else { MUTED_COLOR }),
         )),
+    ];
+    lines.extend(breakdown);
+    lines.extend([
         Line::from(""),
         Line::from(Span::styled(
             format!("  ⏱ Time: {elapsed_secs}s"),
             Style::default().fg(INFO_COLOR),
         )),
-    ])
-    .block(
-        Block::default()
-            .title(" Summary ")
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(BORDER_COLOR)),
-    );
+        Line::from(Span::styled(
+            format!("  ⇣ Compression saved: {}", download::format_size(bytes_saved)),
+            Style::default().fg(MUTED_COLOR),
+        )),
+        Line::from(Span::styled(format!("  ⇄ {}", api.summary()), Style::default().fg(MUTED_COLOR))),
+    ]);
+    let stats = Paragraph::new(lines)
+        .wrap(Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(" Summary ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(BORDER_COLOR)),
+        );
     frame.render_widget(stats, chunks[2]);
 
     // Show last few log lines
-    let log_height = chunks[4].height.saturating_sub(2) as usize;
-    let start = app.log.len().saturating_sub(log_height);
-    let lines: Vec<Line> = app.log[start..]
-        .iter()
-        .map(|(level, msg)| {
-            let (prefix, color) = match level {
-                LogLevel::Info => ("[INFO]", INFO_COLOR),
-                LogLevel::Ok => ("[ OK ]", SUCCESS_COLOR),
-                LogLevel::Warn => ("[WARN]", HIGHLIGHT_COLOR),
-                LogLevel::Error => ("[ ERR]", ERROR_COLOR),
-            };
-            Line::from(vec![
-                Span::styled(format!(" {prefix} "), Style::default().fg(color)),
-                Span::raw(msg),
-            ])
-        })
-        .collect();
+    let lines = log_tail(app, chunks[4].height.saturating_sub(2) as usize);
     let log = Paragraph::new(lines)
         .block(
             Block::default()
@@ -493,7 +588,17 @@ fn render_done_screen(frame: &mut Frame, app: &App) {
         .wrap(Wrap { trim: true });
     frame.render_widget(log, chunks[4]);
 
-    let footer = Paragraph::new(" Restart Lutris to see changes. Press q or Enter to exit.")
+    let restart = app
+        .launcher
+        .shown_in()
+        .map(|app| format!("Restart {app} to see changes. "))
+        .unwrap_or_default();
+    let footer_text = if failed > 0 {
+        format!(" {restart}Press r to retry the failed assets, q or Enter to exit.")
+    } else {
+        format!(" {restart}Press q or Enter to exit.")
+    };
+    let footer = Paragraph::new(footer_text)
         .alignment(Alignment::Center)
         .style(Style::default().fg(MUTED_COLOR));
     frame.render_widget(footer, chunks[5]);
@@ -503,6 +608,29 @@ fn render_done_screen(frame: &mut Frame, app: &App) {
 // Help Popup
 // ---------------------------------------------------------------------------
 
+/// The summary's counts for each asset type — none when the run fetched just one.
+fn asset_breakdown(counts: &RunCounts) -> Vec<Line<'static>> {
+    if counts.by_asset.len() < 2 {
+        return Vec::new();
+    }
+    counts
+        .by_asset
+        .iter()
+        .map(|(asset, counts)| {
+            Line::from(vec![
+                Span::styled(
+                    format!("    {:<7}{} ✓  {} ─  ", asset.display_name(), counts.downloaded, counts.skipped),
+                    Style::default().fg(MUTED_COLOR),
+                ),
+                Span::styled(
+                    format!("{} ✗", counts.failed),
+                    Style::default().fg(if counts.failed > 0 { ERROR_COLOR } else { MUTED_COLOR }),
+                ),
+            ])
+        })
+        .collect()
+}
+
 fn render_help_popup(frame: &mut Frame) {
     let area = centered_rect(60, 60, frame.area());
     frame.render_widget(Clear, area);
@@ -520,9 +648,17 @@ fn render_help_popup(frame: &mut Frame) {
         Line::from("  Home/End   Jump to first/last"),
         Line::from(""),
         Line::from(" Actions"),
-        Line::from("  Enter      Confirm / Start downloads"),
+        Line::from("  Enter      Confirm / Start downloads (highlighted game first)"),
         Line::from("  Space      Toggle selection"),
         Line::from("  a          Toggle all (asset selection)"),
+        Line::from("  s          Cycle game list sort order"),
+        Line::from("  x          Exclude / include highlighted game"),
+        Line::from("  i          Show / hide games that aren't installed"),
+        Line::from("  c          Cycle category filter (game list)"),
+        Line::from("  u / U      Restore replaced art (highlighted game / last run)"),
+        Line::from("  p          Pause / resume downloads"),
+        Line::from("  c          Cancel running downloads (downloading)"),
+        Line::from("  r          Retry failed assets (summary screen)"),
         Line::from(""),
         Line::from(" General"),
         Line::from("  ?          Toggle this help"),